@@ -1,5 +1,8 @@
 BEGIN TRANSACTION;
 
+DROP TABLE IF EXISTS "combat_rating";
+DROP TABLE IF EXISTS "personal_best";
+DROP TABLE IF EXISTS "milestone";
 DROP TABLE IF EXISTS "modes";
 DROP TABLE IF EXISTS "team_result";
 DROP TABLE IF EXISTS "weapon_result";
@@ -13,13 +16,16 @@ DROP TABLE IF EXISTS "member";
 
 DROP INDEX IF EXISTS "modes_activity_index";
 DROP INDEX IF EXISTS "character_activity_stats_char_index";
+DROP INDEX IF EXISTS "character_activity_stats_activity_index";
 DROP INDEX IF EXISTS "activity_period_index";
+DROP INDEX IF EXISTS "milestone_member_index";
+DROP INDEX IF EXISTS "combat_rating_activity_index";
 
 CREATE TABLE IF NOT EXISTS "main"."version" (
     "version"   INTEGER NOT NULL UNIQUE
 );
 
-INSERT INTO "main"."version"("version") VALUES (6);
+INSERT INTO "main"."version"("version") VALUES (11);
 
 CREATE TABLE IF NOT EXISTS "main"."activity_queue" (
     "id"	INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT UNIQUE,
@@ -143,6 +149,7 @@ CREATE TABLE IF NOT EXISTS "main"."character_activity_stats" (
     "weapon_kills_super"        INTEGER NOT NULL,
     "all_medals_earned"         INTEGER NOT NULL,
     "light_level"               INTEGER NOT NULL,
+    "fireteam_id"               INTEGER NOT NULL DEFAULT 0,
 
     UNIQUE("activity", "character"),
 
@@ -155,8 +162,63 @@ CREATE TABLE IF NOT EXISTS "main"."character_activity_stats" (
         ON DELETE CASCADE
 );
 
+CREATE TABLE IF NOT EXISTS "main"."milestone" (
+    "id"	INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT UNIQUE,
+    "member"                    INTEGER NOT NULL,
+    "character_activity_stats"  INTEGER NOT NULL,
+    "type"                      INTEGER NOT NULL,
+    "value"                     REAL NOT NULL,
+    "previous_value"            REAL NOT NULL,
+    "medal_id"                  TEXT NOT NULL DEFAULT '',
+
+    UNIQUE("character_activity_stats", "type", "medal_id"),
+
+    FOREIGN KEY ("member")
+        REFERENCES "member" ("id")
+        ON DELETE CASCADE,
+
+    FOREIGN KEY ("character_activity_stats")
+        REFERENCES "character_activity_stats" ("id")
+        ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "main"."personal_best" (
+    "id"	    INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT UNIQUE,
+    "member"    INTEGER NOT NULL,
+    "type"      INTEGER NOT NULL,
+    "value"     REAL NOT NULL,
+
+    UNIQUE("member", "type"),
+
+    FOREIGN KEY ("member")
+        REFERENCES "member" ("id")
+        ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS "main"."combat_rating" (
+    "id"	        INTEGER NOT NULL PRIMARY KEY AUTOINCREMENT UNIQUE,
+    "character"     INTEGER NOT NULL,
+    "activity"      INTEGER NOT NULL,
+    "mode"          INTEGER NOT NULL,
+    "rating"        REAL NOT NULL,
+    "synced_at"     TEXT NOT NULL,
+
+    UNIQUE("character", "activity", "mode"),
+
+    FOREIGN KEY ("character")
+        REFERENCES "character" ("id")
+        ON DELETE CASCADE,
+
+    FOREIGN KEY ("activity")
+        REFERENCES "activity" ("id")
+        ON DELETE CASCADE
+);
+
 CREATE INDEX modes_activity_index ON modes (activity);
 CREATE INDEX character_activity_stats_char_index ON character_activity_stats (character);
+CREATE INDEX character_activity_stats_activity_index ON character_activity_stats (activity);
 CREATE INDEX activity_period_index ON activity (period);
+CREATE INDEX milestone_member_index ON milestone (member);
+CREATE INDEX combat_rating_activity_index ON combat_rating (activity);
 
 COMMIT;
\ No newline at end of file
@@ -20,19 +20,22 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::PathBuf;
 use std::str::FromStr;
 
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, Duration, Utc};
 
 use crate::{
     crucible::{CrucibleActivity, Team},
     enums::{
         completionreason::CompletionReason,
         itemtype::{ItemSubType, ItemType},
+        milestonetype::MilestoneType,
         moment::DateTimePeriod,
-        standing::Standing,
+        standing::{
+            Standing, DEFAULT_FFA_WIN_PLACEMENT, STANDING_UNKNOWN_MAGIC_NUMBER,
+        },
     },
     response::pgcr::DestinyPostGameCarnageReportEntry,
 };
@@ -44,7 +47,7 @@ use sqlx::{ConnectOptions, SqliteConnection};
 use crate::crucible::{
     ActivityDetail, CruciblePlayerActivityPerformance,
     CruciblePlayerPerformance, CrucibleStats, ExtendedCrucibleStats, Item,
-    Medal, MedalStat, Player, WeaponStat,
+    Medal, MedalStat, Milestone, Player, WeaponStat, NON_MEDAL_IDS,
 };
 use crate::enums::character::{CharacterClass, CharacterClassSelection};
 use crate::enums::medaltier::MedalTier;
@@ -68,13 +71,24 @@ const STORE_DB_SCHEMA: &str = include_str!("../actitvity_store_schema.sql");
 //numer of simultaneous requests we make to server when retrieving activity history
 const PGCR_REQUEST_CHUNK_AMOUNT: usize = 24;
 
-const DB_SCHEMA_VERSION: i32 = 6;
+const DB_SCHEMA_VERSION: i32 = 11;
 const NO_TEAMS_INDEX: i32 = 253;
 
+//number of activities retrieved at a time when checking for an existing win
+//streak
+const STREAK_PAGE_SIZE: u32 = 20;
+
+//combat ratings are only snapshot for activities which ended within this many
+//hours of the sync. Older activities would otherwise be stored with the
+//player's current rating, and not the rating when the game was played
+const COMBAT_RATING_SNAPSHOT_WINDOW_HOURS: i64 = 48;
+
 pub struct ActivityStoreInterface {
     verbose: bool,
     db: SqliteConnection,
     path: String,
+    sync_combat_ratings: bool,
+    ffa_win_placement: u32,
 }
 
 impl ActivityStoreInterface {
@@ -82,6 +96,43 @@ impl ActivityStoreInterface {
         self.path.clone()
     }
 
+    /// Sets whether the combat rating of each player in a lobby should be
+    /// retrieved and stored when new activities are synced. Ratings are only
+    /// stored for activities played within the last 48 hours, and each rating
+    /// records when it was synced. This makes an additional api call per
+    /// player, so syncs will take longer.
+    pub fn set_sync_combat_ratings(&mut self, sync_combat_ratings: bool) {
+        self.sync_combat_ratings = sync_combat_ratings;
+    }
+
+    /// Sets the lowest placement in free for all modes (such as Rumble) which
+    /// is treated as a victory. Defaults to 3.
+    pub fn set_ffa_win_placement(&mut self, ffa_win_placement: u32) {
+        self.ffa_win_placement = ffa_win_placement;
+    }
+
+    /// Returns the standing and, for free for all modes, the placement
+    /// (starting at 1) for the standing value stored for an activity.
+    fn parse_standing(
+        &self,
+        value: u32,
+        mode: &Mode,
+    ) -> (Standing, Option<u32>) {
+        let standing = Standing::from_mode(value, mode, self.ffa_win_placement);
+
+        //for free for all modes, the api returns the placement (starting at 0)
+        //as the standing
+        let placement = if mode.is_free_for_all()
+            && value != STANDING_UNKNOWN_MAGIC_NUMBER
+        {
+            Some(value + 1)
+        } else {
+            None
+        };
+
+        (standing, placement)
+    }
+
     pub async fn init_with_path(
         store_dir: &PathBuf,
         verbose: bool,
@@ -121,7 +172,13 @@ impl ActivityStoreInterface {
             sqlx::query(STORE_DB_SCHEMA).execute(&mut db).await?;
         }
 
-        Ok(ActivityStoreInterface { db, verbose, path })
+        Ok(ActivityStoreInterface {
+            db,
+            verbose,
+            path,
+            sync_combat_ratings: false,
+            ffa_win_placement: DEFAULT_FFA_WIN_PLACEMENT,
+        })
     }
 
     /// TODO currently no way to sync old / delete characters. would be easy to
@@ -147,6 +204,11 @@ impl ActivityStoreInterface {
             .insert_member_id(&member_id, &platform, &display_name)
             .await?;
 
+        //used to determine which activities are inserted during this sync
+        //so we can check them for milestones
+        let last_stats_index =
+            self.get_last_character_activity_stats_index().await?;
+
         let mut total_synced = 0;
         let mut total_in_queue = 0;
 
@@ -187,12 +249,487 @@ impl ActivityStoreInterface {
                 - (a.total_synced + c.total_synced);
         }
 
+        let milestones = self
+            .update_milestones(member_row_id, last_stats_index)
+            .await?;
+
         Ok(SyncResult {
             total_synced,
             total_available: total_in_queue,
+            milestones,
         })
     }
 
+    /// Returns the index of the most recently inserted character activity
+    /// stats row, or 0 if there are none. Rows inserted after this is called
+    /// will have a greater index.
+    async fn get_last_character_activity_stats_index(
+        &mut self,
+    ) -> Result<i64, Error> {
+        let row = sqlx::query(
+            r#"
+            SELECT
+                max(id) as max_id
+            FROM
+                character_activity_stats
+        "#,
+        )
+        .fetch_one(&mut self.db)
+        .await?;
+
+        let max_id: Option<i64> = row.try_get_unchecked("max_id")?;
+
+        Ok(max_id.unwrap_or(0))
+    }
+
+    /// Retrieves the stored personal bests for the member, keyed by
+    /// milestone type id.
+    async fn retrieve_personal_bests(
+        &mut self,
+        member_row_id: i32,
+    ) -> Result<HashMap<u32, f32>, Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                type as milestone_type,
+                value
+            FROM
+                personal_best
+            WHERE
+                member = ?
+        "#,
+        )
+        .bind(member_row_id)
+        .fetch_all(&mut self.db)
+        .await?;
+
+        let mut out: HashMap<u32, f32> = HashMap::new();
+        for row in &rows {
+            let milestone_type: u32 =
+                row.try_get_unchecked("milestone_type")?;
+            let value: f32 = row.try_get_unchecked("value")?;
+            out.insert(milestone_type, value);
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the number of consecutive victories the member had at the end
+    /// of their history, up to and including the specified stats index.
+    async fn get_trailing_win_streak(
+        &mut self,
+        member_row_id: i32,
+        last_stats_index: i64,
+    ) -> Result<u32, Error> {
+        let mut streak: u32 = 0;
+        let mut offset: u32 = 0;
+
+        loop {
+            let rows = sqlx::query(
+                r#"
+                SELECT
+                    character_activity_stats.standing,
+                    activity.mode as activity_mode
+                FROM
+                    character_activity_stats
+                INNER JOIN
+                    activity ON character_activity_stats.activity = activity.id,
+                    character on character_activity_stats.character = character.id
+                WHERE
+                    character.member = ? AND
+                    character_activity_stats.id <= ? AND
+                    not exists (select 1 from modes where activity = activity.id and mode = ?)
+                ORDER BY
+                    activity.period DESC
+                LIMIT ? OFFSET ?
+            "#,
+            )
+            .bind(member_row_id)
+            .bind(last_stats_index)
+            .bind(Mode::PrivateMatchesAll.to_id().to_string())
+            .bind(STREAK_PAGE_SIZE)
+            .bind(offset)
+            .fetch_all(&mut self.db)
+            .await?;
+
+            for row in &rows {
+                let standing: u32 = row.try_get_unchecked("standing")?;
+                let activity_mode: u32 =
+                    row.try_get_unchecked("activity_mode")?;
+                let (standing, _placement) = self
+                    .parse_standing(standing, &Mode::from_id(activity_mode)?);
+
+                if standing != Standing::Victory {
+                    return Ok(streak);
+                }
+
+                streak += 1;
+            }
+
+            if rows.len() < STREAK_PAGE_SIZE as usize {
+                return Ok(streak);
+            }
+
+            offset += STREAK_PAGE_SIZE;
+        }
+    }
+
+    /// Checks the member's activities which were inserted after
+    /// last_stats_index against their stored personal bests (excluding
+    /// private matches), and stores any new personal bests and milestones.
+    ///
+    /// Milestones are not generated the first time a member's activities are
+    /// checked, since there is no history to compare against. The personal
+    /// bests are still stored so they can be used for the next sync.
+    async fn update_milestones(
+        &mut self,
+        member_row_id: i32,
+        last_stats_index: i64,
+    ) -> Result<Vec<Milestone>, Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                character_activity_stats.id as character_activity_stats_index,
+                character_activity_stats.kills,
+                character_activity_stats.deaths,
+                character_activity_stats.assists,
+                character_activity_stats.opponents_defeated,
+                character_activity_stats.precision_kills,
+                character_activity_stats.all_medals_earned,
+                character_activity_stats.standing,
+                activity.id as activity_index_id,
+                activity.period,
+                activity.mode as activity_mode,
+                activity.reference_id
+            FROM
+                character_activity_stats
+            INNER JOIN
+                activity ON character_activity_stats.activity = activity.id,
+                character on character_activity_stats.character = character.id
+            WHERE
+                character.member = ? AND
+                character_activity_stats.id > ? AND
+                not exists (select 1 from modes where activity = activity.id and mode = ?)
+            ORDER BY
+                activity.period ASC
+        "#,
+        )
+        .bind(member_row_id)
+        .bind(last_stats_index)
+        .bind(Mode::PrivateMatchesAll.to_id().to_string())
+        .fetch_all(&mut self.db)
+        .await?;
+
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut bests = self.retrieve_personal_bests(member_row_id).await?;
+        let has_history = !bests.is_empty();
+
+        //character_activity_stats index and milestone
+        let mut milestones: Vec<(i64, Milestone)> = Vec::new();
+
+        let longest_streak_id = MilestoneType::LongestWinStreak.to_id();
+        let mut longest_streak: u32 =
+            *bests.get(&longest_streak_id).unwrap_or(&0.0) as u32;
+
+        //carry over a streak which was in progress at the end of the last sync
+        let mut streak: u32 = if has_history {
+            self.get_trailing_win_streak(member_row_id, last_stats_index)
+                .await?
+        } else {
+            0
+        };
+        let mut streak_previous_best: u32 = longest_streak;
+        let mut pending_streak: Option<(i64, Milestone)> = None;
+
+        for row in &rows {
+            let kills: u32 = row.try_get_unchecked("kills")?;
+            let deaths: u32 = row.try_get_unchecked("deaths")?;
+            let assists: u32 = row.try_get_unchecked("assists")?;
+            let opponents_defeated: u32 =
+                row.try_get_unchecked("opponents_defeated")?;
+            let precision_kills: u32 =
+                row.try_get_unchecked("precision_kills")?;
+            let all_medals_earned: u32 =
+                row.try_get_unchecked("all_medals_earned")?;
+            let standing: u32 = row.try_get_unchecked("standing")?;
+            let activity_mode: u32 = row.try_get_unchecked("activity_mode")?;
+            let (standing, _placement) =
+                self.parse_standing(standing, &Mode::from_id(activity_mode)?);
+
+            let stats: [(MilestoneType, f32); 6] = [
+                (MilestoneType::MostKills, kills as f32),
+                (
+                    MilestoneType::HighestEfficiency,
+                    calculate_efficiency(kills, deaths, assists),
+                ),
+                (
+                    MilestoneType::HighestKillsDeathsRatio,
+                    calculate_kills_deaths_ratio(kills, deaths),
+                ),
+                (
+                    MilestoneType::MostOpponentsDefeated,
+                    opponents_defeated as f32,
+                ),
+                (MilestoneType::MostMedals, all_medals_earned as f32),
+                (MilestoneType::MostPrecisionKills, precision_kills as f32),
+            ];
+
+            for (milestone_type, value) in stats.iter() {
+                let best = bests.entry(milestone_type.to_id()).or_insert(0.0);
+
+                if has_history && *value > *best {
+                    milestones.push(parse_milestone_row(
+                        row,
+                        *milestone_type,
+                        *value,
+                        *best,
+                        None,
+                    )?);
+                }
+
+                *best = best.max(*value);
+            }
+
+            if standing == Standing::Victory {
+                if streak == 0 {
+                    streak_previous_best = longest_streak;
+                }
+
+                streak += 1;
+
+                if streak > longest_streak {
+                    longest_streak = streak;
+
+                    //we only store the milestone once the streak has ended
+                    //so we dont get an entry for each game in the streak
+                    if has_history && streak_previous_best > 0 {
+                        pending_streak = Some(parse_milestone_row(
+                            row,
+                            MilestoneType::LongestWinStreak,
+                            streak as f32,
+                            streak_previous_best as f32,
+                            None,
+                        )?);
+                    }
+                }
+            } else {
+                streak = 0;
+                if let Some(e) = pending_streak.take() {
+                    milestones.push(e);
+                }
+            }
+        }
+
+        if let Some(e) = pending_streak.take() {
+            milestones.push(e);
+        }
+
+        bests.insert(longest_streak_id, longest_streak as f32);
+
+        if has_history {
+            //medals earned in the new activities, which had not been earned
+            //in any previous activity
+            let medal_rows = sqlx::query(
+                r#"
+                SELECT
+                    medal_result.reference_id as medal_id,
+                    medal_result.count,
+                    character_activity_stats.id as character_activity_stats_index,
+                    activity.id as activity_index_id,
+                    activity.period,
+                    activity.mode as activity_mode,
+                    activity.reference_id
+                FROM
+                    medal_result
+                INNER JOIN
+                    character_activity_stats ON medal_result.character_activity_stats = character_activity_stats.id,
+                    activity ON character_activity_stats.activity = activity.id,
+                    character on character_activity_stats.character = character.id
+                WHERE
+                    character.member = ? AND
+                    character_activity_stats.id > ? AND
+                    medal_result.count > 0 AND
+                    not exists (select 1 from modes where activity = activity.id and mode = ?) AND
+                    not exists (
+                        SELECT
+                            1
+                        FROM
+                            medal_result as previous_result
+                        INNER JOIN
+                            character_activity_stats as previous_stats ON previous_result.character_activity_stats = previous_stats.id,
+                            character as previous_character on previous_stats.character = previous_character.id
+                        WHERE
+                            previous_character.member = character.member AND
+                            previous_stats.id <= ? AND
+                            previous_result.reference_id = medal_result.reference_id AND
+                            previous_result.count > 0 AND
+                            not exists (select 1 from modes where activity = previous_stats.activity and mode = ?)
+                    )
+                ORDER BY
+                    activity.period ASC
+            "#,
+            )
+            .bind(member_row_id)
+            .bind(last_stats_index)
+            .bind(Mode::PrivateMatchesAll.to_id().to_string())
+            .bind(last_stats_index)
+            .bind(Mode::PrivateMatchesAll.to_id().to_string())
+            .fetch_all(&mut self.db)
+            .await?;
+
+            let mut earned_medals: HashSet<String> = HashSet::new();
+            for row in &medal_rows {
+                let medal_id: String = row.try_get_unchecked("medal_id")?;
+
+                if NON_MEDAL_IDS.contains(&medal_id.as_str())
+                    || earned_medals.contains(&medal_id)
+                {
+                    continue;
+                }
+
+                let count: u32 = row.try_get_unchecked("count")?;
+                milestones.push(parse_milestone_row(
+                    row,
+                    MilestoneType::FirstMedal,
+                    count as f32,
+                    0.0,
+                    Some(medal_id.clone()),
+                )?);
+
+                earned_medals.insert(medal_id);
+            }
+        }
+
+        sqlx::query("BEGIN TRANSACTION;")
+            .execute(&mut self.db)
+            .await?;
+
+        for (milestone_type, value) in &bests {
+            match sqlx::query(
+                r#"
+                INSERT OR REPLACE INTO "main"."personal_best"
+                ("member", "type", "value")
+                VALUES (?, ?, ?)
+                "#,
+            )
+            .bind(member_row_id)
+            .bind(milestone_type.to_string())
+            .bind(value)
+            .execute(&mut self.db)
+            .await
+            {
+                Ok(_e) => (),
+                Err(e) => {
+                    sqlx::query("ROLLBACK;").execute(&mut self.db).await?;
+                    return Err(Error::from(e));
+                }
+            };
+        }
+
+        for (character_activity_stats_index, m) in &milestones {
+            match sqlx::query(
+                r#"
+                INSERT OR IGNORE INTO "main"."milestone"
+                (
+                    "member", "character_activity_stats", "type", "value",
+                    "previous_value", "medal_id"
+                )
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(member_row_id)
+            .bind(character_activity_stats_index)
+            .bind(m.milestone_type.to_id().to_string())
+            .bind(m.value)
+            .bind(m.previous_value)
+            .bind(m.medal_id.clone().unwrap_or_default())
+            .execute(&mut self.db)
+            .await
+            {
+                Ok(_e) => (),
+                Err(e) => {
+                    sqlx::query("ROLLBACK;").execute(&mut self.db).await?;
+                    return Err(Error::from(e));
+                }
+            };
+        }
+
+        sqlx::query("COMMIT;").execute(&mut self.db).await?;
+
+        let mut out: Vec<Milestone> =
+            milestones.into_iter().map(|(_i, m)| m).collect();
+        out.sort_by_key(|a| a.period);
+
+        Ok(out)
+    }
+
+    /// Retrieves the stored personal bests / milestones for the member for
+    /// activities within the specified time period. Most recent first.
+    pub async fn retrieve_milestones(
+        &mut self,
+        member_id: &str,
+        time_period: &DateTimePeriod,
+    ) -> Result<Vec<Milestone>, Error> {
+        let rows = sqlx::query(
+            r#"
+            SELECT
+                milestone.type as milestone_type,
+                milestone.value,
+                milestone.previous_value,
+                milestone.medal_id,
+                activity.id as activity_index_id,
+                activity.period,
+                activity.mode as activity_mode,
+                activity.reference_id
+            FROM
+                milestone
+            INNER JOIN
+                character_activity_stats ON milestone.character_activity_stats = character_activity_stats.id,
+                activity ON character_activity_stats.activity = activity.id,
+                member ON milestone.member = member.id
+            WHERE
+                member.member_id = ? AND
+                activity.period > ? AND
+                activity.period < ?
+            ORDER BY
+                activity.period DESC, milestone.type ASC
+        "#,
+        )
+        .bind(member_id.to_string())
+        .bind(time_period.get_start().to_rfc3339())
+        .bind(time_period.get_end().to_rfc3339())
+        .fetch_all(&mut self.db)
+        .await?;
+
+        let mut out: Vec<Milestone> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let milestone_type: u32 =
+                row.try_get_unchecked("milestone_type")?;
+            let value: f32 = row.try_get_unchecked("value")?;
+            let previous_value: f32 =
+                row.try_get_unchecked("previous_value")?;
+            let medal_id: String = row.try_get_unchecked("medal_id")?;
+            let medal_id = if medal_id.is_empty() {
+                None
+            } else {
+                Some(medal_id)
+            };
+
+            out.push(parse_milestone(
+                row,
+                MilestoneType::from_id(milestone_type),
+                value,
+                previous_value,
+                medal_id,
+            )?);
+        }
+
+        Ok(out)
+    }
+
     /// download results from ids in queue, and return number of items synced
     async fn sync_activities(
         &mut self,
@@ -221,6 +758,7 @@ impl ActivityStoreInterface {
             return Ok(SyncResult {
                 total_available: 0,
                 total_synced: 0,
+                milestones: Vec::new(),
             });
         }
 
@@ -264,6 +802,21 @@ impl ActivityStoreInterface {
                             {
                                 Ok(_e) => {
                                     total_synced += 1;
+
+                                    if self.sync_combat_ratings {
+                                        //combat ratings are optional, so we dont fail the
+                                        //sync if we cant retrieve them
+                                        if let Err(e) = self
+                                            .insert_combat_ratings(&e, api)
+                                            .await
+                                        {
+                                            eprintln!();
+                                            eprintln!(
+                                                "Error storing combat ratings. Skipping : {}",
+                                                e
+                                            );
+                                        }
+                                    }
                                 }
                                 Err(e) => {
                                     eprintln!();
@@ -308,6 +861,7 @@ impl ActivityStoreInterface {
         Ok(SyncResult {
             total_synced,
             total_available,
+            milestones: Vec::new(),
         })
     }
 
@@ -372,6 +926,7 @@ impl ActivityStoreInterface {
             return Ok(SyncResult {
                 total_available: 0,
                 total_synced: 0,
+                milestones: Vec::new(),
             });
         }
 
@@ -435,6 +990,7 @@ impl ActivityStoreInterface {
         Ok(SyncResult {
             total_available: total,
             total_synced: total,
+            milestones: Vec::new(),
         })
     }
 
@@ -463,6 +1019,199 @@ impl ActivityStoreInterface {
         }
     }
 
+    /// Retrieves the current combat rating for the activity mode for each player
+    /// in the activity, and stores it as a snapshot for the activity. Activities
+    /// older than the snapshot window are skipped, since the current rating
+    /// would not reflect the rating at the time the game was played.
+    async fn insert_combat_ratings(
+        &mut self,
+        data: &DestinyPostGameCarnageReportData,
+        api: &ApiInterface,
+    ) -> Result<(), Error> {
+        let now = Utc::now();
+        if data.period
+            < now - Duration::hours(COMBAT_RATING_SNAPSHOT_WINDOW_HOURS)
+        {
+            return Ok(());
+        }
+
+        let mode = &data.activity_details.mode;
+
+        let mut f = Vec::new();
+        for entry in &data.entries {
+            f.push(api.retrieve_alltime_crucible_stats(
+                &entry.player.user_info.membership_id,
+                &entry.character_id,
+                &entry.player.user_info.membership_type,
+                mode,
+            ));
+        }
+
+        let results = futures::future::join_all(f).await;
+
+        let activity_row_id = self
+            .get_activity_row_id(data.activity_details.instance_id)
+            .await?;
+
+        sqlx::query("BEGIN TRANSACTION;")
+            .execute(&mut self.db)
+            .await?;
+
+        for (entry, result) in data.entries.iter().zip(results.iter()) {
+            //if we cant get a rating for a player, we just dont store one for them
+            let rating = match result {
+                Ok(Some(e)) => e.combat_rating,
+                _ => continue,
+            };
+
+            let member_row_id = match self
+                .insert_member_id(
+                    &entry.player.user_info.membership_id,
+                    &entry.player.user_info.membership_type,
+                    &entry.player.user_info.display_name,
+                )
+                .await
+            {
+                Ok(e) => e,
+                Err(e) => {
+                    sqlx::query("ROLLBACK;").execute(&mut self.db).await?;
+                    return Err(e);
+                }
+            };
+
+            let class_type = CharacterClass::from_hash(entry.player.class_hash);
+
+            let r = match self
+                .insert_character_id(
+                    &entry.character_id,
+                    &class_type,
+                    member_row_id,
+                )
+                .await
+            {
+                Ok(character_row_id) => sqlx::query(
+                    r#"
+                    INSERT OR REPLACE INTO "main"."combat_rating"
+                    (
+                        "character", "activity", "mode", "rating", "synced_at"
+                    )
+                    VALUES(?, ?, ?, ?, ?)
+                    "#,
+                )
+                .bind(character_row_id)
+                .bind(activity_row_id)
+                .bind(mode.to_id().to_string())
+                .bind(rating)
+                .bind(now.to_rfc3339())
+                .execute(&mut self.db)
+                .await
+                .map_err(Error::from),
+                Err(e) => Err(e),
+            };
+
+            if let Err(e) = r {
+                sqlx::query("ROLLBACK;").execute(&mut self.db).await?;
+                return Err(e);
+            }
+        }
+
+        sqlx::query("COMMIT;").execute(&mut self.db).await?;
+
+        Ok(())
+    }
+
+    /// Returns the combat ratings stored for the players in the activity at the
+    /// time the activity was synced, keyed by the hash of the player. Players
+    /// without a stored rating are not included.
+    pub async fn retrieve_combat_ratings(
+        &mut self,
+        activity: &CrucibleActivity,
+    ) -> Result<HashMap<u64, f32>, Error> {
+        let mut ratings = self
+            .retrieve_combat_ratings_for_activities(std::slice::from_ref(
+                activity,
+            ))
+            .await?;
+
+        Ok(ratings
+            .remove(&activity.details.index_id)
+            .unwrap_or_default())
+    }
+
+    /// Returns the combat ratings stored for the players in each of the
+    /// activities, keyed by the activity index id and then by the hash of the
+    /// player. Activities without any stored ratings are not included.
+    pub async fn retrieve_combat_ratings_for_activities(
+        &mut self,
+        activities: &[CrucibleActivity],
+    ) -> Result<HashMap<u32, HashMap<u64, f32>>, Error> {
+        let mut out: HashMap<u32, HashMap<u64, f32>> = HashMap::new();
+        if activities.is_empty() {
+            return Ok(out);
+        }
+
+        //ids are integers from the store, so we can safely build the list
+        //into the query, rather than binding a variable for each activity
+        let ids: Vec<String> = activities
+            .iter()
+            .map(|a| a.details.index_id.to_string())
+            .collect();
+
+        let query = format!(
+            r#"
+            SELECT
+                combat_rating.activity,
+                combat_rating.mode,
+                character.character_id,
+                combat_rating.rating
+            FROM
+                combat_rating
+            INNER JOIN
+                character ON combat_rating.character = character.id
+            WHERE
+                combat_rating.activity IN ({})
+        "#,
+            ids.join(", ")
+        );
+
+        let rows = sqlx::query(&query).fetch_all(&mut self.db).await?;
+
+        //activity index id, mode id, character id
+        let mut ratings: HashMap<(u32, u32, String), f32> = HashMap::new();
+        for row in &rows {
+            let activity: u32 = row.try_get_unchecked("activity")?;
+            let mode: u32 = row.try_get_unchecked("mode")?;
+            let character_id: String = row.try_get_unchecked("character_id")?;
+            let rating: f32 = row.try_get_unchecked("rating")?;
+            ratings.insert((activity, mode, character_id), rating);
+        }
+
+        for activity in activities {
+            let mode = activity.details.mode.to_id();
+
+            let mut activity_ratings: HashMap<u64, f32> = HashMap::new();
+            for t in activity.teams.values() {
+                for p in &t.player_performances {
+                    let key = (
+                        activity.details.index_id,
+                        mode,
+                        p.player.character_id.clone(),
+                    );
+
+                    if let Some(e) = ratings.get(&key) {
+                        activity_ratings.insert(p.player.calculate_hash(), *e);
+                    }
+                }
+            }
+
+            if !activity_ratings.is_empty() {
+                out.insert(activity.details.index_id, activity_ratings);
+            }
+        }
+
+        Ok(out)
+    }
+
     //todo: this doesnt need to be an instance fn, not sure if it matters
     fn get_medal_hash_value(
         &self,
@@ -620,11 +1369,11 @@ impl ActivityStoreInterface {
                 "team", "completion_reason", "start_seconds", "time_played_seconds", 
                 "player_count", "team_score", "precision_kills", "weapon_kills_ability", 
                 "weapon_kills_grenade", "weapon_kills_melee", "weapon_kills_super", 
-                "all_medals_earned", "light_level", "activity"
+                "all_medals_earned", "light_level", "activity", "fireteam_id"
             )
             VALUES (
                 ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?,
-                ?, ? )
+                ?, ?, ? )
             "#,
         )
         //we for through format, as otherwise we have to cast to i32, and while
@@ -659,6 +1408,7 @@ impl ActivityStoreInterface {
         .bind(all_medals_earned as i32) //weapon_kills_super
         .bind(char_data.player.light_level) //activity
         .bind(activity_row_id) //activity
+        .bind(char_data.values.fireteam_id) //fireteam_id
         .execute(&mut self.db)
         .await?;
 
@@ -676,6 +1426,10 @@ impl ActivityStoreInterface {
 
         let character_activity_stats_id: i32 = row.try_get("id")?;
 
+        //all of the extended values (medals and other stats) are stored. For
+        //round based modes, the pgcr does not include any per round values
+        //(the team score is the number of rounds won), so there is no round
+        //data to store
         for (key, value) in medal_hash {
             sqlx::query(
                 r#"
@@ -1114,6 +1868,9 @@ impl ActivityStoreInterface {
             r#"
             SELECT
                 *,
+                ifnull((SELECT max(team_result.score) FROM team_result WHERE team_result.activity = character_activity_stats.activity AND team_result.team_id != character_activity_stats.team), 0) as opponent_team_score,
+                (SELECT count(*) FROM character_activity_stats AS fireteam WHERE fireteam.activity = character_activity_stats.activity AND fireteam.fireteam_id = character_activity_stats.fireteam_id AND fireteam.fireteam_id != 0) as fireteam_size,
+                ifnull((SELECT max(size) FROM (SELECT count(*) as size FROM character_activity_stats AS fireteam WHERE fireteam.activity = character_activity_stats.activity AND fireteam.team != character_activity_stats.team AND fireteam.fireteam_id != 0 GROUP BY fireteam.fireteam_id)), 0) as opponent_fireteam_size,
                 character_activity_stats.id as character_activity_stats_index
             FROM
                 character_activity_stats
@@ -1128,8 +1885,12 @@ impl ActivityStoreInterface {
         .fetch_all(&mut self.db)
         .await?;
 
+        let details = self.parse_activity(manifest, &activity_row).await?;
+
         for c_row in character_rows {
-            let stats = self.parse_crucible_stats(manifest, &c_row).await?;
+            let stats = self
+                .parse_crucible_stats(manifest, &c_row, &details.mode)
+                .await?;
 
             let player = self.parse_player(&c_row).await?;
 
@@ -1147,8 +1908,6 @@ impl ActivityStoreInterface {
             }
         }
 
-        let details = self.parse_activity(manifest, &activity_row).await?;
-
         Ok(CrucibleActivity { details, teams })
     }
 
@@ -1198,6 +1957,243 @@ impl ActivityStoreInterface {
         Ok(out)
     }
 
+    /// Retrieves the number of player performances across all players in the
+    /// member's / character's activities within the time period, along with the
+    /// number of those performances in which each medal was earned.
+    pub async fn retrieve_lobby_medal_counts(
+        &mut self,
+        member_id: &str,
+        character_selection: &CharacterClassSelection,
+        platform: &Platform,
+        mode: &Mode,
+        time_period: &DateTimePeriod,
+    ) -> Result<LobbyMedalCounts, Error> {
+        //if mode if private, we dont restrict results
+        let restrict_mode_id = if mode.is_private() {
+            -1
+        } else {
+            //if not private, then we dont include any results that are private
+            Mode::PrivateMatchesAll.to_id() as i32
+        };
+
+        let (filter, id) =
+            if character_selection == &CharacterClassSelection::All {
+                ("member.member_id = ?", member_id.to_string())
+            } else {
+                let character_id = self
+                    .retrieve_character_selection_id(
+                        member_id,
+                        platform,
+                        character_selection,
+                    )
+                    .await?;
+
+                ("character.character_id = ?", character_id)
+            };
+
+        //sqlx doesnt let us bind the column, so we build the subquery for the
+        //lobbies which we then use in both queries
+        let lobby_query = format!(
+            r#"
+            SELECT DISTINCT
+                activity.id
+            FROM
+                activity
+            INNER JOIN
+                character_activity_stats on character_activity_stats.activity = activity.id,
+                character on character_activity_stats.character = character.id,
+                member on character.member = member.id
+            WHERE
+                {} AND
+                activity.period > ? AND
+                activity.period < ? AND
+                exists (select 1 from modes where activity = activity.id and mode = ?) AND
+                not exists (select 1 from modes where activity = activity.id and mode = ?)
+            "#,
+            filter
+        );
+
+        let query = format!(
+            r#"
+            SELECT
+                count(*) as performance_count
+            FROM
+                character_activity_stats
+            WHERE
+                character_activity_stats.activity IN ({})
+            "#,
+            lobby_query
+        );
+
+        let row = sqlx::query(&query)
+            .bind(&id)
+            .bind(time_period.get_start().to_rfc3339())
+            .bind(time_period.get_end().to_rfc3339())
+            .bind(mode.to_id().to_string())
+            .bind(restrict_mode_id.to_string())
+            .fetch_one(&mut self.db)
+            .await?;
+
+        let performance_count: u32 =
+            row.try_get_unchecked("performance_count")?;
+
+        let query = format!(
+            r#"
+            SELECT
+                medal_result.reference_id as medal_id,
+                count(*) as performance_count
+            FROM
+                medal_result
+            INNER JOIN
+                character_activity_stats ON medal_result.character_activity_stats = character_activity_stats.id
+            WHERE
+                medal_result.count > 0 AND
+                character_activity_stats.activity IN ({})
+            GROUP BY
+                medal_result.reference_id
+            "#,
+            lobby_query
+        );
+
+        let rows = sqlx::query(&query)
+            .bind(&id)
+            .bind(time_period.get_start().to_rfc3339())
+            .bind(time_period.get_end().to_rfc3339())
+            .bind(mode.to_id().to_string())
+            .bind(restrict_mode_id.to_string())
+            .fetch_all(&mut self.db)
+            .await?;
+
+        let mut medal_counts: HashMap<String, u32> = HashMap::new();
+        for row in &rows {
+            let medal_id: String = row.try_get_unchecked("medal_id")?;
+            let count: u32 = row.try_get_unchecked("performance_count")?;
+
+            if NON_MEDAL_IDS.contains(&medal_id.as_str()) {
+                continue;
+            }
+
+            medal_counts.insert(medal_id, count);
+        }
+
+        Ok(LobbyMedalCounts {
+            performance_count,
+            medal_counts,
+        })
+    }
+
+    /// Retrieves the complete activity data (all teams and players) for each
+    /// of the activities the member / character played within the time period.
+    /// Most recent activities first.
+    pub async fn retrieve_crucible_activities_since(
+        &mut self,
+        member_id: &str,
+        character_selection: &CharacterClassSelection,
+        platform: &Platform,
+        mode: &Mode,
+        time_period: &DateTimePeriod,
+        manifest: &mut ManifestInterface,
+    ) -> Result<Option<Vec<CrucibleActivity>>, Error> {
+        //if mode if private, we dont restrict results
+        let restrict_mode_id = if mode.is_private() {
+            -1
+        } else {
+            //if not private, then we dont include any results that are private
+            Mode::PrivateMatchesAll.to_id() as i32
+        };
+
+        let activity_rows = if character_selection
+            == &CharacterClassSelection::All
+        {
+            sqlx::query(
+                r#"
+                SELECT DISTINCT
+                    activity.id as activity_index_id,
+                    activity.activity_id,
+                    activity.period,
+                    activity.mode as activity_mode,
+                    activity.director_activity_hash,
+                    activity.reference_id,
+                    activity.platform
+                FROM
+                    activity
+                INNER JOIN
+                    character_activity_stats on character_activity_stats.activity = activity.id,
+                    character on character_activity_stats.character = character.id,
+                    member on character.member = member.id
+                WHERE
+                    member.member_id = ? AND
+                    activity.period > ? AND
+                    activity.period < ? AND
+                    exists (select 1 from modes where activity = activity.id and mode = ?) AND
+                    not exists (select 1 from modes where activity = activity.id and mode = ?)
+                ORDER BY
+                    activity.period DESC
+                "#,
+            )
+            .bind(member_id.to_string())
+            .bind(time_period.get_start().to_rfc3339())
+            .bind(time_period.get_end().to_rfc3339())
+            .bind(mode.to_id().to_string())
+            .bind(restrict_mode_id.to_string())
+            .fetch_all(&mut self.db)
+            .await?
+        } else {
+            let character_id = self
+                .retrieve_character_selection_id(
+                    member_id,
+                    platform,
+                    character_selection,
+                )
+                .await?;
+
+            sqlx::query(
+                r#"
+                SELECT DISTINCT
+                    activity.id as activity_index_id,
+                    activity.activity_id,
+                    activity.period,
+                    activity.mode as activity_mode,
+                    activity.director_activity_hash,
+                    activity.reference_id,
+                    activity.platform
+                FROM
+                    activity
+                INNER JOIN
+                    character_activity_stats on character_activity_stats.activity = activity.id,
+                    character on character_activity_stats.character = character.id
+                WHERE
+                    character.character_id = ? AND
+                    activity.period > ? AND
+                    activity.period < ? AND
+                    exists (select 1 from modes where activity = activity.id and mode = ?) AND
+                    not exists (select 1 from modes where activity = activity.id and mode = ?)
+                ORDER BY
+                    activity.period DESC
+                "#,
+            )
+            .bind(character_id)
+            .bind(time_period.get_start().to_rfc3339())
+            .bind(time_period.get_end().to_rfc3339())
+            .bind(mode.to_id().to_string())
+            .bind(restrict_mode_id.to_string())
+            .fetch_all(&mut self.db)
+            .await?
+        };
+
+        if activity_rows.is_empty() {
+            return Ok(None);
+        }
+
+        let mut out: Vec<CrucibleActivity> =
+            Vec::with_capacity(activity_rows.len());
+        for row in &activity_rows {
+            out.push(self.populate_activity_data(row, manifest).await?);
+        }
+
+        Ok(Some(out))
+    }
+
     pub async fn retrieve_activities_since(
         &mut self,
         member_id: &str,
@@ -1265,6 +2261,9 @@ impl ActivityStoreInterface {
                 *,
                 activity.mode as activity_mode,
                 activity.id as activity_index_id,
+                ifnull((SELECT max(team_result.score) FROM team_result WHERE team_result.activity = activity.id AND team_result.team_id != character_activity_stats.team), 0) as opponent_team_score,
+                (SELECT count(*) FROM character_activity_stats AS fireteam WHERE fireteam.activity = character_activity_stats.activity AND fireteam.fireteam_id = character_activity_stats.fireteam_id AND fireteam.fireteam_id != 0) as fireteam_size,
+                ifnull((SELECT max(size) FROM (SELECT count(*) as size FROM character_activity_stats AS fireteam WHERE fireteam.activity = character_activity_stats.activity AND fireteam.team != character_activity_stats.team AND fireteam.fireteam_id != 0 GROUP BY fireteam.fireteam_id)), 0) as opponent_fireteam_size,
                 character_activity_stats.id as character_activity_stats_index  
             FROM
                 character_activity_stats
@@ -1328,6 +2327,9 @@ impl ActivityStoreInterface {
                 *,
                 activity.mode as activity_mode,
                 activity.id as activity_index_id,
+                ifnull((SELECT max(team_result.score) FROM team_result WHERE team_result.activity = activity.id AND team_result.team_id != character_activity_stats.team), 0) as opponent_team_score,
+                (SELECT count(*) FROM character_activity_stats AS fireteam WHERE fireteam.activity = character_activity_stats.activity AND fireteam.fireteam_id = character_activity_stats.fireteam_id AND fireteam.fireteam_id != 0) as fireteam_size,
+                ifnull((SELECT max(size) FROM (SELECT count(*) as size FROM character_activity_stats AS fireteam WHERE fireteam.activity = character_activity_stats.activity AND fireteam.team != character_activity_stats.team AND fireteam.fireteam_id != 0 GROUP BY fireteam.fireteam_id)), 0) as opponent_fireteam_size,
                 character_activity_stats.id as character_activity_stats_index  
             FROM
                 character_activity_stats
@@ -1435,6 +2437,7 @@ impl ActivityStoreInterface {
         &mut self,
         manifest: &mut ManifestInterface,
         activity_row: &sqlx::sqlite::SqliteRow,
+        mode: &Mode,
     ) -> Result<CrucibleStats, Error> {
         let assists: u32 = activity_row.try_get_unchecked("assists")?;
         let score: u32 = activity_row.try_get_unchecked("score")?;
@@ -1455,7 +2458,7 @@ impl ActivityStoreInterface {
             activity_row.try_get_unchecked("activity_duration_seconds")?;
 
         let standing: u32 = activity_row.try_get_unchecked("standing")?;
-        let standing: Standing = Standing::from_value(standing);
+        let (standing, placement) = self.parse_standing(standing, mode);
 
         let team: i32 = activity_row.try_get_unchecked("team")?;
 
@@ -1473,6 +2476,13 @@ impl ActivityStoreInterface {
             activity_row.try_get_unchecked("player_count")?;
 
         let team_score: u32 = activity_row.try_get_unchecked("team_score")?;
+        let opponent_team_score: u32 =
+            activity_row.try_get_unchecked("opponent_team_score")?;
+        let fireteam_id: i64 = activity_row.try_get_unchecked("fireteam_id")?;
+        let fireteam_size: u32 =
+            activity_row.try_get_unchecked("fireteam_size")?;
+        let opponent_fireteam_size: u32 =
+            activity_row.try_get_unchecked("opponent_fireteam_size")?;
 
         let precision_kills: u32 =
             activity_row.try_get_unchecked("precision_kills")?;
@@ -1653,6 +2663,11 @@ impl ActivityStoreInterface {
             time_played_seconds,
             player_count,
             team_score,
+            opponent_team_score,
+            placement,
+            fireteam_id,
+            fireteam_size,
+            opponent_fireteam_size,
             extended: Some(extended),
         };
 
@@ -1693,7 +2708,9 @@ impl ActivityStoreInterface {
     ) -> Result<CruciblePlayerActivityPerformance, Error> {
         let activity_detail =
             self.parse_activity(manifest, activity_row).await?;
-        let stats = self.parse_crucible_stats(manifest, activity_row).await?;
+        let stats = self
+            .parse_crucible_stats(manifest, activity_row, &activity_detail.mode)
+            .await?;
         let player = self.parse_player(activity_row).await?;
 
         let performance = CruciblePlayerPerformance { player, stats };
@@ -1707,19 +2724,78 @@ impl ActivityStoreInterface {
     }
 }
 
+fn parse_milestone_row(
+    row: &sqlx::sqlite::SqliteRow,
+    milestone_type: MilestoneType,
+    value: f32,
+    previous_value: f32,
+    medal_id: Option<String>,
+) -> Result<(i64, Milestone), Error> {
+    let character_activity_stats_index: i64 =
+        row.try_get("character_activity_stats_index")?;
+
+    let milestone =
+        parse_milestone(row, milestone_type, value, previous_value, medal_id)?;
+
+    Ok((character_activity_stats_index, milestone))
+}
+
+fn parse_milestone(
+    row: &sqlx::sqlite::SqliteRow,
+    milestone_type: MilestoneType,
+    value: f32,
+    previous_value: f32,
+    medal_id: Option<String>,
+) -> Result<Milestone, Error> {
+    let activity_index_id: u32 = row.try_get_unchecked("activity_index_id")?;
+    let reference_id: u32 = row.try_get_unchecked("reference_id")?;
+    let mode_id: u32 = row.try_get_unchecked("activity_mode")?;
+
+    let period: String = row.try_get_unchecked("period")?;
+    let period = DateTime::parse_from_rfc3339(&period)?.with_timezone(&Utc);
+
+    Ok(Milestone {
+        milestone_type,
+        value,
+        previous_value,
+        medal_id,
+        activity_index_id,
+        reference_id,
+        mode: Mode::from_id(mode_id)?,
+        period,
+    })
+}
+
+#[derive(Debug, Default)]
+pub struct LobbyMedalCounts {
+    /// total number of player performances across all of the lobbies
+    pub performance_count: u32,
+
+    /// number of player performances where each medal was earned, keyed by
+    /// the medal (historical stats) id
+    pub medal_counts: HashMap<String, u32>,
+}
+
 #[derive(Debug)]
 pub struct SyncResult {
     pub total_available: u32,
     pub total_synced: u32,
+
+    /// personal bests and milestones found in the synced activities
+    pub milestones: Vec<Milestone>,
 }
 
 impl std::ops::Add<SyncResult> for SyncResult {
     type Output = SyncResult;
 
     fn add(self, sr: SyncResult) -> SyncResult {
+        let mut milestones = self.milestones;
+        milestones.extend(sr.milestones);
+
         SyncResult {
             total_available: self.total_available + sr.total_available,
             total_synced: self.total_synced + sr.total_synced,
+            milestones,
         }
     }
 }
@@ -20,7 +20,7 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
-use reqwest::header::{HeaderMap, HeaderValue, CONNECTION};
+use reqwest::header::{HeaderMap, HeaderValue, CONNECTION, RANGE};
 use reqwest::{Client, Url};
 
 use crate::error::Error;
@@ -31,6 +31,7 @@ use crate::utils::print_verbose;
 
 const DESTINY_API_KEY: &str = env!("DESTINY_API_KEY");
 const API_TIMEOUT: u64 = 10; //seconds
+const DOWNLOAD_TIMEOUT: u64 = 600; //seconds
 
 //this makes sure that the env variable isnt set, but empty
 static_assertions::const_assert!(!DESTINY_API_KEY.is_empty());
@@ -73,6 +74,33 @@ impl ApiClient {
         Ok(response)
     }
 
+    /// Requests a file download, starting at the specified byte offset.
+    /// Servers which support range requests will respond with a 206 (Partial
+    /// Content) status and only send the remaining bytes. Downloads have a
+    /// longer timeout than API calls since the body may be large.
+    pub async fn download(
+        &self,
+        url: &str,
+        offset: u64,
+    ) -> Result<reqwest::Response, Error> {
+        let url = Url::parse(url).unwrap();
+
+        print_verbose(&format!("{} (offset : {})", url, offset), self.verbose);
+
+        let mut request = self
+            .client
+            .get(url)
+            .timeout(std::time::Duration::from_secs(DOWNLOAD_TIMEOUT));
+
+        if offset > 0 {
+            request = request.header(RANGE, format!("bytes={}-", offset));
+        }
+
+        let response = request.send().await?;
+
+        Ok(response)
+    }
+
     pub async fn call_and_parse<
         T: serde::de::DeserializeOwned + IsDestinyAPIResponse,
     >(
@@ -20,6 +20,7 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+use crate::enums::milestonetype::MilestoneType;
 use crate::enums::mode::Mode;
 use crate::enums::platform::Platform;
 use crate::enums::standing::Standing;
@@ -28,18 +29,32 @@ use crate::enums::{
     itemtype::{ItemSubType, ItemType},
 };
 use crate::enums::{completionreason::CompletionReason, medaltier::MedalTier};
+use crate::manifestinterface::ManifestInterface;
 use chrono::{DateTime, Utc};
 
+use std::fmt;
 use std::{cmp::max, collections::hash_map::DefaultHasher, hash::Hasher};
 use std::{collections::HashMap, hash::Hash};
 
 use crate::utils::{
-    calculate_efficiency, calculate_kills_deaths_assists,
-    calculate_kills_deaths_ratio,
+    calculate_correlation, calculate_efficiency,
+    calculate_kills_deaths_assists, calculate_kills_deaths_ratio,
+    calculate_percent, format_f32,
 };
 
 const PLAYER_START_BUFFER: u32 = 30;
 
+/// Extended stat values which are stored along side medals, but which are
+/// not medals.
+pub const NON_MEDAL_IDS: [&str; 6] = [
+    "allMedalsEarned",
+    "precisionKills",
+    "weaponKillsAbility",
+    "weaponKillsGrenade",
+    "weaponKillsMelee",
+    "weaponKillsSuper",
+];
+
 #[derive(Debug, Clone)]
 pub struct Team {
     pub id: i32,
@@ -49,6 +64,26 @@ pub struct Team {
     pub display_name: String,
 }
 
+impl Team {
+    /// Returns the size of each fireteam on the team, largest first. Players
+    /// without a fireteam id are not included.
+    pub fn get_fireteam_sizes(&self) -> Vec<u32> {
+        let mut fireteams: HashMap<i64, u32> = HashMap::new();
+        for p in &self.player_performances {
+            if p.stats.fireteam_id == 0 {
+                continue;
+            }
+
+            *fireteams.entry(p.stats.fireteam_id).or_default() += 1;
+        }
+
+        let mut sizes: Vec<u32> = fireteams.into_iter().map(|e| e.1).collect();
+        sizes.sort_unstable_by(|a, b| b.cmp(a));
+
+        sizes
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CrucibleActivity {
     pub details: ActivityDetail,
@@ -70,6 +105,122 @@ impl CrucibleActivity {
 
         None
     }
+
+    /// Returns the performances of the players on the opposing team(s) to the
+    /// specified member. For modes without teams (such as Rumble), all other
+    /// players are returned.
+    pub fn get_opponent_performances(
+        &self,
+        member_id: &str,
+    ) -> Vec<&CruciblePlayerPerformance> {
+        let member_team = match self.get_member_performance(member_id) {
+            Some(e) => e.stats.team,
+            None => return Vec::new(),
+        };
+
+        let no_teams = self.teams.len() < 2;
+
+        let mut out: Vec<&CruciblePlayerPerformance> = Vec::new();
+        for t in self.teams.values() {
+            for p in &t.player_performances {
+                if p.player.member_id == member_id {
+                    continue;
+                }
+
+                if no_teams || p.stats.team != member_team {
+                    out.push(p);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Returns the performances of the other players on the member's team.
+    /// Returns an empty Vec if the activity does not have teams.
+    pub fn get_teammate_performances(
+        &self,
+        member_id: &str,
+    ) -> Vec<&CruciblePlayerPerformance> {
+        let member_team = match self.get_member_performance(member_id) {
+            Some(e) => e.stats.team,
+            None => return Vec::new(),
+        };
+
+        if self.teams.len() < 2 {
+            return Vec::new();
+        }
+
+        let mut out: Vec<&CruciblePlayerPerformance> = Vec::new();
+        for t in self.teams.values() {
+            for p in &t.player_performances {
+                if p.player.member_id != member_id
+                    && p.stats.team == member_team
+                {
+                    out.push(p);
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Returns the average light level of the member's team and of the
+    /// opposing team(s). Players without a light level are not included.
+    pub fn get_light_level_advantage(
+        &self,
+        member_id: &str,
+    ) -> Option<LightLevelAdvantage> {
+        let member = self.get_member_performance(member_id)?;
+
+        let team: Vec<&CruciblePlayerPerformance> = self
+            .teams
+            .values()
+            .flat_map(|t| t.player_performances.iter())
+            .filter(|p| p.stats.team == member.stats.team)
+            .collect();
+        let opponents = self.get_opponent_performances(member_id);
+
+        let team_average = calculate_average_light_level(&team)?;
+        let opponent_average = calculate_average_light_level(&opponents)?;
+
+        Some(LightLevelAdvantage {
+            team_average,
+            opponent_average,
+        })
+    }
+
+    /// Returns the rounds won and lost by the specified team, if the activity
+    /// is from a round based mode.
+    pub fn get_team_round_results(&self, team_id: i32) -> Option<RoundResults> {
+        if !self.details.mode.is_round_based() {
+            return None;
+        }
+
+        let team = self.teams.get(&team_id)?;
+        let lost = self
+            .teams
+            .values()
+            .filter(|t| t.id != team_id)
+            .map(|t| t.score)
+            .max()
+            .unwrap_or(0);
+
+        Some(RoundResults {
+            won: team.score,
+            lost,
+        })
+    }
+
+    /// Returns the rounds won and lost by the team of the specified member, if
+    /// the activity is from a round based mode.
+    pub fn get_member_round_results(
+        &self,
+        member_id: &str,
+    ) -> Option<RoundResults> {
+        let performance = self.get_member_performance(member_id)?;
+        self.get_team_round_results(performance.stats.team)
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -84,6 +235,46 @@ pub struct CruciblePlayerActivityPerformance {
     pub activity_detail: ActivityDetail,
 }
 
+impl CruciblePlayerActivityPerformance {
+    /// Returns the rounds won and lost by the player's team, if the activity
+    /// is from a round based mode.
+    pub fn get_round_results(&self) -> Option<RoundResults> {
+        if !self.activity_detail.mode.is_round_based() {
+            return None;
+        }
+
+        Some(RoundResults {
+            won: self.performance.stats.team_score,
+            lost: self.performance.stats.opponent_team_score,
+        })
+    }
+}
+
+/// Rounds won and lost by a team in a round based mode (such as Elimination
+/// or Trials of Osiris).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RoundResults {
+    pub won: u32,
+    pub lost: u32,
+}
+
+impl RoundResults {
+    pub fn total(&self) -> u32 {
+        self.won + self.lost
+    }
+
+    /// Whether one of the teams did not win a round.
+    pub fn is_sweep(&self) -> bool {
+        self.total() > 0 && (self.won == 0 || self.lost == 0)
+    }
+}
+
+impl fmt::Display for RoundResults {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} - {}", self.won, self.lost)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct CrucibleStats {
     pub assists: u32,
@@ -106,18 +297,41 @@ pub struct CrucibleStats {
     pub player_count: u32,
     pub team_score: u32,
 
+    //score of the highest scoring opposing team (0 if there are no teams)
+    pub opponent_team_score: u32,
+
+    //placement (starting at 1) for free for all modes
+    pub placement: Option<u32>,
+
+    //fireteam id is 0 if it is not available. sizes are 0 if unknown
+    pub fireteam_id: i64,
+    pub fireteam_size: u32,
+
+    //size of the largest fireteam on the opposing team(s)
+    pub opponent_fireteam_size: u32,
+
     pub extended: Option<ExtendedCrucibleStats>,
 }
 
 impl CrucibleStats {
+    /// Whether the player joined the activity after it had started.
+    pub fn joined_late(&self) -> bool {
+        self.start_seconds > PLAYER_START_BUFFER
+    }
+
+    /// Whether the player left the activity before it was completed.
+    pub fn left_early(&self) -> bool {
+        !self.completed
+    }
+
     pub fn generate_status(&self) -> String {
         let mut out: Vec<String> = Vec::new();
 
-        if self.start_seconds > PLAYER_START_BUFFER {
+        if self.joined_late() {
             out.push("L".to_string());
         }
 
-        if !self.completed {
+        if self.left_early() {
             out.push("E".to_string());
         }
 
@@ -189,6 +403,14 @@ pub struct Medal {
     pub description: String,
 }
 
+impl Medal {
+    /// Returns whether this is an actual medal, as opposed to one of the
+    /// extended stat values which are stored with the medals.
+    pub fn is_medal(&self) -> bool {
+        !NON_MEDAL_IDS.contains(&self.id.as_str())
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct AggregateCruciblePerformances {
     pub total_activities: u32,
@@ -429,6 +651,530 @@ impl AggregateCruciblePerformances {
     }
 }
 
+fn calculate_average_light_level(
+    performances: &[&CruciblePlayerPerformance],
+) -> Option<f32> {
+    let levels: Vec<i32> = performances
+        .iter()
+        .map(|p| p.player.light_level)
+        .filter(|l| *l > 0)
+        .collect();
+
+    if levels.is_empty() {
+        return None;
+    }
+
+    Some(levels.iter().sum::<i32>() as f32 / levels.len() as f32)
+}
+
+/// Average light levels of a team and their opponents in an activity.
+#[derive(Debug, Clone, Copy)]
+pub struct LightLevelAdvantage {
+    pub team_average: f32,
+    pub opponent_average: f32,
+}
+
+impl LightLevelAdvantage {
+    /// Positive if the team had a higher average light level than their
+    /// opponents.
+    pub fn advantage(&self) -> f32 {
+        self.team_average - self.opponent_average
+    }
+}
+
+/// Returns the correlation between each player's light level (relative to
+/// the average light level of their lobby) and their kills, across all
+/// players in the activities. Players without a light level are ignored.
+pub fn calculate_light_level_kills_correlation(
+    activities: &[&CrucibleActivity],
+) -> f32 {
+    let mut light_levels: Vec<f32> = Vec::new();
+    let mut kills: Vec<f32> = Vec::new();
+
+    for a in activities {
+        let performances: Vec<&CruciblePlayerPerformance> = a
+            .teams
+            .values()
+            .flat_map(|t| t.player_performances.iter())
+            .filter(|p| p.player.light_level > 0)
+            .collect();
+
+        let lobby_average = match calculate_average_light_level(&performances) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        for p in performances {
+            light_levels.push(p.player.light_level as f32 - lobby_average);
+            kills.push(p.stats.kills as f32);
+        }
+    }
+
+    calculate_correlation(&light_levels, &kills)
+}
+
+/// Member results for a group of activities.
+#[derive(Debug, Default, Clone)]
+pub struct ResultStat {
+    pub total_activities: u32,
+    pub wins: u32,
+    pub losses: u32,
+    pub kills: u32,
+    pub deaths: u32,
+    pub assists: u32,
+}
+
+impl ResultStat {
+    pub fn add(&mut self, performance: &CruciblePlayerPerformance) {
+        self.total_activities += 1;
+
+        match performance.stats.standing {
+            Standing::Victory => self.wins += 1,
+            Standing::Defeat => self.losses += 1,
+            Standing::Unknown => (),
+        };
+
+        self.kills += performance.stats.kills;
+        self.deaths += performance.stats.deaths;
+        self.assists += performance.stats.assists;
+    }
+
+    pub fn win_rate(&self) -> f32 {
+        calculate_percent(self.wins, self.total_activities)
+    }
+
+    pub fn kills_deaths_ratio(&self) -> f32 {
+        calculate_kills_deaths_ratio(self.kills, self.deaths)
+    }
+
+    pub fn efficiency(&self) -> f32 {
+        calculate_efficiency(self.kills, self.deaths, self.assists)
+    }
+}
+
+/// The number of players of each class on a team.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ClassComposition {
+    pub titans: u32,
+    pub hunters: u32,
+    pub warlocks: u32,
+}
+
+impl ClassComposition {
+    pub fn with_performances(
+        performances: &[&CruciblePlayerPerformance],
+    ) -> ClassComposition {
+        let mut out = ClassComposition::default();
+        for p in performances {
+            match p.player.class_type {
+                CharacterClass::Titan => out.titans += 1,
+                CharacterClass::Hunter => out.hunters += 1,
+                CharacterClass::Warlock => out.warlocks += 1,
+                CharacterClass::Unknown => (),
+            };
+        }
+
+        out
+    }
+
+    pub fn get_count(&self, class_type: &CharacterClass) -> u32 {
+        match class_type {
+            CharacterClass::Titan => self.titans,
+            CharacterClass::Hunter => self.hunters,
+            CharacterClass::Warlock => self.warlocks,
+            CharacterClass::Unknown => 0,
+        }
+    }
+}
+
+impl fmt::Display for ClassComposition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} Titan, {} Hunter, {} Warlock",
+            self.titans, self.hunters, self.warlocks
+        )
+    }
+}
+
+/// Member results grouped by the member's class and by the class composition
+/// of the opposing team(s).
+#[derive(Debug, Default)]
+pub struct AggregateClassComposition {
+    pub total_activities: u32,
+
+    //results by the class the member played
+    pub member_classes: HashMap<CharacterClass, ResultStat>,
+
+    //results by the number of opponents of each class, keyed by class and
+    //the number of opponents of that class
+    pub opponent_class_counts: HashMap<(CharacterClass, u32), ResultStat>,
+
+    //results by the exact class composition of the opponents
+    pub opponent_compositions: HashMap<ClassComposition, ResultStat>,
+}
+
+impl AggregateClassComposition {
+    pub fn with_activities(
+        activities: &[CrucibleActivity],
+        member_id: &str,
+    ) -> AggregateClassComposition {
+        let mut out = AggregateClassComposition::default();
+
+        for a in activities {
+            let performance = match a.get_member_performance(member_id) {
+                Some(e) => e,
+                None => continue,
+            };
+
+            out.total_activities += 1;
+
+            out.member_classes
+                .entry(performance.player.class_type)
+                .or_default()
+                .add(performance);
+
+            let opponents = a.get_opponent_performances(member_id);
+            let composition = ClassComposition::with_performances(&opponents);
+
+            for class_type in &[
+                CharacterClass::Titan,
+                CharacterClass::Hunter,
+                CharacterClass::Warlock,
+            ] {
+                out.opponent_class_counts
+                    .entry((*class_type, composition.get_count(class_type)))
+                    .or_default()
+                    .add(performance);
+            }
+
+            out.opponent_compositions
+                .entry(composition)
+                .or_default()
+                .add(performance);
+        }
+
+        out
+    }
+
+    /// Returns the combined results for all activities where the opponents
+    /// included at least min_count players of the specified class.
+    pub fn get_opponent_class_min_count(
+        &self,
+        class_type: &CharacterClass,
+        min_count: u32,
+    ) -> ResultStat {
+        let mut out = ResultStat::default();
+        for ((c, count), stat) in &self.opponent_class_counts {
+            if c != class_type || *count < min_count {
+                continue;
+            }
+
+            out.total_activities += stat.total_activities;
+            out.wins += stat.wins;
+            out.losses += stat.losses;
+            out.kills += stat.kills;
+            out.deaths += stat.deaths;
+            out.assists += stat.assists;
+        }
+
+        out
+    }
+}
+
+/// How often a teammate left early while playing with the member.
+#[derive(Debug, Clone)]
+pub struct QuitterStat {
+    pub player: Player,
+    pub total_activities: u32,
+    pub left_early: u32,
+}
+
+impl QuitterStat {
+    pub fn left_early_rate(&self) -> f32 {
+        calculate_percent(self.left_early, self.total_activities)
+    }
+}
+
+/// Member results for activities where players joined late or left early.
+#[derive(Debug, Default)]
+pub struct AggregateEarlyLeaves {
+    pub all: ResultStat,
+
+    //activities where the member joined in progress or left early
+    pub member_joined_late: ResultStat,
+    pub member_left_early: ResultStat,
+
+    //activities where at least one teammate joined late or left early
+    pub teammate_joined_late: ResultStat,
+    pub teammate_left_early: ResultStat,
+
+    //activities where at least one opponent left early
+    pub opponent_left_early: ResultStat,
+
+    //activities where everyone played the entire game
+    pub no_early_leaves: ResultStat,
+
+    //teammates keyed by member id. Includes teammates who never left early
+    pub teammates: HashMap<String, QuitterStat>,
+}
+
+impl AggregateEarlyLeaves {
+    pub fn with_activities(
+        activities: &[CrucibleActivity],
+        member_id: &str,
+    ) -> AggregateEarlyLeaves {
+        let mut out = AggregateEarlyLeaves::default();
+
+        for a in activities {
+            let performance = match a.get_member_performance(member_id) {
+                Some(e) => e,
+                None => continue,
+            };
+
+            out.all.add(performance);
+
+            if performance.stats.joined_late() {
+                out.member_joined_late.add(performance);
+            }
+
+            let member_left = performance.stats.left_early();
+            if member_left {
+                out.member_left_early.add(performance);
+            }
+
+            let teammates = a.get_teammate_performances(member_id);
+            let teammate_left = teammates.iter().any(|p| p.stats.left_early());
+
+            if teammates.iter().any(|p| p.stats.joined_late()) {
+                out.teammate_joined_late.add(performance);
+            }
+
+            if teammate_left {
+                out.teammate_left_early.add(performance);
+            }
+
+            let opponent_left = a
+                .get_opponent_performances(member_id)
+                .iter()
+                .any(|p| p.stats.left_early());
+
+            if opponent_left {
+                out.opponent_left_early.add(performance);
+            }
+
+            if !member_left && !teammate_left && !opponent_left {
+                out.no_early_leaves.add(performance);
+            }
+
+            for p in teammates {
+                let stat = out
+                    .teammates
+                    .entry(p.player.member_id.to_string())
+                    .or_insert_with(|| QuitterStat {
+                        player: p.player.clone(),
+                        total_activities: 0,
+                        left_early: 0,
+                    });
+
+                stat.total_activities += 1;
+                if p.stats.left_early() {
+                    stat.left_early += 1;
+                }
+            }
+        }
+
+        out
+    }
+
+    /// Returns teammates who left early in at least min_count activities,
+    /// sorted by the number of activities they left early.
+    pub fn get_repeat_quitters(&self, min_count: u32) -> Vec<&QuitterStat> {
+        let mut out: Vec<&QuitterStat> = self
+            .teammates
+            .values()
+            .filter(|e| e.left_early >= min_count)
+            .collect();
+
+        out.sort_by(|a, b| {
+            b.left_early
+                .cmp(&a.left_early)
+                .then(b.total_activities.cmp(&a.total_activities))
+        });
+
+        out
+    }
+}
+
+/// Aggregate placements for activities from free for all modes. Activities
+/// from other modes are ignored.
+#[derive(Debug, Default)]
+pub struct AggregatePlacements {
+    pub total_activities: u32,
+    pub wins: u32,
+    pub win_rate: f32,
+    pub average_placement: f32,
+    pub best_placement: u32,
+
+    //number of activities for each placement, index 0 is 1st place
+    pub placements: Vec<u32>,
+}
+
+impl AggregatePlacements {
+    pub fn with_performances(
+        performances: &[&CruciblePlayerPerformance],
+    ) -> AggregatePlacements {
+        let mut out = AggregatePlacements::default();
+        let mut total_placement = 0;
+
+        for p in performances {
+            let placement = match p.stats.placement {
+                Some(e) => e,
+                None => continue,
+            };
+
+            out.total_activities += 1;
+            total_placement += placement;
+
+            if p.stats.standing == Standing::Victory {
+                out.wins += 1;
+            }
+
+            if out.best_placement == 0 || placement < out.best_placement {
+                out.best_placement = placement;
+            }
+
+            let index = (placement.max(1) - 1) as usize;
+            if out.placements.len() <= index {
+                out.placements.resize(index + 1, 0);
+            }
+            out.placements[index] += 1;
+        }
+
+        out.win_rate = calculate_percent(out.wins, out.total_activities);
+        out.average_placement = if out.total_activities == 0 {
+            0.0
+        } else {
+            total_placement as f32 / out.total_activities as f32
+        };
+
+        out
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct RoundScoreStat {
+    pub results: RoundResults,
+    pub count: u32,
+    pub mercy_count: u32,
+}
+
+/// Aggregate round results for activities from round based modes. Activities
+/// from other modes are ignored.
+#[derive(Debug, Default)]
+pub struct AggregateRoundResults {
+    pub total_activities: u32,
+    pub wins: u32,
+    pub losses: u32,
+
+    pub rounds_won: u32,
+    pub rounds_lost: u32,
+    pub round_win_rate: f32,
+
+    //games won where the opponent won at least one round, and games lost
+    //where the team won at least one round. The pgcr only includes the final
+    //score for each team, and not the result of each round, so games won after
+    //losing the first round(s) can not be determined.
+    pub wins_conceding_rounds: u32,
+    pub losses_winning_rounds: u32,
+
+    pub sweeps_won: u32,
+    pub sweeps_lost: u32,
+    pub total_mercy: u32,
+
+    //final round scores, sorted by most common
+    pub scores: Vec<RoundScoreStat>,
+}
+
+impl AggregateRoundResults {
+    pub fn with_performances(
+        performances: &[CruciblePlayerActivityPerformance],
+    ) -> AggregateRoundResults {
+        let mut out = AggregateRoundResults::default();
+        let mut score_hash: HashMap<RoundResults, RoundScoreStat> =
+            HashMap::new();
+
+        for p in performances {
+            let results = match p.get_round_results() {
+                Some(e) => e,
+                None => continue,
+            };
+
+            let is_mercy = p.performance.stats.completion_reason
+                == CompletionReason::Mercy;
+
+            out.total_activities += 1;
+            out.rounds_won += results.won;
+            out.rounds_lost += results.lost;
+
+            match p.performance.stats.standing {
+                Standing::Victory => {
+                    out.wins += 1;
+                    if results.lost > 0 {
+                        out.wins_conceding_rounds += 1;
+                    }
+
+                    if results.is_sweep() {
+                        out.sweeps_won += 1;
+                    }
+                }
+                Standing::Defeat => {
+                    out.losses += 1;
+                    if results.won > 0 {
+                        out.losses_winning_rounds += 1;
+                    }
+
+                    if results.is_sweep() {
+                        out.sweeps_lost += 1;
+                    }
+                }
+                Standing::Unknown => (),
+            };
+
+            if is_mercy {
+                out.total_mercy += 1;
+            }
+
+            let stat =
+                score_hash.entry(results).or_insert_with(|| RoundScoreStat {
+                    results,
+                    count: 0,
+                    mercy_count: 0,
+                });
+
+            stat.count += 1;
+            if is_mercy {
+                stat.mercy_count += 1;
+            }
+        }
+
+        out.round_win_rate =
+            calculate_percent(out.rounds_won, out.rounds_won + out.rounds_lost);
+
+        let mut scores: Vec<RoundScoreStat> =
+            score_hash.into_iter().map(|e| e.1).collect();
+        scores.sort_by(|a, b| {
+            b.count
+                .cmp(&a.count)
+                .then(b.results.won.cmp(&a.results.won))
+                .then(a.results.lost.cmp(&b.results.lost))
+        });
+        out.scores = scores;
+
+        out
+    }
+}
+
 #[derive(Debug, Default)]
 pub struct ExtendedCruciblePlayerActivityPerformances {
     pub precision_kills: u32,
@@ -460,3 +1206,62 @@ pub struct ActivityDetail {
     pub director_activity_hash: u32,
     pub reference_id: u32,
 }
+
+#[derive(Debug, Clone)]
+pub struct Milestone {
+    pub milestone_type: MilestoneType,
+    pub value: f32,
+    pub previous_value: f32,
+
+    //historical stats id for the medal (only set for FirstMedal)
+    pub medal_id: Option<String>,
+
+    pub activity_index_id: u32,
+    pub reference_id: u32,
+    pub mode: Mode,
+    pub period: DateTime<Utc>,
+}
+
+impl Milestone {
+    /// Returns a human readable description of the milestone, i.e.
+    /// "New personal best: 34 kills on Javelin-4"
+    pub async fn get_description(
+        &self,
+        manifest: &mut ManifestInterface,
+    ) -> String {
+        let map_name =
+            match manifest.get_activity_definition(self.reference_id).await {
+                Ok(Some(e)) => e.display_properties.name,
+                _ => "Unknown".to_string(),
+            };
+
+        let value = if self.milestone_type.is_ratio() {
+            format_f32(self.value, 2)
+        } else {
+            format!("{}", self.value as u32)
+        };
+
+        match self.milestone_type {
+            MilestoneType::LongestWinStreak => format!(
+                "New longest win streak: {} wins (ended on {})",
+                value, map_name
+            ),
+            MilestoneType::FirstMedal => {
+                let medal_id = self.medal_id.clone().unwrap_or_default();
+                let medal_name = match manifest
+                    .get_historical_stats_definition(&medal_id)
+                    .await
+                {
+                    Ok(Some(e)) => e.name,
+                    _ => medal_id,
+                };
+
+                format!("First time medal: {} on {}", medal_name, map_name)
+            }
+            _ => format!(
+                "New personal best: {} {} on {}",
+                value, self.milestone_type, map_name
+            ),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+///Languages the Destiny 2 manifest is available in
+#[derive(PartialEq, Clone, Copy, Debug, Hash, Eq)]
+pub enum Language {
+    English,
+    French,
+    Spanish,
+    SpanishMexico,
+    German,
+    Italian,
+    Japanese,
+    PortugueseBrazil,
+    Russian,
+    Polish,
+    Korean,
+    ChineseTraditional,
+    ChineseSimplified,
+}
+
+impl Language {
+    /// Returns the language code used by the Destiny 2 API
+    pub fn to_code(&self) -> &'static str {
+        match self {
+            Language::English => "en",
+            Language::French => "fr",
+            Language::Spanish => "es",
+            Language::SpanishMexico => "es-mx",
+            Language::German => "de",
+            Language::Italian => "it",
+            Language::Japanese => "ja",
+            Language::PortugueseBrazil => "pt-br",
+            Language::Russian => "ru",
+            Language::Polish => "pl",
+            Language::Korean => "ko",
+            Language::ChineseTraditional => "zh-cht",
+            Language::ChineseSimplified => "zh-chs",
+        }
+    }
+}
+
+impl FromStr for Language {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        //wrap in String so we can convert to lower case
+        let s = String::from(s).to_lowercase();
+
+        //get a slice to get a &str for the match
+        match &s[..] {
+            "en" => Ok(Language::English),
+            "fr" => Ok(Language::French),
+            "es" => Ok(Language::Spanish),
+            "es-mx" => Ok(Language::SpanishMexico),
+            "de" => Ok(Language::German),
+            "it" => Ok(Language::Italian),
+            "ja" => Ok(Language::Japanese),
+            "pt-br" => Ok(Language::PortugueseBrazil),
+            "ru" => Ok(Language::Russian),
+            "pl" => Ok(Language::Polish),
+            "ko" => Ok(Language::Korean),
+            "zh-cht" => Ok(Language::ChineseTraditional),
+            "zh-chs" => Ok(Language::ChineseSimplified),
+            _ => Err("Unknown language code"),
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out = match self {
+            Language::English => "English",
+            Language::French => "French",
+            Language::Spanish => "Spanish",
+            Language::SpanishMexico => "Spanish (Mexico)",
+            Language::German => "German",
+            Language::Italian => "Italian",
+            Language::Japanese => "Japanese",
+            Language::PortugueseBrazil => "Portuguese (Brazil)",
+            Language::Russian => "Russian",
+            Language::Polish => "Polish",
+            Language::Korean => "Korean",
+            Language::ChineseTraditional => "Chinese (Traditional)",
+            Language::ChineseSimplified => "Chinese (Simplified)",
+        };
+
+        write!(f, "{}", out)
+    }
+}
@@ -21,6 +21,7 @@
 */
 
 use serde_repr::{Deserialize_repr, Serialize_repr};
+use std::fmt;
 
 #[derive(PartialEq, Debug, Clone, Deserialize_repr, Serialize_repr)]
 #[repr(u32)]
@@ -48,3 +49,20 @@ impl MedalTier {
         }
     }
 }
+
+impl fmt::Display for MedalTier {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out = match self {
+            MedalTier::Tier1 => "Tier 1",
+            MedalTier::Tier2 => "Tier 2",
+            MedalTier::Tier3 => "Tier 3",
+            MedalTier::Tier4 => "Tier 4",
+            MedalTier::Tier5 => "Tier 5",
+            MedalTier::Tier6 => "Tier 6",
+            MedalTier::Tier7 => "Tier 7",
+            MedalTier::Unknown => "Unknown",
+        };
+
+        write!(f, "{}", out)
+    }
+}
@@ -0,0 +1,82 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fmt;
+
+/// Type of personal milestone detected for a member after a sync
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+#[repr(u32)]
+pub enum MilestoneType {
+    MostKills = 0,
+    HighestEfficiency = 1,
+    HighestKillsDeathsRatio = 2,
+    MostOpponentsDefeated = 3,
+    MostMedals = 4,
+    MostPrecisionKills = 5,
+    LongestWinStreak = 6,
+    FirstMedal = 7,
+    Unknown = 255,
+}
+
+impl MilestoneType {
+    pub fn to_id(&self) -> u32 {
+        *self as u32
+    }
+
+    pub fn from_id(id: u32) -> MilestoneType {
+        match id {
+            0 => MilestoneType::MostKills,
+            1 => MilestoneType::HighestEfficiency,
+            2 => MilestoneType::HighestKillsDeathsRatio,
+            3 => MilestoneType::MostOpponentsDefeated,
+            4 => MilestoneType::MostMedals,
+            5 => MilestoneType::MostPrecisionKills,
+            6 => MilestoneType::LongestWinStreak,
+            7 => MilestoneType::FirstMedal,
+            _ => MilestoneType::Unknown,
+        }
+    }
+
+    /// whether the milestone value is a ratio (and not a count)
+    pub fn is_ratio(&self) -> bool {
+        *self == MilestoneType::HighestEfficiency
+            || *self == MilestoneType::HighestKillsDeathsRatio
+    }
+}
+
+impl fmt::Display for MilestoneType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out = match self {
+            MilestoneType::MostKills => "kills",
+            MilestoneType::HighestEfficiency => "efficiency",
+            MilestoneType::HighestKillsDeathsRatio => "K/D",
+            MilestoneType::MostOpponentsDefeated => "opponents defeated",
+            MilestoneType::MostMedals => "medals",
+            MilestoneType::MostPrecisionKills => "precision kills",
+            MilestoneType::LongestWinStreak => "win streak",
+            MilestoneType::FirstMedal => "first medal",
+            MilestoneType::Unknown => "unknown",
+        };
+
+        write!(f, "{}", out)
+    }
+}
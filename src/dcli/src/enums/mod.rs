@@ -23,7 +23,9 @@
 pub mod character;
 pub mod completionreason;
 pub mod itemtype;
+pub mod language;
 pub mod medaltier;
+pub mod milestonetype;
 pub mod mode;
 pub mod moment;
 pub mod platform;
@@ -205,6 +205,12 @@ impl Mode {
         *self == Mode::Rumble || *self == Mode::PrivateMatchesRumble
     }
 
+    /// Whether the mode is a free for all mode, where players are ranked by
+    /// placement instead of winning or losing.
+    pub fn is_free_for_all(&self) -> bool {
+        self.is_rumble() || *self == Mode::Scorched
+    }
+
     pub fn is_nightfall(&self) -> bool {
         *self == Mode::Nightfall
             || *self == Mode::HeroicNightfall
@@ -256,6 +262,32 @@ impl Mode {
             || *self == Mode::Momentum
     }
 
+    /// Whether light level advantages are enabled in the mode.
+    pub fn is_light_level_enabled(&self) -> bool {
+        *self == Mode::IronBanner
+            || *self == Mode::IronBannerControl
+            || *self == Mode::IronBannerClash
+            || *self == Mode::IronBannerSupremacy
+            || *self == Mode::TrialsOfOsiris
+            || *self == Mode::TrialsOfTheNine
+            || *self == Mode::TrialsCountdown
+            || *self == Mode::TrialsSurvival
+    }
+
+    /// Whether the mode is played in rounds, in which case the team score is
+    /// the number of rounds won.
+    pub fn is_round_based(&self) -> bool {
+        *self == Mode::Elimination
+            || *self == Mode::TrialsOfOsiris
+            || *self == Mode::TrialsOfTheNine
+            || *self == Mode::TrialsCountdown
+            || *self == Mode::TrialsSurvival
+            || *self == Mode::Survival
+            || *self == Mode::PrivateMatchesSurvival
+            || *self == Mode::Countdown
+            || *self == Mode::PrivateMatchesCountdown
+    }
+
     pub fn is_private(&self) -> bool {
         *self == Mode::PrivateMatchesAll
             || *self == Mode::PrivateMatchesClash
@@ -23,18 +23,35 @@
 use std::fmt;
 use std::str::FromStr;
 
-use chrono::prelude::*;
 use chrono::{DateTime, Duration, Utc};
 
 use crate::{
     error::Error,
+    manifestinterface::ManifestInterface,
+    seasons::{Season, Seasons},
     utils::{
         get_destiny2_launch_date, get_last_daily_reset, get_last_friday_reset,
         get_last_weekly_reset,
     },
 };
 
-#[derive(PartialEq, Debug, Copy, Clone)]
+/// Identifies a season by either its number, or its name
+#[derive(PartialEq, Debug, Clone)]
+pub enum SeasonSelection {
+    Number(u32),
+    Name(String),
+}
+
+impl fmt::Display for SeasonSelection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SeasonSelection::Number(e) => write!(f, "season {}", e),
+            SeasonSelection::Name(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum Moment {
     Now,
     Daily,
@@ -52,24 +69,20 @@ pub enum Moment {
     AllTime,
     Custom,
 
-    Launch,
-    CurseOfOsiris,
-    Warmind,
-    SeasonOfTheOutlaw,
-    SeasonOfTheForge,
-    SeasonOfTheDrifter,
-    SeasonOfOpulence,
-    SeasonOfTheUndying,
-    SeasonOfDawn,
-    SeasonOfTheWorthy,
-    SeasonOfArrivals,
-    SeasonOfTheHunt,
-    SeasonOfTheChosen,
+    //season moments are the start of the season, and are loaded from the
+    //manifest
+    Season(SeasonSelection),
+    CurrentSeason,
+    PreviousSeason,
 }
 
 impl Moment {
-    pub fn get_date_time(&self) -> DateTime<Utc> {
-        match self {
+    /// Returns the date / time for the moment, or None for moments which
+    /// cant be determined without additional data. Custom moments are
+    /// specified separately, and season moments are determined by
+    /// DateTimePeriod::with_moments.
+    pub fn get_date_time(&self) -> Option<DateTime<Utc>> {
+        let out = match self {
             Moment::Now => Utc::now(),
             Moment::Daily => get_last_daily_reset(),
             Moment::NextDaily => get_last_daily_reset() + Duration::days(1),
@@ -84,27 +97,44 @@ impl Moment {
             Moment::Month => Utc::now() - Duration::days(30),
             Moment::NextMonth => Utc::now() + Duration::days(30),
             Moment::AllTime => get_destiny2_launch_date(),
-            Moment::Custom => Utc.ymd(0, 0, 0).and_hms(0, 0, 0),
-
-            Moment::Launch => Utc.ymd(2017, 9, 6).and_hms(0, 0, 1),
-            Moment::CurseOfOsiris => Utc.ymd(2017, 12, 5).and_hms(18, 0, 0),
-            Moment::Warmind => Utc.ymd(2018, 5, 8).and_hms(18, 0, 0),
-            Moment::SeasonOfTheOutlaw => Utc.ymd(2018, 9, 4).and_hms(18, 0, 0),
-            Moment::SeasonOfTheForge => Utc.ymd(2018, 12, 4).and_hms(18, 0, 0),
-            Moment::SeasonOfTheDrifter => Utc.ymd(2019, 3, 5).and_hms(18, 0, 0),
-            Moment::SeasonOfOpulence => Utc.ymd(2019, 6, 4).and_hms(18, 0, 0),
-            Moment::SeasonOfTheUndying => {
-                Utc.ymd(2019, 10, 1).and_hms(18, 0, 0)
-            }
-            Moment::SeasonOfDawn => Utc.ymd(2019, 12, 10).and_hms(18, 0, 0),
-            Moment::SeasonOfTheWorthy => Utc.ymd(2020, 3, 10).and_hms(18, 0, 0),
-            Moment::SeasonOfArrivals => Utc.ymd(2020, 6, 9).and_hms(18, 0, 0),
-            Moment::SeasonOfTheHunt => Utc.ymd(2020, 11, 10).and_hms(18, 0, 0),
-            Moment::SeasonOfTheChosen => Utc.ymd(2021, 2, 9).and_hms(18, 0, 0),
-        }
+
+            Moment::Custom
+            | Moment::Season(_)
+            | Moment::CurrentSeason
+            | Moment::PreviousSeason => return None,
+        };
+
+        Some(out)
+    }
+
+    /// Returns whether the moment is a season moment, which requires the
+    /// manifest to determine its date / time.
+    pub fn is_season(&self) -> bool {
+        matches!(
+            self,
+            Moment::Season(_) | Moment::CurrentSeason | Moment::PreviousSeason
+        )
     }
 }
 
+//season moments which used to have hard coded dates. We keep these
+//so existing scripts continue to work, but now look them up by season number.
+const LEGACY_SEASON_MOMENTS: [(&str, u32); 13] = [
+    ("launch", 1),
+    ("curse_of_osiris", 2),
+    ("warmind", 3),
+    ("season_of_the_outlaw", 4),
+    ("season_of_the_forge", 5),
+    ("season_of_the_drifter", 6),
+    ("season_of_opulence", 7),
+    ("season_of_the_undying", 8),
+    ("season_of_dawn", 9),
+    ("season_of_the_worthy", 10),
+    ("season_of_arrivals", 11),
+    ("season_of_the_hunt", 12),
+    ("season_of_the_chosen", 13),
+];
+
 impl FromStr for Moment {
     type Err = &'static str;
 
@@ -130,21 +160,30 @@ impl FromStr for Moment {
             "all_time" => Ok(Moment::AllTime),
             "custom" => Ok(Moment::Custom),
 
-            "launch" => Ok(Moment::Launch),
-            "curse_of_osiris" => Ok(Moment::CurseOfOsiris),
-            "warmind" => Ok(Moment::Warmind),
-            "season_of_the_outlaw" => Ok(Moment::SeasonOfTheOutlaw),
-            "season_of_the_forge" => Ok(Moment::SeasonOfTheForge),
-            "season_of_the_drifter" => Ok(Moment::SeasonOfTheDrifter),
-            "season_of_opulence" => Ok(Moment::SeasonOfOpulence),
-            "season_of_the_undying" => Ok(Moment::SeasonOfTheUndying),
-            "season_of_dawn" => Ok(Moment::SeasonOfDawn),
-            "season_of_the_worthy" => Ok(Moment::SeasonOfTheWorthy),
-            "season_of_arrivals" => Ok(Moment::SeasonOfArrivals),
-            "season_of_the_hunt" => Ok(Moment::SeasonOfTheHunt),
-            "season_of_the_chosen" => Ok(Moment::SeasonOfTheChosen),
-
-            _ => Err("Unknown Moment type"),
+            "current_season" => Ok(Moment::CurrentSeason),
+            "previous_season" => Ok(Moment::PreviousSeason),
+
+            _ => {
+                if let Some(e) = s.strip_prefix("season:") {
+                    let e = e.trim();
+                    if e.is_empty() {
+                        return Err("Season name or number required");
+                    }
+
+                    return match e.parse::<u32>() {
+                        Ok(n) => Ok(Moment::Season(SeasonSelection::Number(n))),
+                        Err(_) => Ok(Moment::Season(SeasonSelection::Name(
+                            e.to_string(),
+                        ))),
+                    };
+                }
+
+                LEGACY_SEASON_MOMENTS
+                    .iter()
+                    .find(|(name, _)| *name == s)
+                    .map(|(_, n)| Moment::Season(SeasonSelection::Number(*n)))
+                    .ok_or("Unknown Moment type")
+            }
         }
     }
 }
@@ -169,19 +208,9 @@ impl fmt::Display for Moment {
 
             Moment::Custom => "custom",
 
-            Moment::Launch => "launch",
-            Moment::CurseOfOsiris => "Curse of Osiris",
-            Moment::Warmind => "Warmind",
-            Moment::SeasonOfTheOutlaw => "Season of the Outlaw",
-            Moment::SeasonOfTheForge => "Season of the Forge",
-            Moment::SeasonOfTheDrifter => "Season of the Drifter",
-            Moment::SeasonOfOpulence => "Season of Opulence",
-            Moment::SeasonOfTheUndying => "Season of the Undying",
-            Moment::SeasonOfDawn => "Season of Dawn",
-            Moment::SeasonOfTheWorthy => "Season of the Worthy",
-            Moment::SeasonOfArrivals => "Season of Arrivals",
-            Moment::SeasonOfTheHunt => "Season of the Hunt",
-            Moment::SeasonOfTheChosen => "Season of the Chosen",
+            Moment::Season(e) => return write!(f, "{}", e),
+            Moment::CurrentSeason => "current season",
+            Moment::PreviousSeason => "previous season",
         };
 
         write!(f, "{}", out)
@@ -225,4 +254,77 @@ impl DateTimePeriod {
 
         Ok(DateTimePeriod { start, end })
     }
+
+    /// Creates a period from the moments specified by the user. Custom times
+    /// are required for custom moments. If end_moment isnt specified, periods
+    /// starting at a season moment end when the season ends (or now if it
+    /// hasnt ended yet), and all other periods end now. Season definitions
+    /// are only loaded from the manifest if a season moment is used.
+    pub async fn with_moments(
+        moment: &Moment,
+        custom_time: Option<DateTime<Utc>>,
+        end_moment: Option<&Moment>,
+        end_custom_time: Option<DateTime<Utc>>,
+        manifest: &mut ManifestInterface,
+    ) -> Result<DateTimePeriod, Error> {
+        let seasons = if moment.is_season()
+            || matches!(end_moment, Some(e) if e.is_season())
+        {
+            Some(Seasons::with_manifest(manifest).await?)
+        } else {
+            None
+        };
+
+        let start = get_moment_date_time(moment, custom_time, &seasons)?;
+
+        let end = match end_moment {
+            Some(e) => get_moment_date_time(e, end_custom_time, &seasons)?,
+            None => {
+                let now = Utc::now();
+                match get_moment_season(moment, &seasons) {
+                    Some(season) => match season?.end {
+                        Some(e) if e < now => e,
+                        _ => now,
+                    },
+                    None => now,
+                }
+            }
+        };
+
+        DateTimePeriod::with_start_end_time(start, end)
+    }
+}
+
+//returns the season for season moments, or None for all other moments
+fn get_moment_season<'a>(
+    moment: &Moment,
+    seasons: &'a Option<Seasons>,
+) -> Option<Result<&'a Season, Error>> {
+    if !moment.is_season() {
+        return None;
+    }
+
+    Some(match seasons {
+        Some(e) => e.get_season_for_moment(moment),
+        None => Err(Error::InvalidParameters),
+    })
+}
+
+fn get_moment_date_time(
+    moment: &Moment,
+    custom_time: Option<DateTime<Utc>>,
+    seasons: &Option<Seasons>,
+) -> Result<DateTime<Utc>, Error> {
+    if *moment == Moment::Custom {
+        return custom_time.ok_or(Error::InvalidParameters);
+    }
+
+    if let Some(e) = moment.get_date_time() {
+        return Ok(e);
+    }
+
+    match get_moment_season(moment, seasons) {
+        Some(season) => Ok(season?.start),
+        None => Err(Error::InvalidParameters),
+    }
 }
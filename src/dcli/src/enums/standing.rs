@@ -26,6 +26,9 @@ use crate::enums::mode::Mode;
 
 pub const STANDING_UNKNOWN_MAGIC_NUMBER: u32 = 2325;
 
+/// Default lowest placement in free for all modes which counts as a victory
+pub const DEFAULT_FFA_WIN_PLACEMENT: u32 = 3;
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[repr(u32)]
 pub enum Standing {
@@ -50,22 +53,30 @@ impl Standing {
         }
     }
 
-    pub fn from_mode(value: u32, mode: &Mode) -> Standing {
-        if value == 0 {
-            return Standing::Victory;
-        } else if value == STANDING_UNKNOWN_MAGIC_NUMBER {
+    /// Returns the standing for a free for all placement (starting at 1),
+    /// where any placement up to and including win_placement is a victory.
+    pub fn from_placement(placement: u32, win_placement: u32) -> Standing {
+        if placement <= win_placement {
+            Standing::Victory
+        } else {
+            Standing::Defeat
+        }
+    }
+
+    /// Returns the standing for the standing value returned by the api for an
+    /// activity in the mode. For free for all modes the api returns the
+    /// placement (starting at 0), and any placement up to and including
+    /// win_placement is a victory.
+    pub fn from_mode(value: u32, mode: &Mode, win_placement: u32) -> Standing {
+        if value == STANDING_UNKNOWN_MAGIC_NUMBER {
             return Standing::Unknown;
         }
 
-        if value > 0 {
-            if mode == &Mode::Rumble && value > 2 {
-                Standing::Defeat
-            } else {
-                Standing::Victory
-            }
-        } else {
-            Standing::Victory
+        if mode.is_free_for_all() {
+            return Standing::from_placement(value + 1, win_placement);
         }
+
+        Standing::from_value(value)
     }
 }
 
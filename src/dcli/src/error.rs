@@ -65,6 +65,10 @@ pub enum Error {
     CharacterDoesNotExist,
     ActivityNotFound,
     DateTimePeriodOrder,
+    ManifestLanguageNotAvailable,
+    ManifestInvalid { description: String },
+    SeasonNotFound { description: String },
+    InvalidImage { description: String },
 }
 
 impl Display for Error {
@@ -162,6 +166,18 @@ impl Display for Error {
             Error::DateTimePeriodOrder  => {
                 write!(f, "Start date must be before end date.")
             },
+            Error::ManifestLanguageNotAvailable  => {
+                write!(f, "Manifest is not available for the specified language.")
+            },
+            Error::ManifestInvalid { description } => {
+                write!(f, "Invalid manifest database. {}", description)
+            },
+            Error::SeasonNotFound { description } => {
+                write!(f, "Could not find season in manifest. {}", description)
+            },
+            Error::InvalidImage { description } => {
+                write!(f, "Invalid image data. {}", description)
+            },
         }
     }
 }
@@ -0,0 +1,548 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use serde_derive::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::apiutils::RESOURCE_BASE_URL;
+use crate::error::Error;
+use crate::utils::print_verbose;
+
+pub const IMAGE_CACHE_DIR_NAME: &str = "images";
+const IMAGE_INDEX_FILE_NAME: &str = "index.json";
+const OBJECTS_DIR_NAME: &str = "objects";
+const IMAGE_TIMEOUT: u64 = 30; //seconds
+
+/// Entry in the index, mapping an image path to the cached image data
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct ImageEntry {
+    //sha256 of the image data
+    hash: String,
+    extension: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+struct ImageIndex {
+    images: HashMap<String, ImageEntry>,
+}
+
+#[derive(Default, Debug)]
+pub struct ImageCacheResults {
+    /// Number of images which were downloaded
+    pub retrieved: u32,
+
+    /// Number of images which were already in the cache
+    pub cached: u32,
+
+    /// Images which could not be retrieved, along with the reason
+    pub failed: Vec<(String, Error)>,
+}
+
+/// Local cache of images (icons, emblems, PGCR images) referenced by the API
+/// and manifest, so they can be used without a network connection.
+///
+/// Images are stored by the sha256 hash of their contents (i.e.
+/// images/objects/ab/abcd...png), so the same image referenced by multiple
+/// paths is only stored once. An index maps each image path to its data.
+pub struct ImageCache {
+    dir: PathBuf,
+    base_url: String,
+    index: ImageIndex,
+    client: Client,
+    verbose: bool,
+}
+
+impl ImageCache {
+    /// Opens the image cache stored in the specified data directory, retrieving
+    /// images from bungie.net.
+    pub fn new(data_dir: &Path, verbose: bool) -> Result<ImageCache, Error> {
+        ImageCache::with_base_url(data_dir, RESOURCE_BASE_URL, verbose)
+    }
+
+    /// Opens the image cache stored in the specified data directory, retrieving
+    /// images relative to the specified base url (i.e. a local server).
+    pub fn with_base_url(
+        data_dir: &Path,
+        base_url: &str,
+        verbose: bool,
+    ) -> Result<ImageCache, Error> {
+        let dir = data_dir.join(IMAGE_CACHE_DIR_NAME);
+        fs::create_dir_all(dir.join(OBJECTS_DIR_NAME))?;
+
+        let index_path = dir.join(IMAGE_INDEX_FILE_NAME);
+        let index: ImageIndex = if index_path.exists() {
+            serde_json::from_str(&fs::read_to_string(&index_path)?)?
+        } else {
+            ImageIndex::default()
+        };
+
+        let client = Client::builder()
+            .timeout(std::time::Duration::from_secs(IMAGE_TIMEOUT))
+            .build()?;
+
+        Ok(ImageCache {
+            dir,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            index,
+            client,
+            verbose,
+        })
+    }
+
+    /// Returns the key used to store the image in the index. Images can be
+    /// referenced either by their full bungie.net url, or by their path, and
+    /// both are stored as the path.
+    fn get_key(&self, image: &str) -> String {
+        for base in [RESOURCE_BASE_URL, self.base_url.as_str()].iter() {
+            if let Some(e) = image.strip_prefix(base) {
+                if e.starts_with('/') {
+                    return e.to_string();
+                }
+            }
+        }
+
+        image.to_string()
+    }
+
+    fn get_url(&self, key: &str) -> String {
+        if key.starts_with('/') {
+            format!("{}{}", self.base_url, key)
+        } else {
+            key.to_string()
+        }
+    }
+
+    fn get_object_path(&self, entry: &ImageEntry) -> PathBuf {
+        self.dir
+            .join(OBJECTS_DIR_NAME)
+            .join(&entry.hash[..2])
+            .join(format!("{}.{}", entry.hash, entry.extension))
+    }
+
+    /// Returns the path to the cached copy of the image, or None if the image
+    /// has not been cached. Image can be the full url or the path.
+    pub fn local_path(&self, image: &str) -> Option<PathBuf> {
+        let entry = self.index.images.get(&self.get_key(image))?;
+        let path = self.get_object_path(entry);
+
+        if path.exists() {
+            Some(path)
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path to the cached copy of the image, downloading it if it
+    /// has not been cached.
+    pub async fn retrieve(&mut self, image: &str) -> Result<PathBuf, Error> {
+        if let Some(e) = self.local_path(image) {
+            return Ok(e);
+        }
+
+        let path = self.download(image).await?;
+        self.save_index()?;
+
+        Ok(path)
+    }
+
+    /// Downloads all of the images which have not already been cached. Images
+    /// which fail are returned in the results, and do not stop the other
+    /// images from being downloaded.
+    pub async fn retrieve_all(
+        &mut self,
+        images: &[String],
+    ) -> Result<ImageCacheResults, Error> {
+        let mut results = ImageCacheResults::default();
+
+        let mut keys: HashSet<String> = HashSet::new();
+        for image in images {
+            let key = self.get_key(image);
+            if !keys.insert(key.clone()) {
+                continue;
+            }
+
+            if self.local_path(&key).is_some() {
+                results.cached += 1;
+                continue;
+            }
+
+            match self.download(&key).await {
+                Ok(_) => results.retrieved += 1,
+                Err(e) => results.failed.push((key, e)),
+            }
+        }
+
+        self.save_index()?;
+
+        Ok(results)
+    }
+
+    async fn download(&mut self, image: &str) -> Result<PathBuf, Error> {
+        let key = self.get_key(image);
+        let url = self.get_url(&key);
+
+        print_verbose(&url, self.verbose);
+
+        let response = self.client.get(&url).send().await?;
+
+        if !response.status().is_success() {
+            return Err(Error::InvalidImage {
+                description: format!("{} returned {}", url, response.status()),
+            });
+        }
+
+        if let Some(e) = response.headers().get(CONTENT_TYPE) {
+            let content_type = e.to_str().unwrap_or_default();
+            if !content_type.starts_with("image/") {
+                return Err(Error::InvalidImage {
+                    description: format!(
+                        "{} returned content type {}",
+                        url, content_type
+                    ),
+                });
+            }
+        }
+
+        let expected_length = response.content_length();
+        let data = response.bytes().await?;
+
+        //content length isnt always sent, in which case we just check we got
+        //some data
+        let is_complete = match expected_length {
+            Some(e) => e == data.len() as u64,
+            None => true,
+        };
+
+        if data.is_empty() || !is_complete {
+            return Err(Error::InvalidImage {
+                description: format!("{} returned incomplete data", url),
+            });
+        }
+
+        let extension = Path::new(&key)
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_else(|| "img".to_string());
+
+        let entry = ImageEntry {
+            hash: format!("{:x}", Sha256::digest(&data)),
+            extension,
+        };
+
+        let path = self.get_object_path(&entry);
+
+        //if we already have the data from another path, we dont need to store
+        //it again
+        if !path.exists() {
+            fs::create_dir_all(path.parent().unwrap())?;
+
+            //write to a temp file first, so a partial write is never cached
+            let tmp_path = path.with_extension("tmp");
+            fs::write(&tmp_path, &data)?;
+            fs::rename(&tmp_path, &path)?;
+        }
+
+        self.index.images.insert(key, entry);
+
+        Ok(path)
+    }
+
+    /// Checks that all of the cached images exist and match their hash. Images
+    /// which are missing or corrupt are removed from the cache (and will be
+    /// downloaded again the next time they are retrieved). Returns the number
+    /// of images which were removed.
+    pub fn verify(&mut self) -> Result<u32, Error> {
+        let mut valid: HashMap<String, bool> = HashMap::new();
+        let mut invalid_keys: Vec<String> = Vec::new();
+
+        for (key, entry) in self.index.images.iter() {
+            let is_valid = match valid.get(&entry.hash) {
+                Some(e) => *e,
+                None => {
+                    let path = self.get_object_path(entry);
+                    let is_valid = match fs::read(&path) {
+                        Ok(data) => {
+                            format!("{:x}", Sha256::digest(&data)) == entry.hash
+                        }
+                        Err(_) => false,
+                    };
+
+                    if !is_valid && path.exists() {
+                        fs::remove_file(&path)?;
+                    }
+
+                    valid.insert(entry.hash.clone(), is_valid);
+                    is_valid
+                }
+            };
+
+            if !is_valid {
+                invalid_keys.push(key.clone());
+            }
+        }
+
+        for key in invalid_keys.iter() {
+            self.index.images.remove(key);
+        }
+
+        self.save_index()?;
+
+        Ok(invalid_keys.len() as u32)
+    }
+
+    fn save_index(&self) -> Result<(), Error> {
+        let index_path = self.dir.join(IMAGE_INDEX_FILE_NAME);
+        let tmp_path = index_path.with_extension("tmp");
+
+        fs::write(&tmp_path, serde_json::to_string(&self.index)?)?;
+        fs::rename(&tmp_path, &index_path)?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::io::{Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    const IMAGE_DATA: &[u8] = b"\x89PNG\r\n\x1a\nnot really a png";
+    const OTHER_IMAGE_DATA: &[u8] = b"\x89PNG\r\n\x1a\nanother image";
+
+    fn respond(stream: &mut std::net::TcpStream) {
+        let mut request: Vec<u8> = Vec::new();
+        let mut buf = [0; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+            match stream.read(&mut buf) {
+                Ok(0) | Err(_) => return,
+                Ok(n) => request.extend_from_slice(&buf[..n]),
+            }
+        }
+
+        let request = String::from_utf8_lossy(&request);
+        let path = request.split(' ').nth(1).unwrap_or_default();
+
+        //(status, content type, content length, body)
+        let (status, content_type, length, body): (&str, &str, usize, &[u8]) =
+            match path {
+                "/a.png" | "/copy_of_a.png" => {
+                    ("200 OK", "image/png", IMAGE_DATA.len(), IMAGE_DATA)
+                }
+                "/b.png" => (
+                    "200 OK",
+                    "image/png",
+                    OTHER_IMAGE_DATA.len(),
+                    OTHER_IMAGE_DATA,
+                ),
+                "/page.png" => ("200 OK", "text/html", 6, b"<html>"),
+                "/short.png" => ("200 OK", "image/png", 100, &IMAGE_DATA[..10]),
+                _ => ("404 Not Found", "text/plain", 9, b"not found"),
+            };
+
+        let header = format!(
+            "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+            status, content_type, length
+        );
+
+        let _ = stream.write_all(header.as_bytes());
+        let _ = stream.write_all(body);
+    }
+
+    //local stand in for bungie.net. Returns the base url.
+    fn start_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let address = listener.local_addr().unwrap();
+
+        thread::spawn(move || {
+            for mut stream in listener.incoming().flatten() {
+                respond(&mut stream);
+            }
+        });
+
+        format!("http://{}", address)
+    }
+
+    fn create_data_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!(
+            "dcli_imagecache_test_{}_{}",
+            name,
+            std::process::id()
+        ));
+
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+
+        dir
+    }
+
+    fn count_objects(data_dir: &Path) -> usize {
+        let objects =
+            data_dir.join(IMAGE_CACHE_DIR_NAME).join(OBJECTS_DIR_NAME);
+
+        fs::read_dir(objects)
+            .unwrap()
+            .map(|e| fs::read_dir(e.unwrap().path()).unwrap().count())
+            .sum()
+    }
+
+    #[tokio::test]
+    async fn download_image() {
+        let base_url = start_server();
+        let data_dir = create_data_dir("download");
+
+        let mut cache =
+            ImageCache::with_base_url(&data_dir, &base_url, false).unwrap();
+
+        assert!(cache.local_path("/a.png").is_none());
+
+        let path = cache.retrieve("/a.png").await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), IMAGE_DATA);
+        assert_eq!(path.extension().unwrap(), "png");
+
+        //full urls and paths are the same image
+        assert_eq!(cache.local_path("/a.png"), Some(path.clone()));
+        assert_eq!(
+            cache.local_path(&format!("{}/a.png", base_url)),
+            Some(path.clone())
+        );
+        assert!(cache.local_path("/b.png").is_none());
+
+        //the index is saved, so the image is found when the cache is reopened
+        let cache =
+            ImageCache::with_base_url(&data_dir, &base_url, false).unwrap();
+        assert_eq!(cache.local_path("/a.png"), Some(path));
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn dedupe_identical_images() {
+        let base_url = start_server();
+        let data_dir = create_data_dir("dedupe");
+
+        let mut cache =
+            ImageCache::with_base_url(&data_dir, &base_url, false).unwrap();
+
+        let images = vec![
+            "/a.png".to_string(),
+            "/copy_of_a.png".to_string(),
+            format!("{}/a.png", base_url),
+        ];
+
+        let results = cache.retrieve_all(&images).await.unwrap();
+        assert_eq!(results.retrieved, 2);
+        assert_eq!(results.cached, 0);
+        assert!(results.failed.is_empty());
+
+        assert_eq!(count_objects(&data_dir), 1);
+        assert_eq!(
+            cache.local_path("/a.png"),
+            cache.local_path("/copy_of_a.png")
+        );
+
+        let results = cache.retrieve_all(&images).await.unwrap();
+        assert_eq!(results.retrieved, 0);
+        assert_eq!(results.cached, 2);
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn reject_invalid_images() {
+        let base_url = start_server();
+        let data_dir = create_data_dir("invalid");
+
+        let mut cache =
+            ImageCache::with_base_url(&data_dir, &base_url, false).unwrap();
+
+        //not an image content type
+        assert!(matches!(
+            cache.retrieve("/page.png").await,
+            Err(Error::InvalidImage { .. })
+        ));
+
+        //body shorter than the content length (the connection is closed
+        //before all of the data is sent)
+        assert!(cache.retrieve("/short.png").await.is_err());
+
+        //not found
+        assert!(matches!(
+            cache.retrieve("/missing.png").await,
+            Err(Error::InvalidImage { .. })
+        ));
+
+        let images = vec!["/page.png".to_string(), "/a.png".to_string()];
+        let results = cache.retrieve_all(&images).await.unwrap();
+        assert_eq!(results.retrieved, 1);
+        assert_eq!(results.failed.len(), 1);
+        assert_eq!(results.failed[0].0, "/page.png");
+
+        assert!(cache.local_path("/page.png").is_none());
+        assert!(cache.local_path("/short.png").is_none());
+        assert_eq!(count_objects(&data_dir), 1);
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn verify_removes_corrupt_images() {
+        let base_url = start_server();
+        let data_dir = create_data_dir("verify");
+
+        let mut cache =
+            ImageCache::with_base_url(&data_dir, &base_url, false).unwrap();
+
+        let images = vec![
+            "/a.png".to_string(),
+            "/copy_of_a.png".to_string(),
+            "/b.png".to_string(),
+        ];
+        cache.retrieve_all(&images).await.unwrap();
+
+        assert_eq!(cache.verify().unwrap(), 0);
+
+        let corrupt_path = cache.local_path("/a.png").unwrap();
+        fs::write(&corrupt_path, b"corrupt").unwrap();
+
+        //both paths referencing the corrupt data are removed
+        assert_eq!(cache.verify().unwrap(), 2);
+        assert!(!corrupt_path.exists());
+        assert!(cache.local_path("/a.png").is_none());
+        assert!(cache.local_path("/copy_of_a.png").is_none());
+        assert!(cache.local_path("/b.png").is_some());
+
+        //and are downloaded again
+        let path = cache.retrieve("/a.png").await.unwrap();
+        assert_eq!(fs::read(&path).unwrap(), IMAGE_DATA);
+
+        fs::remove_dir_all(&data_dir).unwrap();
+    }
+}
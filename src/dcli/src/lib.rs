@@ -30,9 +30,15 @@ pub mod cruciblestats;
 pub mod emblem;
 pub mod enums;
 pub mod error;
+pub mod imagecache;
 pub mod manifest;
+pub mod manifestdiff;
+pub mod manifestexport;
 pub mod manifestinterface;
+pub mod manifestsearch;
+pub mod matchpredictor;
 pub mod output;
 pub mod response;
+pub mod seasons;
 pub mod statscontainer;
 pub mod utils;
@@ -1,8 +1,12 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use serde_derive::{Deserialize, Serialize};
 
 use crate::enums::itemtype::{ItemSubType, ItemType};
 use crate::enums::medaltier::MedalTier;
-use crate::response::utils::prepend_base_url_option;
+use crate::enums::mode::Mode;
+use crate::response::utils::{prepend_base_url_option, str_to_datetime_option};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct DisplayPropertiesData {
@@ -94,6 +98,9 @@ pub struct HistoricalStatsDefinition {
 
     #[serde(rename = "medalTierHash")]
     pub medal_tier: Option<MedalTier>,
+
+    #[serde(default)]
+    pub modes: Vec<u32>,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -113,3 +120,206 @@ pub struct ActivityTypeDefinitionData {
     #[serde(rename = "displayProperties")]
     pub display_properties: DisplayPropertiesData,
 }
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-Seasons-DestinySeasonDefinition.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SeasonDefinitionData {
+    #[serde(rename = "hash")]
+    pub id: u32,
+
+    #[serde(rename = "displayProperties")]
+    pub display_properties: DisplayPropertiesData,
+
+    #[serde(rename = "seasonNumber")]
+    pub season_number: u32,
+
+    #[serde(default)]
+    #[serde(
+        rename = "backgroundImagePath",
+        deserialize_with = "prepend_base_url_option"
+    )]
+    pub background_image_path: Option<String>,
+
+    #[serde(default, skip_serializing)]
+    #[serde(rename = "startDate", deserialize_with = "str_to_datetime_option")]
+    pub start_date: Option<DateTime<Utc>>,
+
+    #[serde(default, skip_serializing)]
+    #[serde(rename = "endDate", deserialize_with = "str_to_datetime_option")]
+    pub end_date: Option<DateTime<Utc>>,
+
+    #[serde(rename = "artifactItemHash")]
+    pub artifact_item_hash: Option<u32>,
+
+    #[serde(rename = "sealPresentationNodeHash")]
+    pub seal_presentation_node_hash: Option<u32>,
+
+    #[serde(rename = "seasonalChallengesPresentationNodeHash")]
+    pub seasonal_challenges_presentation_node_hash: Option<u32>,
+}
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-Records-DestinyRecordTitleBlock.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordTitleInfoData {
+    #[serde(rename = "hasTitle")]
+    pub has_title: bool,
+
+    //keyed by gender (Male, Female)
+    #[serde(default, rename = "titlesByGender")]
+    pub titles_by_gender: HashMap<String, String>,
+
+    #[serde(rename = "gildingTrackingRecordHash")]
+    pub gilding_tracking_record_hash: Option<u32>,
+}
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-Records-DestinyRecordDefinition.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecordDefinitionData {
+    #[serde(rename = "hash")]
+    pub id: u32,
+
+    #[serde(rename = "displayProperties")]
+    pub display_properties: DisplayPropertiesData,
+
+    #[serde(rename = "loreHash")]
+    pub lore_hash: Option<u32>,
+
+    #[serde(default, rename = "objectiveHashes")]
+    pub objective_hashes: Vec<u32>,
+
+    #[serde(default, rename = "parentNodeHashes")]
+    pub parent_node_hashes: Vec<u32>,
+
+    #[serde(rename = "titleInfo")]
+    pub title_info: Option<RecordTitleInfoData>,
+
+    #[serde(default, rename = "forTitleGilding")]
+    pub for_title_gilding: bool,
+}
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-Collectibles-DestinyCollectibleDefinition.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CollectibleDefinitionData {
+    #[serde(rename = "hash")]
+    pub id: u32,
+
+    #[serde(rename = "displayProperties")]
+    pub display_properties: DisplayPropertiesData,
+
+    #[serde(default, rename = "sourceString")]
+    pub source_string: String,
+
+    #[serde(rename = "sourceHash")]
+    pub source_hash: Option<u32>,
+
+    #[serde(rename = "itemHash")]
+    pub item_hash: u32,
+
+    #[serde(default, rename = "parentNodeHashes")]
+    pub parent_node_hashes: Vec<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PresentationNodeChildNodeData {
+    #[serde(rename = "presentationNodeHash")]
+    pub presentation_node_hash: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PresentationNodeChildCollectibleData {
+    #[serde(rename = "collectibleHash")]
+    pub collectible_hash: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PresentationNodeChildRecordData {
+    #[serde(rename = "recordHash")]
+    pub record_hash: u32,
+}
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-Presentation-DestinyPresentationNodeChildrenBlock.html
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct PresentationNodeChildrenData {
+    #[serde(default, rename = "presentationNodes")]
+    pub presentation_nodes: Vec<PresentationNodeChildNodeData>,
+
+    #[serde(default)]
+    pub collectibles: Vec<PresentationNodeChildCollectibleData>,
+
+    #[serde(default)]
+    pub records: Vec<PresentationNodeChildRecordData>,
+}
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-Presentation-DestinyPresentationNodeDefinition.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PresentationNodeDefinitionData {
+    #[serde(rename = "hash")]
+    pub id: u32,
+
+    #[serde(rename = "displayProperties")]
+    pub display_properties: DisplayPropertiesData,
+
+    //https://bungie-net.github.io/multi/schema_Destiny-DestinyPresentationNodeType.html
+    #[serde(default, rename = "nodeType")]
+    pub node_type: u32,
+
+    #[serde(rename = "objectiveHash")]
+    pub objective_hash: Option<u32>,
+
+    #[serde(rename = "completionRecordHash")]
+    pub completion_record_hash: Option<u32>,
+
+    #[serde(default)]
+    pub children: PresentationNodeChildrenData,
+
+    #[serde(default, rename = "parentNodeHashes")]
+    pub parent_node_hashes: Vec<u32>,
+}
+
+//https://bungie-net.github.io/multi/schema_Destiny-Definitions-DestinyActivityModeDefinition.html
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ActivityModeDefinitionData {
+    #[serde(rename = "hash")]
+    pub id: u32,
+
+    #[serde(rename = "displayProperties")]
+    pub display_properties: DisplayPropertiesData,
+
+    #[serde(default)]
+    #[serde(
+        rename = "pgcrImage",
+        deserialize_with = "prepend_base_url_option"
+    )]
+    pub pgcr_image: Option<String>,
+
+    //stored as the raw id, so new modes dont break parsing. Use mode() to
+    //get the Mode
+    #[serde(rename = "modeType")]
+    pub mode_type: u32,
+
+    #[serde(default, rename = "isTeamBased")]
+    pub is_team_based: bool,
+
+    #[serde(default, rename = "isAggregateMode")]
+    pub is_aggregate_mode: bool,
+
+    #[serde(default, rename = "parentHashes")]
+    pub parent_hashes: Vec<u32>,
+
+    #[serde(default, rename = "friendlyName")]
+    pub friendly_name: String,
+
+    #[serde(default)]
+    pub order: i32,
+
+    #[serde(default)]
+    pub display: bool,
+}
+
+impl ActivityModeDefinitionData {
+    /// Returns the Mode for the definition, or None if it is not a mode dcli
+    /// knows about.
+    pub fn mode(&self) -> Option<Mode> {
+        Mode::from_id(self.mode_type).ok()
+    }
+}
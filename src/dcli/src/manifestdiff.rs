@@ -0,0 +1,410 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::path::Path;
+use std::str::FromStr;
+
+use futures::TryStreamExt;
+use serde_json::Value;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqliteRow};
+use sqlx::Row;
+use sqlx::{ConnectOptions, Connection, SqliteConnection};
+
+use crate::error::Error;
+
+//top level fields which change between most manifest versions without the
+//definition actually changing, and so are not included when comparing
+const IGNORED_FIELDS: [&str; 1] = ["index"];
+
+#[derive(Debug, Clone)]
+pub struct DefinitionSummary {
+    //hash of the definition, or the key for tables keyed by name (such as
+    //DestinyHistoricalStatsDefinition)
+    pub key: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct FieldChange {
+    //path to the field, i.e. displayProperties.name or perks[2].perkHash
+    pub path: String,
+
+    //json values, or an empty string if the field did not exist
+    pub old_value: String,
+    pub new_value: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ChangedDefinition {
+    pub definition: DefinitionSummary,
+    pub fields: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TableDiff {
+    pub table_name: String,
+    pub added: Vec<DefinitionSummary>,
+    pub removed: Vec<DefinitionSummary>,
+    pub changed: Vec<ChangedDefinition>,
+}
+
+impl TableDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty()
+            && self.removed.is_empty()
+            && self.changed.is_empty()
+    }
+}
+
+/// Definitions which were added, removed or changed between two manifest
+/// versions. Only tables with changes are included.
+#[derive(Debug, Clone, Default)]
+pub struct ManifestDiff {
+    pub tables: Vec<TableDiff>,
+}
+
+impl ManifestDiff {
+    pub fn is_empty(&self) -> bool {
+        self.tables.is_empty()
+    }
+
+    /// Compares the definitions in every table with json and id (or key)
+    /// columns in the old and new manifest databases.
+    pub async fn with_manifests(
+        old_path: &Path,
+        new_path: &Path,
+    ) -> Result<ManifestDiff, Error> {
+        for p in &[old_path, new_path] {
+            if !p.exists() {
+                return Err(Error::IoFileDoesNotExist {
+                    description: format!(
+                        "Manifest path points to non-existent file. {}",
+                        p.display()
+                    ),
+                });
+            }
+        }
+
+        let connection_string = format!("{}", new_path.display());
+        let mut db = SqliteConnectOptions::from_str(&connection_string)?
+            .journal_mode(SqliteJournalMode::Memory)
+            .read_only(true)
+            .connect()
+            .await?;
+
+        sqlx::query("ATTACH DATABASE ? AS old")
+            .bind(format!("{}", old_path.display()))
+            .execute(&mut db)
+            .await?;
+
+        let old_tables = get_definition_tables(&mut db, "old").await?;
+        let new_tables = get_definition_tables(&mut db, "main").await?;
+
+        let mut out = ManifestDiff::default();
+
+        for table in &new_tables {
+            let diff = if old_tables.contains(table) {
+                diff_table(&mut db, table).await?
+            } else {
+                TableDiff {
+                    table_name: table.0.to_string(),
+                    added: get_all_definitions(&mut db, "main", table).await?,
+                    removed: Vec::new(),
+                    changed: Vec::new(),
+                }
+            };
+
+            if !diff.is_empty() {
+                out.tables.push(diff);
+            }
+        }
+
+        for table in &old_tables {
+            if new_tables.contains(table) {
+                continue;
+            }
+
+            let diff = TableDiff {
+                table_name: table.0.to_string(),
+                added: Vec::new(),
+                removed: get_all_definitions(&mut db, "old", table).await?,
+                changed: Vec::new(),
+            };
+
+            if !diff.is_empty() {
+                out.tables.push(diff);
+            }
+        }
+
+        db.close().await?;
+
+        out.tables.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+
+        Ok(out)
+    }
+}
+
+//returns the names of the tables in the schema which have a json column,
+//along with the column the definitions are keyed by (id or key)
+async fn get_definition_tables(
+    db: &mut SqliteConnection,
+    schema: &str,
+) -> Result<Vec<(String, String)>, Error> {
+    let q = format!(
+        r#"
+        SELECT m.name AS name, p.name AS key_column
+        FROM {schema}.sqlite_master AS m
+        JOIN pragma_table_info(m.name, '{schema}') AS p
+        WHERE
+            m.type = 'table' AND
+            p.name IN ('id', 'key') AND
+            (SELECT count(*) FROM pragma_table_info(m.name, '{schema}') AS j
+                WHERE j.name = 'json') = 1
+    "#,
+        schema = schema
+    );
+
+    let mut out: Vec<(String, String)> = Vec::new();
+    let mut rows = sqlx::query(&q).fetch(db);
+    while let Some(row) = rows.try_next().await? {
+        let name: String = row.try_get("name")?;
+        let key_column: String = row.try_get("key_column")?;
+        out.push((name, key_column));
+    }
+
+    Ok(out)
+}
+
+fn get_key(row: &SqliteRow, key_column: &str) -> Result<String, Error> {
+    //ids are stored as signed ints, so we convert back to the api hash
+    if key_column == "id" {
+        let id: i64 = row.try_get("key")?;
+        Ok(format!("{}", id as u32))
+    } else {
+        Ok(row.try_get("key")?)
+    }
+}
+
+fn parse_summary(key: String, json: &str) -> DefinitionSummary {
+    //historical stats definitions dont have display properties
+    let name = serde_json::from_str::<Value>(json)
+        .ok()
+        .and_then(|v| {
+            v.pointer("/displayProperties/name")
+                .or_else(|| v.pointer("/statName"))
+                .and_then(|n| n.as_str())
+                .map(|n| n.to_string())
+        })
+        .unwrap_or_default();
+
+    DefinitionSummary { key, name }
+}
+
+async fn get_all_definitions(
+    db: &mut SqliteConnection,
+    schema: &str,
+    table: &(String, String),
+) -> Result<Vec<DefinitionSummary>, Error> {
+    let (table, key) = table;
+
+    //for some reason sqlx doesnt let you bind table names
+    let q = format!(
+        "SELECT {key} AS key, json FROM {schema}.{table} ORDER BY {key}",
+        key = key,
+        schema = schema,
+        table = table
+    );
+
+    let mut out: Vec<DefinitionSummary> = Vec::new();
+    let mut rows = sqlx::query(&q).fetch(db);
+    while let Some(row) = rows.try_next().await? {
+        let json: &str = row.try_get("json")?;
+        out.push(parse_summary(get_key(&row, key)?, json));
+    }
+
+    Ok(out)
+}
+
+async fn diff_table(
+    db: &mut SqliteConnection,
+    table: &(String, String),
+) -> Result<TableDiff, Error> {
+    let (table, key) = table;
+
+    let mut out = TableDiff {
+        table_name: table.to_string(),
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+
+    //added
+    {
+        let q = format!(
+            r#"
+            SELECT n.{key} as key, n.json as json FROM main.{table} AS n
+            WHERE NOT EXISTS
+                (SELECT 1 FROM old.{table} AS o WHERE o.{key} = n.{key})
+            ORDER BY n.{key}
+        "#,
+            key = key,
+            table = table
+        );
+
+        let mut rows = sqlx::query(&q).fetch(&mut *db);
+        while let Some(row) = rows.try_next().await? {
+            let json: &str = row.try_get("json")?;
+            out.added.push(parse_summary(get_key(&row, key)?, json));
+        }
+    }
+
+    //removed
+    {
+        let q = format!(
+            r#"
+            SELECT o.{key} as key, o.json as json FROM old.{table} AS o
+            WHERE NOT EXISTS
+                (SELECT 1 FROM main.{table} AS n WHERE n.{key} = o.{key})
+            ORDER BY o.{key}
+        "#,
+            key = key,
+            table = table
+        );
+
+        let mut rows = sqlx::query(&q).fetch(&mut *db);
+        while let Some(row) = rows.try_next().await? {
+            let json: &str = row.try_get("json")?;
+            out.removed.push(parse_summary(get_key(&row, key)?, json));
+        }
+    }
+
+    //changed
+    {
+        let q = format!(
+            r#"
+            SELECT n.{key} as key, o.json as old_json, n.json as new_json
+            FROM main.{table} AS n
+            JOIN old.{table} AS o ON o.{key} = n.{key}
+            WHERE o.json != n.json
+            ORDER BY n.{key}
+        "#,
+            key = key,
+            table = table
+        );
+
+        let mut rows = sqlx::query(&q).fetch(&mut *db);
+        while let Some(row) = rows.try_next().await? {
+            let old_json: &str = row.try_get("old_json")?;
+            let new_json: &str = row.try_get("new_json")?;
+
+            let old_value: Value =
+                serde_json::from_str(old_json).unwrap_or(Value::Null);
+            let new_value: Value =
+                serde_json::from_str(new_json).unwrap_or(Value::Null);
+
+            let mut fields: Vec<FieldChange> = Vec::new();
+            compare_values("", &old_value, &new_value, &mut fields);
+
+            //only ignored fields changed
+            if fields.is_empty() {
+                continue;
+            }
+
+            out.changed.push(ChangedDefinition {
+                definition: parse_summary(get_key(&row, key)?, new_json),
+                fields,
+            });
+        }
+    }
+
+    Ok(out)
+}
+
+fn format_value(value: Option<&Value>) -> String {
+    match value {
+        Some(e) => e.to_string(),
+        None => "".to_string(),
+    }
+}
+
+fn join_path(path: &str, key: &str) -> String {
+    if path.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", path, key)
+    }
+}
+
+//recursively compares the values, adding a FieldChange for each leaf value
+//which was added, removed or changed
+fn compare_values(
+    path: &str,
+    old: &Value,
+    new: &Value,
+    out: &mut Vec<FieldChange>,
+) {
+    if old == new {
+        return;
+    }
+
+    match (old, new) {
+        (Value::Object(o), Value::Object(n)) => {
+            let mut keys: Vec<&String> = o.keys().chain(n.keys()).collect();
+            keys.sort();
+            keys.dedup();
+
+            for key in keys {
+                if path.is_empty() && IGNORED_FIELDS.contains(&key.as_str()) {
+                    continue;
+                }
+
+                let p = join_path(path, key);
+                match (o.get(key), n.get(key)) {
+                    (Some(ov), Some(nv)) => compare_values(&p, ov, nv, out),
+                    (ov, nv) => out.push(FieldChange {
+                        path: p,
+                        old_value: format_value(ov),
+                        new_value: format_value(nv),
+                    }),
+                }
+            }
+        }
+        (Value::Array(o), Value::Array(n)) => {
+            for i in 0..std::cmp::max(o.len(), n.len()) {
+                let p = format!("{}[{}]", path, i);
+                match (o.get(i), n.get(i)) {
+                    (Some(ov), Some(nv)) => compare_values(&p, ov, nv, out),
+                    (ov, nv) => out.push(FieldChange {
+                        path: p,
+                        old_value: format_value(ov),
+                        new_value: format_value(nv),
+                    }),
+                }
+            }
+        }
+        _ => out.push(FieldChange {
+            path: path.to_string(),
+            old_value: format_value(Some(old)),
+            new_value: format_value(Some(new)),
+        }),
+    }
+}
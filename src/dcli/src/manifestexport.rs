@@ -0,0 +1,250 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fs;
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use futures::TryStreamExt;
+use serde_json::Value;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use sqlx::Row;
+use sqlx::{ConnectOptions, Connection, SqliteConnection};
+
+use crate::error::Error;
+
+//convenience views created over the manifest tables. hashes are the unsigned
+//api hashes (not the signed ids used as the table keys), so they can be joined
+//against the activity store.
+const MANIFEST_VIEWS: [(&str, &str); 2] = [
+    (
+        "weapons",
+        r#"
+        SELECT
+            json_extract(json, '$.hash') AS hash,
+            json_extract(json, '$.displayProperties.name') AS name,
+            json_extract(json, '$.itemTypeDisplayName') AS type,
+            json_extract(json, '$.itemSubType') AS sub_type,
+            json_extract(json, '$.inventory.tierTypeName') AS tier
+        FROM
+            DestinyInventoryItemDefinition
+        WHERE
+            json_extract(json, '$.itemType') = 3
+    "#,
+    ),
+    (
+        "activities",
+        r#"
+        SELECT
+            json_extract(a.json, '$.hash') AS hash,
+            json_extract(a.json, '$.displayProperties.name') AS name,
+            json_extract(a.json, '$.directActivityModeType') AS mode,
+            json_extract(p.json, '$.displayProperties.name') AS place
+        FROM
+            DestinyActivityDefinition AS a
+        LEFT JOIN DestinyPlaceDefinition AS p ON p.id =
+            CASE
+                WHEN json_extract(a.json, '$.placeHash') > 2147483647
+                THEN json_extract(a.json, '$.placeHash') - 4294967296
+                ELSE json_extract(a.json, '$.placeHash')
+            END
+    "#,
+    ),
+];
+
+#[derive(Debug, Clone)]
+pub struct ExportedTable {
+    pub table_name: String,
+    pub path: PathBuf,
+    pub count: u32,
+}
+
+async fn open_manifest(
+    manifest_path: &Path,
+    read_only: bool,
+) -> Result<SqliteConnection, Error> {
+    let connection_string = format!("{}", manifest_path.display());
+    let db = SqliteConnectOptions::from_str(&connection_string)?
+        .journal_mode(SqliteJournalMode::Memory)
+        .read_only(read_only)
+        .connect()
+        .await?;
+
+    Ok(db)
+}
+
+//returns the names of the tables which have a json column, along with the
+//column the definitions are keyed by (id or key)
+async fn get_json_tables(
+    db: &mut SqliteConnection,
+) -> Result<Vec<(String, String)>, Error> {
+    let rows = sqlx::query(
+        r#"
+        SELECT m.name AS name, p.name AS key_column
+        FROM sqlite_master AS m
+        JOIN pragma_table_info(m.name) AS p
+        WHERE
+            m.type = 'table' AND
+            p.name IN ('id', 'key') AND
+            (SELECT count(*) FROM pragma_table_info(m.name) AS j
+                WHERE j.name = 'json') = 1
+        ORDER BY m.name
+    "#,
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let mut out: Vec<(String, String)> = Vec::new();
+    for row in &rows {
+        let name: String = row.try_get("name")?;
+        let key_column: String = row.try_get("key_column")?;
+        out.push((name, key_column));
+    }
+
+    Ok(out)
+}
+
+//indents all lines after the first, so a pretty printed value can be nested
+//in the containing object
+fn indent(value: &str) -> String {
+    value.replace('\n', "\n  ")
+}
+
+async fn export_table(
+    db: &mut SqliteConnection,
+    table_name: &str,
+    key_column: &str,
+    path: &Path,
+) -> Result<u32, Error> {
+    //write to a temp file, so a failed export doesnt leave a partial file
+    let tmp_path = path.with_extension("json.tmp");
+    let mut writer = BufWriter::new(fs::File::create(&tmp_path)?);
+
+    //for some reason sqlx doesnt let you bind table names
+    let q = format!(
+        "SELECT {key} AS key, json FROM {table} ORDER BY {key}",
+        key = key_column,
+        table = table_name
+    );
+
+    let mut count: u32 = 0;
+    write!(writer, "{{")?;
+
+    let mut rows = sqlx::query(&q).fetch(&mut *db);
+    while let Some(row) = rows.try_next().await? {
+        //ids are stored as signed ints, so we convert back to the api hash
+        let key: String = if key_column == "id" {
+            let id: i64 = row.try_get("key")?;
+            format!("{}", id as u32)
+        } else {
+            row.try_get("key")?
+        };
+
+        let json: &str = row.try_get_unchecked("json")?;
+        let value: Value = serde_json::from_str(json)?;
+
+        if count > 0 {
+            write!(writer, ",")?;
+        }
+
+        write!(
+            writer,
+            "\n  {}: {}",
+            serde_json::to_string(&key)?,
+            indent(&serde_json::to_string_pretty(&value)?)
+        )?;
+
+        count += 1;
+    }
+
+    if count > 0 {
+        writeln!(writer)?;
+    }
+    writeln!(writer, "}}")?;
+
+    writer.flush()?;
+    drop(writer);
+
+    fs::rename(&tmp_path, path)?;
+
+    Ok(count)
+}
+
+/// Exports each definition table in the manifest to a pretty printed json file
+/// (i.e. DestinyInventoryItemDefinition.json) in the specified directory, with
+/// the definitions keyed by their hash.
+pub async fn export_json(
+    manifest_path: &Path,
+    out_dir: &Path,
+) -> Result<Vec<ExportedTable>, Error> {
+    fs::create_dir_all(out_dir)?;
+
+    let mut db = open_manifest(manifest_path, true).await?;
+    let tables = get_json_tables(&mut db).await?;
+
+    let mut out: Vec<ExportedTable> = Vec::new();
+    for (table_name, key_column) in tables.iter() {
+        let path = out_dir.join(format!("{}.json", table_name));
+        let count =
+            match export_table(&mut db, table_name, key_column, &path).await {
+                Ok(e) => e,
+                Err(e) => {
+                    db.close().await?;
+                    return Err(e);
+                }
+            };
+
+        out.push(ExportedTable {
+            table_name: table_name.to_string(),
+            path,
+            count,
+        });
+    }
+
+    db.close().await?;
+
+    Ok(out)
+}
+
+/// Creates convenience views (weapons and activities) in the manifest database
+/// which extract commonly used fields from the definition json. Existing views
+/// are replaced. Returns the names of the views which were created.
+pub async fn create_views(manifest_path: &Path) -> Result<Vec<String>, Error> {
+    let mut db = open_manifest(manifest_path, false).await?;
+
+    let mut tx = db.begin().await?;
+    for (name, select) in MANIFEST_VIEWS.iter() {
+        sqlx::query(&format!("DROP VIEW IF EXISTS {}", name))
+            .execute(&mut tx)
+            .await?;
+
+        sqlx::query(&format!("CREATE VIEW {} AS {}", name, select))
+            .execute(&mut tx)
+            .await?;
+    }
+    tx.commit().await?;
+
+    db.close().await?;
+
+    Ok(MANIFEST_VIEWS.iter().map(|(e, _)| e.to_string()).collect())
+}
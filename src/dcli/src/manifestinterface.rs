@@ -20,7 +20,7 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 
 use futures::TryStreamExt;
@@ -30,16 +30,104 @@ use sqlx::Row;
 use sqlx::{ConnectOptions, Connection, SqliteConnection};
 use std::collections::HashMap;
 
+use crate::enums::itemtype::ItemType;
+use crate::enums::language::Language;
 use crate::error::Error;
 use crate::manifest::definitions::{
-    ActivityDefinitionData, ActivityTypeDefinitionData,
+    ActivityDefinitionData, ActivityModeDefinitionData,
+    ActivityTypeDefinitionData, CollectibleDefinitionData,
     DestinationDefinitionData, DisplayPropertiesData,
     HistoricalStatsDefinition, InventoryItemDefinitionData,
-    PlaceDefinitionData,
+    PlaceDefinitionData, PresentationNodeDefinitionData, RecordDefinitionData,
+    SeasonDefinitionData,
 };
 
 pub const MANIFEST_FILE_NAME: &str = "manifest.sqlite3";
 
+/// Returns the file name for the manifest for the specified language.
+///
+/// The English manifest uses MANIFEST_FILE_NAME, while manifests for other
+/// languages include the language code (i.e. manifest_de.sqlite3) so they
+/// can be stored side by side.
+pub fn get_manifest_file_name(language: &Language) -> String {
+    match language {
+        Language::English => MANIFEST_FILE_NAME.to_string(),
+        _ => format!("manifest_{}.sqlite3", language.to_code()),
+    }
+}
+
+//tables (and their key column) which are used by ManifestInterface, and must
+//exist in a valid manifest
+const REQUIRED_TABLES: [(&str, &str); 6] = [
+    ("DestinyActivityDefinition", "id"),
+    ("DestinyActivityTypeDefinition", "id"),
+    ("DestinyDestinationDefinition", "id"),
+    ("DestinyHistoricalStatsDefinition", "key"),
+    ("DestinyInventoryItemDefinition", "id"),
+    ("DestinyPlaceDefinition", "id"),
+];
+
+/// Checks that the file is a sqlite3 database containing the tables, and their
+/// key and json columns, which are required to use it as a manifest, and that
+/// the database passes an integrity check.
+pub async fn validate_manifest(path: &Path) -> Result<(), Error> {
+    let connection_string = format!("{}", path.display());
+    let mut db = SqliteConnectOptions::from_str(&connection_string)?
+        .journal_mode(SqliteJournalMode::Memory)
+        .read_only(true)
+        .connect()
+        .await
+        .map_err(|e| Error::ManifestInvalid {
+            description: format!("{}", e),
+        })?;
+
+    let mut missing: Vec<String> = Vec::new();
+    for (table, key) in REQUIRED_TABLES.iter() {
+        let rows = sqlx::query("SELECT name FROM pragma_table_info(?)")
+            .bind(table)
+            .fetch_all(&mut db)
+            .await
+            .map_err(|e| Error::ManifestInvalid {
+                description: format!("{}", e),
+            })?;
+
+        let columns: Vec<String> =
+            rows.iter().filter_map(|r| r.try_get("name").ok()).collect();
+
+        for column in &[*key, "json"] {
+            if !columns.iter().any(|c| c == column) {
+                missing.push(format!("{}.{}", table, column));
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        db.close().await?;
+        return Err(Error::ManifestInvalid {
+            description: format!("Missing : {}", missing.join(", ")),
+        });
+    }
+
+    //make sure the database file itself isnt corrupt (i.e. truncated write)
+    let row = sqlx::query("PRAGMA quick_check")
+        .fetch_one(&mut db)
+        .await
+        .map_err(|e| Error::ManifestInvalid {
+            description: format!("{}", e),
+        })?;
+
+    db.close().await?;
+
+    let result: String = row.try_get(0)?;
+    if result != "ok" {
+        return Err(Error::ManifestInvalid {
+            description: format!("Integrity check failed : {}", result),
+        });
+    }
+
+    Ok(())
+}
+
 /// Takes a Destiny 2 API has and converts it to a Destiny 2 manifest db index value
 pub fn convert_hash_to_id(hash: u32) -> i64 {
     let mut id: i64 = hash as i64;
@@ -51,20 +139,79 @@ pub fn convert_hash_to_id(hash: u32) -> i64 {
     id
 }
 
+/// Copies all of the tables (and their indexes) from the manifest database at
+/// the specified path into the database for the connection (used to load the
+/// manifest into memory).
+async fn copy_manifest(
+    db: &mut SqliteConnection,
+    path: &str,
+) -> Result<(), Error> {
+    sqlx::query("ATTACH DATABASE ? AS manifest")
+        .bind(path)
+        .execute(&mut *db)
+        .await?;
+
+    //tables are sorted before indexes, so tables exist before we index them.
+    //internal (autoindex) indexes dont have any sql, and are created along
+    //with their tables
+    let rows = sqlx::query(
+        "SELECT type, name, sql FROM manifest.sqlite_master WHERE sql IS NOT NULL AND type IN ('table', 'index') ORDER BY type DESC",
+    )
+    .fetch_all(&mut *db)
+    .await?;
+
+    let mut tx = db.begin().await?;
+    for row in rows.iter() {
+        let item_type: &str = row.try_get("type")?;
+        let name: &str = row.try_get("name")?;
+        let sql: &str = row.try_get("sql")?;
+
+        //sql from sqlite_master creates the table / index in the main db
+        sqlx::query(sql).execute(&mut tx).await?;
+
+        if item_type == "table" {
+            //for some reason sqlx doesnt let you bind table names
+            let q = format!(
+                "INSERT INTO main.\"{}\" SELECT * FROM manifest.\"{}\"",
+                name, name
+            );
+            sqlx::query(&q).execute(&mut tx).await?;
+        }
+    }
+    tx.commit().await?;
+
+    sqlx::query("DETACH DATABASE manifest")
+        .execute(&mut *db)
+        .await?;
+
+    Ok(())
+}
+
 pub struct ManifestInterface {
     manifest_db: SqliteConnection,
     activity_definition_cache: HashMap<i64, ActivityDefinitionData>,
     inventory_item_definition_cache: HashMap<i64, InventoryItemDefinitionData>,
     historical_stats_definition_cache:
         HashMap<String, HistoricalStatsDefinition>,
+    season_definition_cache: HashMap<i64, SeasonDefinitionData>,
+    record_definition_cache: HashMap<i64, RecordDefinitionData>,
+    collectible_definition_cache: HashMap<i64, CollectibleDefinitionData>,
+    presentation_node_definition_cache:
+        HashMap<i64, PresentationNodeDefinitionData>,
+    activity_mode_definition_cache: HashMap<i64, ActivityModeDefinitionData>,
 }
 
 impl ManifestInterface {
+    /// Opens the manifest for the specified language. If cache is true, the
+    /// entire manifest is copied into memory when opened. This takes a bit of
+    /// time (and memory) up front, but makes lookups faster, which helps when
+    /// running large numbers of queries (i.e. parsing all time activity data).
     pub async fn new(
         manifest_dir: &PathBuf,
+        language: &Language,
         cache: bool,
     ) -> Result<ManifestInterface, Error> {
-        let manifest_path = manifest_dir.join(MANIFEST_FILE_NAME);
+        let manifest_path = manifest_dir.join(get_manifest_file_name(language));
 
         if !manifest_path.exists() {
             return Err(Error::IoFileDoesNotExist {
@@ -88,58 +235,29 @@ impl ManifestInterface {
         //as it can causes errors when opening a DB in readonly mode
         //We use Memory which should provide better performance
         //since we never write to the DB
-        let db = SqliteConnectOptions::from_str(&connection_string)?
+        let mut db = SqliteConnectOptions::from_str(&connection_string)?
             .journal_mode(SqliteJournalMode::Memory)
             .read_only(read_only)
             .connect()
             .await?;
 
-        /*
         if cache {
-            match sqlx::query("ATTACH DATABASE '?' as 'tmpDb'")
-                .bind(path)
-                .execute(&mut db)
-                .await
-            {
-                Ok(e) => e,
-                Err(e) => {
-                    db.close().await?;
-                    return Err(Error::from(e));
-                }
-            };
-
-
-            //TODO: Need to impliment this to dynamically pull table names
-            //"SELECT name FROM sqlite_master WHERE type='table'"
-            let table_name: String = "InventoryItemDefinition".to_string();
-            //todo: do we need to pass table_name twice?
-            match sqlx::query("CREATE TABLE ? AS SELECT * FROM tmpDb.?")
-                .bind(table_name)
-                .execute(&mut db)
-                .await
-            {
-                Ok(e) => e,
-                Err(e) => {
-                    db.close().await?;
-                    return Err(Error::from(e));
-                }
-            };
-
-            match sqlx::query("DETACH DATABASE tmpDb").execute(&mut db).await {
-                Ok(e) => e,
-                Err(e) => {
-                    db.close().await?;
-                    return Err(Error::from(e));
-                }
+            if let Err(e) = copy_manifest(&mut db, &path).await {
+                db.close().await?;
+                return Err(e);
             }
         }
-        */
 
         Ok(ManifestInterface {
             manifest_db: db,
             activity_definition_cache: HashMap::new(),
             inventory_item_definition_cache: HashMap::new(),
             historical_stats_definition_cache: HashMap::new(),
+            season_definition_cache: HashMap::new(),
+            record_definition_cache: HashMap::new(),
+            collectible_definition_cache: HashMap::new(),
+            presentation_node_definition_cache: HashMap::new(),
+            activity_mode_definition_cache: HashMap::new(),
         })
     }
 
@@ -212,6 +330,112 @@ impl ManifestInterface {
         Ok(tables)
     }
 
+    /// Returns the hash and display name for each entry in the table. Entries
+    /// without a display name are not included.
+    pub async fn get_display_names(
+        &mut self,
+        table: &str,
+    ) -> Result<Vec<(u32, String)>, Error> {
+        let mut out: Vec<(u32, String)> = Vec::new();
+
+        //for some reason sqlx doesnt let you bind table names
+        let q = format!(
+            r#"
+            SELECT
+                id,
+                json_extract(json, '$.displayProperties.name') as name
+            FROM {}
+            WHERE
+                json_extract(json, '$.displayProperties.name') != ''
+        "#,
+            table
+        );
+
+        let mut rows = sqlx::query(&q).fetch(&mut self.manifest_db);
+
+        while let Some(row) = rows.try_next().await? {
+            let id: i64 = row.try_get("id")?;
+            let name: String = row.try_get("name")?;
+
+            //convert the id back to the (unsigned) api hash
+            out.push((id as u32, name));
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the (unique) image paths referenced by the specified json path
+    /// (i.e. $.pgcrImage) for all of the definitions in the table. Paths are
+    /// relative to the bungie.net resource url.
+    pub async fn get_image_paths(
+        &mut self,
+        table: &str,
+        json_path: &str,
+    ) -> Result<Vec<String>, Error> {
+        let mut out: Vec<String> = Vec::new();
+
+        //for some reason sqlx doesnt let you bind table names
+        let q = format!(
+            r#"
+            SELECT DISTINCT
+                json_extract(json, ?) as image
+            FROM {}
+            WHERE
+                image IS NOT NULL AND image != ''
+        "#,
+            table
+        );
+
+        let mut rows =
+            sqlx::query(&q).bind(json_path).fetch(&mut self.manifest_db);
+
+        while let Some(row) = rows.try_next().await? {
+            let image: String = row.try_get("image")?;
+            out.push(image);
+        }
+
+        Ok(out)
+    }
+
+    /// Returns the (unique) image paths referenced by the emblem definitions
+    /// (the icon, and the secondary images used for the emblem backgrounds).
+    /// Paths are relative to the bungie.net resource url.
+    pub async fn get_emblem_image_paths(
+        &mut self,
+    ) -> Result<Vec<String>, Error> {
+        let mut out: Vec<String> = Vec::new();
+
+        let json_paths = [
+            "$.displayProperties.icon",
+            "$.secondaryIcon",
+            "$.secondaryOverlay",
+            "$.secondarySpecial",
+        ];
+
+        for json_path in json_paths.iter() {
+            let mut rows = sqlx::query(
+                r#"
+                SELECT DISTINCT
+                    json_extract(json, ?) as image
+                FROM DestinyInventoryItemDefinition
+                WHERE
+                    json_extract(json, '$.itemType') = ? AND
+                    image IS NOT NULL AND image != ''
+            "#,
+            )
+            .bind(json_path)
+            .bind(ItemType::Emblem as i32)
+            .fetch(&mut self.manifest_db);
+
+            while let Some(row) = rows.try_next().await? {
+                let image: String = row.try_get("image")?;
+                out.push(image);
+            }
+        }
+
+        Ok(out)
+    }
+
     pub async fn get_activity_definition(
         &mut self,
         id: u32,
@@ -295,6 +519,25 @@ impl ManifestInterface {
         Ok(data)
     }
 
+    /// Returns all of the historical stats definitions in the manifest, which
+    /// includes the definitions for all medals.
+    pub async fn get_historical_stats_definitions(
+        &mut self,
+    ) -> Result<Vec<HistoricalStatsDefinition>, Error> {
+        let data: Vec<HistoricalStatsDefinition> = self
+            .get_definitions(
+                "SELECT json FROM DestinyHistoricalStatsDefinition",
+            )
+            .await?;
+
+        for d in &data {
+            self.historical_stats_definition_cache
+                .insert(d.id.clone(), d.clone());
+        }
+
+        Ok(data)
+    }
+
     pub async fn get_destination_definition(
         &mut self,
         id: u32,
@@ -343,6 +586,179 @@ impl ManifestInterface {
         Ok(data)
     }
 
+    pub async fn get_season_definition(
+        &mut self,
+        id: u32,
+    ) -> Result<Option<SeasonDefinitionData>, Error> {
+        let id = convert_hash_to_id(id);
+
+        if let Some(out) = self.season_definition_cache.get(&id) {
+            return Ok(Some(out.clone()));
+        }
+
+        let query = &format!(
+            "SELECT json FROM DestinySeasonDefinition WHERE id = {}",
+            id
+        );
+
+        let data: Option<SeasonDefinitionData> =
+            self.get_definition(query).await?;
+
+        if let Some(e) = data.as_ref() {
+            self.season_definition_cache.insert(id, e.clone());
+        }
+
+        Ok(data)
+    }
+
+    /// Returns all of the season definitions in the manifest.
+    pub async fn get_season_definitions(
+        &mut self,
+    ) -> Result<Vec<SeasonDefinitionData>, Error> {
+        let data: Vec<SeasonDefinitionData> = self
+            .get_definitions("SELECT json FROM DestinySeasonDefinition")
+            .await?;
+
+        for d in &data {
+            self.season_definition_cache
+                .insert(convert_hash_to_id(d.id), d.clone());
+        }
+
+        Ok(data)
+    }
+
+    pub async fn get_record_definition(
+        &mut self,
+        id: u32,
+    ) -> Result<Option<RecordDefinitionData>, Error> {
+        let id = convert_hash_to_id(id);
+
+        if let Some(out) = self.record_definition_cache.get(&id) {
+            return Ok(Some(out.clone()));
+        }
+
+        let query = &format!(
+            "SELECT json FROM DestinyRecordDefinition WHERE id = {}",
+            id
+        );
+
+        let data: Option<RecordDefinitionData> =
+            self.get_definition(query).await?;
+
+        if let Some(e) = data.as_ref() {
+            self.record_definition_cache.insert(id, e.clone());
+        }
+
+        Ok(data)
+    }
+
+    pub async fn get_collectible_definition(
+        &mut self,
+        id: u32,
+    ) -> Result<Option<CollectibleDefinitionData>, Error> {
+        let id = convert_hash_to_id(id);
+
+        if let Some(out) = self.collectible_definition_cache.get(&id) {
+            return Ok(Some(out.clone()));
+        }
+
+        let query = &format!(
+            "SELECT json FROM DestinyCollectibleDefinition WHERE id = {}",
+            id
+        );
+
+        let data: Option<CollectibleDefinitionData> =
+            self.get_definition(query).await?;
+
+        if let Some(e) = data.as_ref() {
+            self.collectible_definition_cache.insert(id, e.clone());
+        }
+
+        Ok(data)
+    }
+
+    pub async fn get_presentation_node_definition(
+        &mut self,
+        id: u32,
+    ) -> Result<Option<PresentationNodeDefinitionData>, Error> {
+        let id = convert_hash_to_id(id);
+
+        if let Some(out) = self.presentation_node_definition_cache.get(&id) {
+            return Ok(Some(out.clone()));
+        }
+
+        let query = &format!(
+            "SELECT json FROM DestinyPresentationNodeDefinition WHERE id = {}",
+            id
+        );
+
+        let data: Option<PresentationNodeDefinitionData> =
+            self.get_definition(query).await?;
+
+        if let Some(e) = data.as_ref() {
+            self.presentation_node_definition_cache
+                .insert(id, e.clone());
+        }
+
+        Ok(data)
+    }
+
+    pub async fn get_activity_mode_definition(
+        &mut self,
+        id: u32,
+    ) -> Result<Option<ActivityModeDefinitionData>, Error> {
+        let id = convert_hash_to_id(id);
+
+        if let Some(out) = self.activity_mode_definition_cache.get(&id) {
+            return Ok(Some(out.clone()));
+        }
+
+        let query = &format!(
+            "SELECT json FROM DestinyActivityModeDefinition WHERE id = {}",
+            id
+        );
+
+        let data: Option<ActivityModeDefinitionData> =
+            self.get_definition(query).await?;
+
+        if let Some(e) = data.as_ref() {
+            self.activity_mode_definition_cache.insert(id, e.clone());
+        }
+
+        Ok(data)
+    }
+
+    /// Returns all of the activity mode definitions in the manifest.
+    pub async fn get_activity_mode_definitions(
+        &mut self,
+    ) -> Result<Vec<ActivityModeDefinitionData>, Error> {
+        let data: Vec<ActivityModeDefinitionData> = self
+            .get_definitions("SELECT json FROM DestinyActivityModeDefinition")
+            .await?;
+
+        for d in &data {
+            self.activity_mode_definition_cache
+                .insert(convert_hash_to_id(d.id), d.clone());
+        }
+
+        Ok(data)
+    }
+
+    async fn get_definitions<T: serde::de::DeserializeOwned>(
+        &mut self,
+        query: &str,
+    ) -> Result<Vec<T>, Error> {
+        let rows = sqlx::query(query).fetch_all(&mut self.manifest_db).await?;
+
+        let mut out: Vec<T> = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let json: &str = row.try_get_unchecked("json")?;
+            out.push(serde_json::from_str(json)?);
+        }
+
+        Ok(out)
+    }
+
     async fn get_definition<T: serde::de::DeserializeOwned>(
         &mut self,
         query: &str,
@@ -0,0 +1,447 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use futures::TryStreamExt;
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode};
+use sqlx::Row;
+use sqlx::{ConnectOptions, Connection, SqliteConnection};
+
+use crate::enums::language::Language;
+use crate::error::Error;
+use crate::manifestinterface::ManifestInterface;
+use crate::utils::normalize_name;
+
+pub const SEARCH_INDEX_FILE_NAME: &str = "manifest_search.sqlite3";
+
+//minimum similarity (0.0 to 1.0) for a fuzzy match
+const FUZZY_MATCH_THRESHOLD: f32 = 0.7;
+
+const SEARCH_INDEX_SCHEMA: &str = r#"
+    CREATE TABLE IF NOT EXISTS "search_index" (
+        "hash" INTEGER NOT NULL,
+        "table_name" TEXT NOT NULL,
+        "name" TEXT NOT NULL,
+        "normalized_name" TEXT NOT NULL
+    );
+
+    CREATE INDEX IF NOT EXISTS search_index_table_name_index ON search_index (table_name);
+    CREATE INDEX IF NOT EXISTS search_index_normalized_name_index ON search_index (normalized_name);
+"#;
+
+/// Returns the file name for the search index for the manifest for the
+/// specified language.
+pub fn get_search_index_file_name(language: &Language) -> String {
+    match language {
+        Language::English => SEARCH_INDEX_FILE_NAME.to_string(),
+        _ => format!("manifest_search_{}.sqlite3", language.to_code()),
+    }
+}
+
+/// Returns true if the table name matches the filter. Matching is case
+/// insensitive, and the Destiny prefix is optional (i.e. InventoryItemDefinition
+/// matches DestinyInventoryItemDefinition).
+pub fn table_matches(table_name: &str, filter: &str) -> bool {
+    let table_name = table_name.to_lowercase();
+    let filter = filter.to_lowercase();
+
+    table_name == filter || table_name == format!("destiny{}", filter)
+}
+
+fn levenshtein_distance(a: &[char], b: &[char]) -> usize {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current: Vec<usize> = vec![0; b.len() + 1];
+
+    for (i, ac) in a.iter().enumerate() {
+        current[0] = i + 1;
+
+        for (j, bc) in b.iter().enumerate() {
+            let cost = if ac == bc { 0 } else { 1 };
+            current[j + 1] = (previous[j] + cost)
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[b.len()]
+}
+
+//returns similarity between 0.0 (nothing in common) and 1.0 (the same)
+fn calculate_similarity(a: &str, b: &str) -> f32 {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+
+    let max_len = std::cmp::max(a.len(), b.len());
+    if max_len == 0 {
+        return 1.0;
+    }
+
+    1.0 - levenshtein_distance(&a, &b) as f32 / max_len as f32
+}
+
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum MatchType {
+    Fuzzy,
+    Substring,
+    WordPrefix,
+    Prefix,
+    Exact,
+}
+
+impl fmt::Display for MatchType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out = match self {
+            MatchType::Exact => "Exact",
+            MatchType::Prefix => "Prefix",
+            MatchType::WordPrefix => "Word",
+            MatchType::Substring => "Substring",
+            MatchType::Fuzzy => "Fuzzy",
+        };
+
+        write!(f, "{}", out)
+    }
+}
+
+/// Returns how well the (normalized) name matches the (normalized) query, or
+/// None if it does not match.
+fn match_name(query: &str, name: &str) -> Option<(MatchType, f32)> {
+    if name == query {
+        return Some((MatchType::Exact, 1.0));
+    }
+
+    if name.starts_with(query) {
+        return Some((MatchType::Prefix, 1.0));
+    }
+
+    if name.split(' ').any(|w| w.starts_with(query)) {
+        return Some((MatchType::WordPrefix, 1.0));
+    }
+
+    if name.contains(query) {
+        return Some((MatchType::Substring, 1.0));
+    }
+
+    //compare against the full name, as well as each run of words the same
+    //length as the query, so typos in part of a longer name still match
+    let mut similarity = calculate_similarity(query, name);
+
+    let words: Vec<&str> = name.split(' ').collect();
+    let query_word_count = query.split(' ').count();
+    if words.len() > query_word_count {
+        for w in words.windows(query_word_count) {
+            similarity =
+                similarity.max(calculate_similarity(query, &w.join(" ")));
+        }
+    }
+
+    if similarity >= FUZZY_MATCH_THRESHOLD {
+        Some((MatchType::Fuzzy, similarity))
+    } else {
+        None
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult {
+    pub hash: u32,
+    pub table_name: String,
+    pub name: String,
+    pub match_type: MatchType,
+
+    //similarity of fuzzy matches (0.0 to 1.0). 1.0 for all other matches
+    pub similarity: f32,
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResults {
+    //total number of matches, across all pages
+    pub total: usize,
+    pub results: Vec<SearchResult>,
+}
+
+/// Name search index for the manifest, stored in its own sqlite3 database
+/// alongside the manifest, and generated by dclim when the manifest is
+/// downloaded.
+pub struct ManifestSearchIndex {
+    path: PathBuf,
+}
+
+impl ManifestSearchIndex {
+    pub fn new(
+        manifest_dir: &Path,
+        language: &Language,
+    ) -> Result<ManifestSearchIndex, Error> {
+        let path = manifest_dir.join(get_search_index_file_name(language));
+
+        if !path.exists() {
+            return Err(Error::IoFileDoesNotExist {
+                description: format!(
+                    "Search index does not exist. Run dclim to generate it. {}",
+                    path.display()
+                ),
+            });
+        }
+
+        Ok(ManifestSearchIndex { path })
+    }
+
+    /// Builds a new search index from the manifest, containing the display
+    /// name for every entry in each table with an id column. Returns the
+    /// number of entries in the index.
+    pub async fn build(
+        manifest: &mut ManifestInterface,
+        manifest_dir: &Path,
+        language: &Language,
+    ) -> Result<u32, Error> {
+        let path = manifest_dir.join(get_search_index_file_name(language));
+
+        //build into a temp file, and then replace the existing index, so the
+        //existing index is left untouched if anything fails
+        let tmp_path = path.with_extension("tmp");
+        if tmp_path.exists() {
+            fs::remove_file(&tmp_path)?;
+        }
+
+        let connection_string = format!("{}", tmp_path.display());
+        let mut db = SqliteConnectOptions::from_str(&connection_string)?
+            .journal_mode(SqliteJournalMode::Memory)
+            .create_if_missing(true)
+            .connect()
+            .await?;
+
+        sqlx::query(SEARCH_INDEX_SCHEMA).execute(&mut db).await?;
+
+        let mut count = 0;
+        sqlx::query("BEGIN TRANSACTION;").execute(&mut db).await?;
+
+        for table in manifest.get_tables_with_id_column().await? {
+            //tables without display properties dont return any names
+            let names = manifest.get_display_names(&table).await?;
+
+            for (hash, name) in names {
+                sqlx::query(
+                    r#"
+                    INSERT INTO "main"."search_index"
+                        ("hash", "table_name", "name", "normalized_name")
+                    VALUES (?, ?, ?, ?)
+                "#,
+                )
+                .bind(hash)
+                .bind(&table)
+                .bind(&name)
+                .bind(normalize_name(&name))
+                .execute(&mut db)
+                .await?;
+
+                count += 1;
+            }
+        }
+
+        sqlx::query("COMMIT;").execute(&mut db).await?;
+        db.close().await?;
+
+        fs::rename(&tmp_path, &path)?;
+
+        Ok(count)
+    }
+
+    /// Searches the index for names matching the query. Results are ranked
+    /// by match type (exact, prefix, word prefix, substring and then fuzzy),
+    /// and then by name length. If tables is not empty, only entries from the
+    /// specified tables are included.
+    ///
+    /// Returns Error::InvalidParameters if the query doesnt contain any
+    /// letters or numbers.
+    pub async fn search(
+        &self,
+        query: &str,
+        tables: &[String],
+        limit: usize,
+        offset: usize,
+    ) -> Result<SearchResults, Error> {
+        let query = normalize_name(query);
+
+        if query.is_empty() {
+            return Err(Error::InvalidParameters);
+        }
+
+        let connection_string = format!("{}", self.path.display());
+        let mut db = SqliteConnectOptions::from_str(&connection_string)?
+            .journal_mode(SqliteJournalMode::Memory)
+            .read_only(true)
+            .connect()
+            .await?;
+
+        let table_names = find_tables(&mut db, tables).await?;
+
+        let table_clause = if tables.is_empty() {
+            String::new()
+        } else if table_names.is_empty() {
+            //none of the tables are in the index
+            db.close().await?;
+            return Ok(SearchResults {
+                total: 0,
+                results: Vec::new(),
+            });
+        } else {
+            format!(
+                " AND table_name IN ({})",
+                vec!["?"; table_names.len()].join(", ")
+            )
+        };
+
+        //names shorter than this cant be similar enough to be a fuzzy match
+        let min_fuzzy_len = (query.chars().count() as f32
+            * FUZZY_MATCH_THRESHOLD)
+            .ceil() as usize;
+
+        //each name is only returned by one of the queries. Exact and prefix
+        //matches use the normalized_name index (normalized names dont contain
+        //any glob characters), word prefix and substring matches are found in
+        //sql, and only the remaining candidates are checked for fuzzy matches.
+        let queries = [
+            (
+                format!(
+                    "SELECT hash, table_name, name, normalized_name FROM search_index WHERE normalized_name GLOB ?{}",
+                    table_clause
+                ),
+                format!("{}*", query),
+            ),
+            (
+                format!(
+                    "SELECT hash, table_name, name, normalized_name FROM search_index WHERE instr(normalized_name, ?) > 1{}",
+                    table_clause
+                ),
+                query.clone(),
+            ),
+            (
+                format!(
+                    "SELECT hash, table_name, name, normalized_name FROM search_index WHERE instr(normalized_name, ?) = 0 AND length(normalized_name) >= {}{}",
+                    min_fuzzy_len, table_clause
+                ),
+                query.clone(),
+            ),
+        ];
+
+        let mut matches: Vec<SearchResult> = Vec::new();
+        for (sql, value) in &queries {
+            find_matches(
+                &mut db,
+                sql,
+                value,
+                &table_names,
+                &query,
+                &mut matches,
+            )
+            .await?;
+        }
+
+        db.close().await?;
+
+        matches.sort_by(|a, b| {
+            b.match_type
+                .cmp(&a.match_type)
+                .then(
+                    b.similarity
+                        .partial_cmp(&a.similarity)
+                        .unwrap_or(std::cmp::Ordering::Equal),
+                )
+                .then(a.name.len().cmp(&b.name.len()))
+                .then(a.name.cmp(&b.name))
+                .then(a.table_name.cmp(&b.table_name))
+        });
+
+        let total = matches.len();
+        let results = matches.into_iter().skip(offset).take(limit).collect();
+
+        Ok(SearchResults { total, results })
+    }
+}
+
+/// Returns the names of the tables in the index which match any of the
+/// filters.
+async fn find_tables(
+    db: &mut SqliteConnection,
+    filters: &[String],
+) -> Result<Vec<String>, Error> {
+    let mut out: Vec<String> = Vec::new();
+
+    if filters.is_empty() {
+        return Ok(out);
+    }
+
+    let mut rows =
+        sqlx::query("SELECT DISTINCT table_name FROM search_index").fetch(db);
+
+    while let Some(row) = rows.try_next().await? {
+        let table_name: String = row.try_get("table_name")?;
+
+        if filters.iter().any(|t| table_matches(&table_name, t)) {
+            out.push(table_name);
+        }
+    }
+
+    Ok(out)
+}
+
+/// Runs the search query (binding value, followed by the table names), and
+/// adds the rows which match the (normalized) query to matches.
+async fn find_matches(
+    db: &mut SqliteConnection,
+    sql: &str,
+    value: &str,
+    table_names: &[String],
+    query: &str,
+    matches: &mut Vec<SearchResult>,
+) -> Result<(), Error> {
+    let mut q = sqlx::query(sql).bind(value);
+    for t in table_names {
+        q = q.bind(t);
+    }
+
+    let mut rows = q.fetch(db);
+    while let Some(row) = rows.try_next().await? {
+        let normalized_name: &str = row.try_get("normalized_name")?;
+        let (match_type, similarity) = match match_name(query, normalized_name)
+        {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let hash: i64 = row.try_get("hash")?;
+        matches.push(SearchResult {
+            hash: hash as u32,
+            table_name: row.try_get("table_name")?,
+            name: row.try_get("name")?,
+            match_type,
+            similarity,
+        });
+    }
+
+    Ok(())
+}
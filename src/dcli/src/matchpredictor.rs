@@ -0,0 +1,807 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+//Simple local model for estimating the outcome of a game from the lobby.
+//
+//Each game is described by the difference between the member's team and the
+//opposing team for a small set of features (historical performance of the
+//players, fireteam and team sizes, and light level), and a logistic
+//regression model is trained on the stored games to estimate the probability
+//that the member's team wins.
+//
+//Player history only includes games which are in the local activity store,
+//and is built walking through the games in chronological order, so the
+//features for a game only use information available before it was played.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_derive::{Deserialize, Serialize};
+
+use crate::crucible::{ActivityDetail, CrucibleActivity, Team};
+use crate::enums::standing::Standing;
+use crate::error::Error;
+use crate::utils::{calculate_avg, calculate_percent};
+
+pub const FEATURE_COUNT: usize = 6;
+
+pub const FEATURE_NAMES: [&str; FEATURE_COUNT] = [
+    "Player Efficiency",
+    "Player Win Rate",
+    "Player Games",
+    "Fireteam Size",
+    "Team Size",
+    "Light Level",
+];
+
+//number of average games each player's history is padded with, so players
+//with few stored games are pulled towards the average
+const PRIOR_GAMES: f32 = 5.0;
+const PRIOR_EFFICIENCY: f32 = 1.0;
+const PRIOR_WIN_RATE: f32 = 0.5;
+
+/// Multiplier applied to a player's efficiency when generating local player
+/// ratings, so they are on a similar scale to combat ratings
+pub const LOCAL_RATING_SCALE: f32 = 100.0;
+
+const EPOCHS: u32 = 1000;
+const LEARNING_RATE: f32 = 0.1;
+const L2_PENALTY: f32 = 0.01;
+
+/// A result is considered an upset if the model gave it less than this
+/// probability.
+pub const UPSET_PROBABILITY: f32 = 0.3;
+
+/// Fraction of the most recent games held out to evaluate the model
+pub const HOLDOUT_FRACTION: f32 = 0.2;
+
+/// Minimum number of games required to train the model
+pub const MIN_TRAINING_GAMES: usize = 20;
+
+/// Number of folds used to generate predictions for games the model was not
+/// trained on
+pub const CROSS_VALIDATION_FOLDS: usize = 5;
+
+/// Number of games which can be played after a saved model was trained,
+/// before it is retrained
+pub const RETRAIN_GAME_COUNT: usize = 25;
+
+#[derive(Debug, Default)]
+struct PlayerHistory {
+    games: u32,
+    wins: u32,
+    efficiency_total: f32,
+}
+
+impl PlayerHistory {
+    fn efficiency(&self) -> f32 {
+        (self.efficiency_total + PRIOR_EFFICIENCY * PRIOR_GAMES)
+            / (self.games as f32 + PRIOR_GAMES)
+    }
+
+    fn win_rate(&self) -> f32 {
+        (self.wins as f32 + PRIOR_WIN_RATE * PRIOR_GAMES)
+            / (self.games as f32 + PRIOR_GAMES)
+    }
+
+    fn experience(&self) -> f32 {
+        (self.games as f32).ln_1p()
+    }
+}
+
+#[derive(Debug, Default)]
+struct TeamFeatures {
+    efficiency: f32,
+    win_rate: f32,
+    experience: f32,
+    fireteam_size: f32,
+    team_size: f32,
+}
+
+impl TeamFeatures {
+    fn with_team(
+        team: &Team,
+        history: &HashMap<String, PlayerHistory>,
+    ) -> TeamFeatures {
+        let default = PlayerHistory::default();
+        let count = team.player_performances.len() as u32;
+
+        let mut out = TeamFeatures::default();
+        for p in &team.player_performances {
+            let h = history.get(&p.player.member_id).unwrap_or(&default);
+
+            out.efficiency += h.efficiency();
+            out.win_rate += h.win_rate();
+            out.experience += h.experience();
+
+            //players joining in progress arent known when the game starts
+            if !p.stats.joined_late() {
+                out.team_size += 1.0;
+            }
+        }
+
+        out.efficiency = calculate_avg(out.efficiency, count);
+        out.win_rate = calculate_avg(out.win_rate, count);
+        out.experience = calculate_avg(out.experience, count);
+        out.fireteam_size =
+            team.get_fireteam_sizes().first().copied().unwrap_or(0) as f32;
+
+        out
+    }
+}
+
+/// A game described from the perspective of the member's team.
+#[derive(Debug, Clone)]
+pub struct MatchSample {
+    pub details: ActivityDetail,
+
+    //difference between the member's team and the opposing team for each
+    //feature in FEATURE_NAMES
+    pub features: [f32; FEATURE_COUNT],
+    pub won: bool,
+}
+
+/// Returns a sample for each game with two teams and a known result, oldest
+/// first.
+pub fn generate_match_samples(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+) -> Vec<MatchSample> {
+    let mut sorted: Vec<&CrucibleActivity> = activities.iter().collect();
+    sorted.sort_by_key(|a| a.details.period);
+
+    let mut history: HashMap<String, PlayerHistory> = HashMap::new();
+    let mut out: Vec<MatchSample> = Vec::new();
+
+    for activity in sorted {
+        let member = match activity.get_member_performance(member_id) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let teams: Vec<&Team> = activity.teams.values().collect();
+        if teams.len() != 2
+            || teams.iter().any(|t| t.standing == Standing::Unknown)
+        {
+            continue;
+        }
+
+        let (member_team, opponent_team) = if teams[0].id == member.stats.team {
+            (teams[0], teams[1])
+        } else {
+            (teams[1], teams[0])
+        };
+
+        let team = TeamFeatures::with_team(member_team, &history);
+        let opponents = TeamFeatures::with_team(opponent_team, &history);
+
+        let light = activity
+            .get_light_level_advantage(member_id)
+            .map(|e| e.advantage())
+            .unwrap_or(0.0);
+
+        out.push(MatchSample {
+            details: activity.details.clone(),
+            features: [
+                team.efficiency - opponents.efficiency,
+                team.win_rate - opponents.win_rate,
+                team.experience - opponents.experience,
+                team.fireteam_size - opponents.fireteam_size,
+                team.team_size - opponents.team_size,
+                light,
+            ],
+            won: member_team.standing == Standing::Victory,
+        });
+
+        add_to_history(&mut history, &teams);
+    }
+
+    out
+}
+
+fn add_to_history(
+    history: &mut HashMap<String, PlayerHistory>,
+    teams: &[&Team],
+) {
+    for t in teams {
+        for p in &t.player_performances {
+            let h = history.entry(p.player.member_id.clone()).or_default();
+
+            h.games += 1;
+            h.efficiency_total += p.stats.efficiency;
+            if t.standing == Standing::Victory {
+                h.wins += 1;
+            }
+        }
+    }
+}
+
+/// Generates a rating for each player in each activity, from the player's
+/// efficiency in the activities played before it (scaled by
+/// LOCAL_RATING_SCALE). Ratings are keyed by activity index id, and then by
+/// player hash.
+pub fn generate_player_ratings(
+    activities: &[CrucibleActivity],
+) -> HashMap<u32, HashMap<u64, f32>> {
+    let mut sorted: Vec<&CrucibleActivity> = activities.iter().collect();
+    sorted.sort_by_key(|a| a.details.period);
+
+    let mut history: HashMap<String, PlayerHistory> = HashMap::new();
+    let mut out: HashMap<u32, HashMap<u64, f32>> = HashMap::new();
+    let default = PlayerHistory::default();
+
+    for activity in sorted {
+        let teams: Vec<&Team> = activity.teams.values().collect();
+
+        let mut ratings: HashMap<u64, f32> = HashMap::new();
+        for t in &teams {
+            for p in &t.player_performances {
+                let h = history.get(&p.player.member_id).unwrap_or(&default);
+                ratings.insert(
+                    p.player.calculate_hash(),
+                    h.efficiency() * LOCAL_RATING_SCALE,
+                );
+            }
+        }
+
+        out.insert(activity.details.index_id, ratings);
+        add_to_history(&mut history, &teams);
+    }
+
+    out
+}
+
+fn sigmoid(x: f32) -> f32 {
+    1.0 / (1.0 + (-x).exp())
+}
+
+/// Logistic regression model estimating the probability that the member's
+/// team wins a game. Features are standardized before training, so the
+/// weights indicate the relative influence of each feature.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MatchPredictor {
+    means: [f32; FEATURE_COUNT],
+    std_devs: [f32; FEATURE_COUNT],
+    pub weights: [f32; FEATURE_COUNT],
+    pub bias: f32,
+
+    //percent of training games won (0.0 to 1.0)
+    pub training_win_rate: f32,
+}
+
+impl MatchPredictor {
+    /// Trains a model using batch gradient descent. Returns None if there are
+    /// no samples.
+    pub fn train(samples: &[MatchSample]) -> Option<MatchPredictor> {
+        let samples: Vec<&MatchSample> = samples.iter().collect();
+        MatchPredictor::train_with_refs(&samples)
+    }
+
+    fn train_with_refs(samples: &[&MatchSample]) -> Option<MatchPredictor> {
+        if samples.is_empty() {
+            return None;
+        }
+
+        let n = samples.len() as f32;
+
+        let mut means = [0.0; FEATURE_COUNT];
+        let mut std_devs = [0.0; FEATURE_COUNT];
+        for i in 0..FEATURE_COUNT {
+            means[i] = samples.iter().map(|s| s.features[i]).sum::<f32>() / n;
+
+            let variance = samples
+                .iter()
+                .map(|s| (s.features[i] - means[i]).powi(2))
+                .sum::<f32>()
+                / n;
+
+            //features which never change are ignored
+            std_devs[i] = if variance > 0.0 { variance.sqrt() } else { 1.0 };
+        }
+
+        let mut model = MatchPredictor {
+            means,
+            std_devs,
+            weights: [0.0; FEATURE_COUNT],
+            bias: 0.0,
+            training_win_rate: samples.iter().filter(|s| s.won).count() as f32
+                / n,
+        };
+
+        let scaled: Vec<([f32; FEATURE_COUNT], f32)> = samples
+            .iter()
+            .map(|s| (model.scale(&s.features), if s.won { 1.0 } else { 0.0 }))
+            .collect();
+
+        for _ in 0..EPOCHS {
+            let mut weight_gradients = [0.0; FEATURE_COUNT];
+            let mut bias_gradient = 0.0;
+
+            for (x, y) in &scaled {
+                let error = model.predict_scaled(x) - y;
+
+                for (g, v) in weight_gradients.iter_mut().zip(x.iter()) {
+                    *g += error * v;
+                }
+                bias_gradient += error;
+            }
+
+            for (w, g) in model.weights.iter_mut().zip(weight_gradients.iter())
+            {
+                *w -= LEARNING_RATE * (g / n + L2_PENALTY * *w);
+            }
+            model.bias -= LEARNING_RATE * bias_gradient / n;
+        }
+
+        Some(model)
+    }
+
+    fn scale(&self, features: &[f32; FEATURE_COUNT]) -> [f32; FEATURE_COUNT] {
+        let mut out = [0.0; FEATURE_COUNT];
+        for i in 0..FEATURE_COUNT {
+            out[i] = (features[i] - self.means[i]) / self.std_devs[i];
+        }
+
+        out
+    }
+
+    fn predict_scaled(&self, x: &[f32; FEATURE_COUNT]) -> f32 {
+        let z: f32 = self
+            .weights
+            .iter()
+            .zip(x.iter())
+            .map(|(w, v)| w * v)
+            .sum::<f32>()
+            + self.bias;
+
+        sigmoid(z)
+    }
+
+    /// Returns the probability (0.0 to 1.0) that the member's team wins
+    pub fn predict(&self, features: &[f32; FEATURE_COUNT]) -> f32 {
+        self.predict_scaled(&self.scale(features))
+    }
+
+    pub fn evaluate(&self, samples: &[MatchSample]) -> PredictionEvaluation {
+        let mut out = PredictionEvaluation::default();
+
+        //baseline always predicts the most common result from training
+        let baseline_win = self.training_win_rate >= 0.5;
+
+        for s in samples {
+            let p = self.predict(&s.features);
+            let y = if s.won { 1.0 } else { 0.0 };
+
+            out.total_activities += 1;
+
+            if (p >= 0.5) == s.won {
+                out.correct += 1;
+            }
+
+            if baseline_win == s.won {
+                out.baseline_correct += 1;
+            }
+
+            //clamp to avoid ln(0)
+            let p = p.clamp(f32::EPSILON, 1.0 - f32::EPSILON);
+            out.log_loss_total -= y * p.ln() + (1.0 - y) * (1.0 - p).ln();
+            out.brier_score_total += (p - y).powi(2);
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct PredictionEvaluation {
+    pub total_activities: u32,
+    pub correct: u32,
+    pub baseline_correct: u32,
+    pub log_loss_total: f32,
+    pub brier_score_total: f32,
+}
+
+impl PredictionEvaluation {
+    /// Percent of games where the predicted winner won
+    pub fn accuracy(&self) -> f32 {
+        calculate_percent(self.correct, self.total_activities)
+    }
+
+    /// Accuracy from always predicting the most common training result
+    pub fn baseline_accuracy(&self) -> f32 {
+        calculate_percent(self.baseline_correct, self.total_activities)
+    }
+
+    pub fn log_loss(&self) -> f32 {
+        calculate_avg(self.log_loss_total, self.total_activities)
+    }
+
+    pub fn brier_score(&self) -> f32 {
+        calculate_avg(self.brier_score_total, self.total_activities)
+    }
+}
+
+/// Expected vs actual result for a game
+#[derive(Debug, Clone)]
+pub struct MatchPrediction {
+    pub details: ActivityDetail,
+
+    //probability (0.0 to 1.0) that the member's team would win
+    pub win_probability: f32,
+    pub won: bool,
+}
+
+impl MatchPrediction {
+    /// Probability the model gave to the actual result
+    pub fn actual_probability(&self) -> f32 {
+        if self.won {
+            self.win_probability
+        } else {
+            1.0 - self.win_probability
+        }
+    }
+
+    pub fn is_upset(&self) -> bool {
+        self.actual_probability() < UPSET_PROBABILITY
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct MatchPredictions {
+    //model trained on all games
+    pub model: MatchPredictor,
+
+    //results of training on the older games and predicting the most recent
+    //HOLDOUT_FRACTION games. None if there are not enough games.
+    pub evaluation: Option<PredictionEvaluation>,
+
+    //predictions for games, oldest first. Each game is predicted by a model
+    //which was not trained on it (see predict_out_of_fold). Games without
+    //enough other games to train a model are not included.
+    pub predictions: Vec<MatchPrediction>,
+}
+
+impl MatchPredictions {
+    pub fn with_activities(
+        activities: &[CrucibleActivity],
+        member_id: &str,
+    ) -> Option<MatchPredictions> {
+        let samples = generate_match_samples(activities, member_id);
+
+        if samples.len() < MIN_TRAINING_GAMES {
+            return None;
+        }
+
+        let holdout = (samples.len() as f32 * HOLDOUT_FRACTION).ceil() as usize;
+        let split = samples.len() - holdout;
+
+        let evaluation = if split >= MIN_TRAINING_GAMES {
+            MatchPredictor::train(&samples[..split])
+                .map(|e| e.evaluate(&samples[split..]))
+        } else {
+            None
+        };
+
+        let model = MatchPredictor::train(&samples)?;
+        let predictions = predict_out_of_fold(&samples);
+
+        Some(MatchPredictions {
+            model,
+            evaluation,
+            predictions,
+        })
+    }
+
+    pub fn get_prediction(&self, activity_id: i64) -> Option<&MatchPrediction> {
+        self.predictions
+            .iter()
+            .find(|e| e.details.id == activity_id)
+    }
+}
+
+/// Splits the samples into CROSS_VALIDATION_FOLDS consecutive folds, and
+/// predicts the games in each fold using a model trained on the other folds,
+/// so no game is predicted by a model which was trained on it. Folds where
+/// the other folds have less than MIN_TRAINING_GAMES games are skipped.
+pub fn predict_out_of_fold(samples: &[MatchSample]) -> Vec<MatchPrediction> {
+    let mut out: Vec<MatchPrediction> = Vec::new();
+    let n = samples.len();
+
+    for fold in 0..CROSS_VALIDATION_FOLDS {
+        let start = fold * n / CROSS_VALIDATION_FOLDS;
+        let end = (fold + 1) * n / CROSS_VALIDATION_FOLDS;
+
+        let training: Vec<&MatchSample> = samples[..start]
+            .iter()
+            .chain(samples[end..].iter())
+            .collect();
+
+        if training.len() < MIN_TRAINING_GAMES {
+            continue;
+        }
+
+        let model = match MatchPredictor::train_with_refs(&training) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        for s in &samples[start..end] {
+            out.push(MatchPrediction {
+                details: s.details.clone(),
+                win_probability: model.predict(&s.features),
+                won: s.won,
+            });
+        }
+    }
+
+    out
+}
+
+/// A trained model saved to disk, so the model doesnt have to be retrained
+/// each time a single game is predicted.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedMatchPredictor {
+    pub member_id: String,
+
+    //id of the most recent game the model was trained on
+    pub last_activity_id: i64,
+    pub model: MatchPredictor,
+}
+
+impl SavedMatchPredictor {
+    pub fn get_path(data_dir: &Path, member_id: &str) -> PathBuf {
+        data_dir.join(format!("match_predictor_{}.json", member_id))
+    }
+
+    pub fn load(path: &Path) -> Result<SavedMatchPredictor, Error> {
+        let data = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&data)?)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), Error> {
+        let tmp_path = path.with_extension("tmp");
+
+        fs::write(&tmp_path, serde_json::to_string(&self)?)?;
+        fs::rename(&tmp_path, path)?;
+
+        Ok(())
+    }
+}
+
+/// Predicts the result of a single game, using a model trained only on the
+/// games played before it.
+///
+/// The trained model is saved to the specified path, and reused for later
+/// games until RETRAIN_GAME_COUNT more games have been played. Returns None
+/// if the game is not found, or there are not enough earlier games to train a
+/// model.
+pub fn predict_activity(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    activity_id: i64,
+    path: &Path,
+) -> Result<Option<MatchPrediction>, Error> {
+    let samples = generate_match_samples(activities, member_id);
+
+    let index = match samples.iter().position(|s| s.details.id == activity_id) {
+        Some(e) => e,
+        None => return Ok(None),
+    };
+
+    if index < MIN_TRAINING_GAMES {
+        return Ok(None);
+    }
+
+    let previous = &samples[..index];
+
+    //a saved model can be used if it was only trained on games before this
+    //one, and isnt too out of date
+    let saved_model = match SavedMatchPredictor::load(path) {
+        Ok(e) if e.member_id == member_id => previous
+            .iter()
+            .position(|s| s.details.id == e.last_activity_id)
+            .filter(|i| index - (i + 1) < RETRAIN_GAME_COUNT)
+            .map(|_i| e.model),
+        _ => None,
+    };
+
+    let model = match saved_model {
+        Some(e) => e,
+        None => {
+            let model = match MatchPredictor::train(previous) {
+                Some(e) => e,
+                None => return Ok(None),
+            };
+
+            SavedMatchPredictor {
+                member_id: member_id.to_string(),
+                last_activity_id: previous[previous.len() - 1].details.id,
+                model: model.clone(),
+            }
+            .save(path)?;
+
+            model
+        }
+    };
+
+    let sample = &samples[index];
+    Ok(Some(MatchPrediction {
+        details: sample.details.clone(),
+        win_probability: model.predict(&sample.features),
+        won: sample.won,
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use chrono::{TimeZone, Utc};
+
+    use crate::enums::mode::Mode;
+    use crate::enums::platform::Platform;
+
+    fn sample(
+        id: i64,
+        features: [f32; FEATURE_COUNT],
+        won: bool,
+    ) -> MatchSample {
+        MatchSample {
+            details: ActivityDetail {
+                index_id: id as u32,
+                id,
+                period: Utc.timestamp(1_600_000_000 + id * 60, 0),
+                map_name: "Test Map".to_string(),
+                mode: Mode::ControlQuickplay,
+                platform: Platform::Xbox,
+                director_activity_hash: 0,
+                reference_id: 0,
+            },
+            features,
+            won,
+        }
+    }
+
+    //games are won when the team has a player efficiency advantage
+    fn separable_samples(count: usize) -> Vec<MatchSample> {
+        (0..count)
+            .map(|i| {
+                let advantage = (i % 10) as f32 - 4.5;
+                sample(
+                    i as i64,
+                    [advantage, 0.0, 0.0, 0.0, 0.0, 0.0],
+                    advantage > 0.0,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sigmoid_is_centered() {
+        assert!((sigmoid(0.0) - 0.5).abs() < f32::EPSILON);
+        assert!(sigmoid(10.0) > 0.99);
+        assert!(sigmoid(-10.0) < 0.01);
+        assert!((sigmoid(2.0) + sigmoid(-2.0) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn train_without_samples() {
+        assert!(MatchPredictor::train(&[]).is_none());
+    }
+
+    #[test]
+    fn train_separable_samples() {
+        let samples = separable_samples(100);
+        let model = MatchPredictor::train(&samples).unwrap();
+
+        assert!(model.weights[0] > 0.0);
+        assert!(model.predict(&[4.0, 0.0, 0.0, 0.0, 0.0, 0.0]) > 0.8);
+        assert!(model.predict(&[-4.0, 0.0, 0.0, 0.0, 0.0, 0.0]) < 0.2);
+        assert!((model.training_win_rate - 0.5).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn train_ignores_constant_features() {
+        let samples = separable_samples(50);
+        let model = MatchPredictor::train(&samples).unwrap();
+
+        for i in 1..FEATURE_COUNT {
+            assert!((model.std_devs[i] - 1.0).abs() < f32::EPSILON);
+            assert!(model.weights[i].abs() < f32::EPSILON);
+        }
+
+        let scaled = model.scale(&[0.5, 3.0, 0.0, 0.0, 0.0, 0.0]);
+        assert!(scaled.iter().all(|e| e.is_finite()));
+        assert!((scaled[1] - 3.0).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn evaluate_counts_results() {
+        let samples = separable_samples(50);
+        let model = MatchPredictor::train(&samples).unwrap();
+
+        let evaluation = model.evaluate(&samples[..10]);
+
+        assert_eq!(evaluation.total_activities, 10);
+        assert_eq!(evaluation.correct, 10);
+        assert!((evaluation.accuracy() - 100.0).abs() < f32::EPSILON);
+        assert!((evaluation.baseline_accuracy() - 50.0).abs() < f32::EPSILON);
+        assert!(evaluation.log_loss() > 0.0);
+        assert!(evaluation.brier_score() < 0.25);
+    }
+
+    #[test]
+    fn predict_out_of_fold_uses_other_games() {
+        //every game is a loss except one, so a model which was trained on
+        //the win would give it a higher probability than the other games
+        let mut samples: Vec<MatchSample> = (0..50)
+            .map(|i| sample(i, [0.0, 0.0, 0.0, 0.0, 0.0, 0.0], false))
+            .collect();
+        samples[7] = sample(7, [5.0, 0.0, 0.0, 0.0, 0.0, 0.0], true);
+
+        let predictions = predict_out_of_fold(&samples);
+
+        assert_eq!(predictions.len(), samples.len());
+
+        let win = predictions.iter().find(|e| e.details.id == 7).unwrap();
+        assert!(win.win_probability < 0.5);
+        assert!(win.is_upset());
+    }
+
+    #[test]
+    fn predict_out_of_fold_skips_small_folds() {
+        let samples = separable_samples(MIN_TRAINING_GAMES);
+        assert!(predict_out_of_fold(&samples).is_empty());
+    }
+
+    #[test]
+    fn saved_predictor_round_trip() {
+        let dir = std::env::temp_dir()
+            .join(format!("dcli_matchpredictor_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        let path = SavedMatchPredictor::get_path(&dir, "1234");
+        let model = MatchPredictor::train(&separable_samples(50)).unwrap();
+
+        SavedMatchPredictor {
+            member_id: "1234".to_string(),
+            last_activity_id: 49,
+            model: model.clone(),
+        }
+        .save(&path)
+        .unwrap();
+
+        let loaded = SavedMatchPredictor::load(&path).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(loaded.member_id, "1234");
+        assert_eq!(loaded.last_activity_id, 49);
+        assert_eq!(loaded.model.weights, model.weights);
+
+        let features = [1.0, 0.0, 0.0, 0.0, 0.0, 0.0];
+        assert!(
+            (loaded.model.predict(&features) - model.predict(&features)).abs()
+                < f32::EPSILON
+        );
+    }
+}
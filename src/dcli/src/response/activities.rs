@@ -6,7 +6,8 @@ use crate::enums::platform::Platform;
 use crate::response::drs::{DestinyResponseStatus, IsDestinyAPIResponse};
 use crate::response::utils::str_to_datetime;
 use crate::response::utils::{
-    property_to_u32_value, property_to_value, standing_default, string_to_i64,
+    property_to_id_value, property_to_u32_value, property_to_value,
+    standing_default, string_to_i64,
 };
 
 pub const MAX_ACTIVITIES_REQUEST_COUNT: i32 = 250;
@@ -133,6 +134,10 @@ pub struct ActivityHistoricalStatsValues {
 
     #[serde(rename = "teamScore", deserialize_with = "property_to_value")]
     pub team_score: f32,
+
+    #[serde(rename = "fireteamId", deserialize_with = "property_to_id_value")]
+    #[serde(default)]
+    pub fireteam_id: i64,
 }
 
 //https://bungie-net.github.io/multi/schema_Destiny-HistoricalStats-DestinyHistoricalStatsActivity.html#schema_Destiny-HistoricalStats-DestinyHistoricalStatsActivity
@@ -20,10 +20,13 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+use std::collections::HashMap;
+
 use serde_derive::{Deserialize, Serialize};
 
+use crate::apiutils::RESOURCE_BASE_URL;
+use crate::enums::language::Language;
 use crate::response::drs::{DestinyResponseStatus, IsDestinyAPIResponse};
-use crate::response::utils::prepend_base_url;
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct ManifestResponse {
@@ -50,6 +53,17 @@ pub struct ManifestData {
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct MobileWorldContentPathsData {
-    #[serde(deserialize_with = "prepend_base_url")]
-    pub en: String,
+    //paths to the manifest for each language, keyed by language code
+    #[serde(flatten)]
+    pub paths: HashMap<String, String>,
+}
+
+impl MobileWorldContentPathsData {
+    /// Returns the url for the manifest for the specified language, or None
+    /// if it is not available.
+    pub fn get_url(&self, language: &Language) -> Option<String> {
+        self.paths
+            .get(language.to_code())
+            .map(|e| format!("{}{}", RESOURCE_BASE_URL, e))
+    }
 }
@@ -89,6 +89,45 @@ where
     Ok(helper.basic.value)
 }
 
+/// Deserializes an id value from a stat property. The api returns ids such
+/// as fireteamId as a double, which loses precision, so the id is parsed from
+/// the displayValue instead, falling back to the value if it is not available.
+pub fn property_to_id_value<'de, D>(deserializer: D) -> Result<i64, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Outer {
+        pub basic: Inner,
+    }
+
+    #[derive(Deserialize)]
+    struct Inner {
+        pub value: f64,
+
+        #[serde(rename = "displayValue")]
+        pub display_value: Option<String>,
+    }
+
+    let helper = <Outer>::deserialize(deserializer)?;
+
+    let display_value = match helper.basic.display_value {
+        Some(e) => e,
+        None => return Ok(helper.basic.value as i64),
+    };
+
+    //ids are unsigned, but are stored as signed, so values larger than
+    //i64::MAX wrap around
+    if let Ok(e) = display_value.parse::<i64>() {
+        return Ok(e);
+    }
+
+    match display_value.parse::<u64>() {
+        Ok(e) => Ok(e as i64),
+        Err(_e) => Ok(helper.basic.value as i64),
+    }
+}
+
 /*
 pub fn property_to_standing<'de, D>(deserializer: D) -> Result<Standing, D::Error>
 where
@@ -215,6 +254,30 @@ where
     Ok(dt)
 }
 
+pub fn str_to_datetime_option<'de, D>(
+    deserializer: D,
+) -> Result<Option<DateTime<Utc>>, D::Error>
+where
+    D: serde::de::Deserializer<'de>,
+{
+    let s = match Option::<String>::deserialize(deserializer)? {
+        Some(e) => e,
+        None => return Ok(None),
+    };
+
+    let n = match NaiveDateTime::parse_from_str(&s, API_DATE_TIME_FORMAT) {
+        Ok(e) => e,
+        Err(e) => {
+            return Err(serde::de::Error::custom(format!(
+                "Could not parse date-time : {}",
+                e
+            )))
+        }
+    };
+
+    Ok(Some(DateTime::<Utc>::from_utc(n, Utc)))
+}
+
 pub fn standing_default() -> u32 {
     STANDING_UNKNOWN_MAGIC_NUMBER
 }
@@ -0,0 +1,162 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use chrono::{DateTime, Utc};
+
+use crate::enums::moment::{Moment, SeasonSelection};
+use crate::error::Error;
+use crate::manifestinterface::ManifestInterface;
+use crate::utils::normalize_name;
+
+#[derive(Debug, Clone)]
+pub struct Season {
+    pub number: u32,
+    pub name: String,
+    pub start: DateTime<Utc>,
+
+    /// End of the season. None if the manifest doesnt specify an end date and
+    /// there isnt a following season.
+    pub end: Option<DateTime<Utc>>,
+}
+
+impl Season {
+    pub fn is_active(&self, date_time: &DateTime<Utc>) -> bool {
+        if *date_time < self.start {
+            return false;
+        }
+
+        match self.end {
+            Some(e) => *date_time < e,
+            None => true,
+        }
+    }
+}
+
+/// Season boundaries, loaded from the season definitions in the manifest.
+pub struct Seasons {
+    //sorted by start date
+    seasons: Vec<Season>,
+}
+
+impl Seasons {
+    pub async fn with_manifest(
+        manifest: &mut ManifestInterface,
+    ) -> Result<Seasons, Error> {
+        let definitions = manifest.get_season_definitions().await?;
+
+        //seasons which havent been announced yet dont have a start date
+        let mut seasons: Vec<Season> = definitions
+            .into_iter()
+            .filter_map(|d| {
+                d.start_date.map(|start| Season {
+                    number: d.season_number,
+                    name: d.display_properties.name,
+                    start,
+                    end: d.end_date,
+                })
+            })
+            .collect();
+
+        seasons.sort_by_key(|e| e.start);
+
+        //if a season doesnt have an end date, it ends when the next one starts
+        let starts: Vec<DateTime<Utc>> =
+            seasons.iter().skip(1).map(|e| e.start).collect();
+        for (season, next_start) in seasons.iter_mut().zip(starts) {
+            if season.end.is_none() {
+                season.end = Some(next_start);
+            }
+        }
+
+        Ok(Seasons { seasons })
+    }
+
+    pub fn get_seasons(&self) -> &Vec<Season> {
+        &self.seasons
+    }
+
+    /// Returns the season active at the specified date / time.
+    pub fn get_season_at(&self, date_time: &DateTime<Utc>) -> Option<&Season> {
+        self.seasons.iter().rev().find(|e| e.is_active(date_time))
+    }
+
+    pub fn get_current(&self) -> Option<&Season> {
+        self.get_season_at(&Utc::now())
+    }
+
+    pub fn get_previous(&self) -> Option<&Season> {
+        let current = self.get_current()?;
+
+        self.seasons.iter().rev().find(|e| e.start < current.start)
+    }
+
+    /// Finds a season by number or name. Names are matched ignoring case and
+    /// punctuation, and can be a partial name (i.e. "chosen" matches "Season
+    /// of the Chosen") as long as only one season matches.
+    pub fn find(&self, selection: &SeasonSelection) -> Option<&Season> {
+        match selection {
+            SeasonSelection::Number(n) => {
+                self.seasons.iter().find(|e| e.number == *n)
+            }
+            SeasonSelection::Name(name) => {
+                let name = normalize_name(name);
+
+                if let Some(e) = self
+                    .seasons
+                    .iter()
+                    .find(|e| normalize_name(&e.name) == name)
+                {
+                    return Some(e);
+                }
+
+                let matches: Vec<&Season> = self
+                    .seasons
+                    .iter()
+                    .filter(|e| normalize_name(&e.name).contains(&name))
+                    .collect();
+
+                if matches.len() == 1 {
+                    Some(matches[0])
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// Returns the season for a season moment.
+    pub fn get_season_for_moment(
+        &self,
+        moment: &Moment,
+    ) -> Result<&Season, Error> {
+        let season = match moment {
+            Moment::Season(e) => self.find(e),
+            Moment::CurrentSeason => self.get_current(),
+            Moment::PreviousSeason => self.get_previous(),
+            _ => None,
+        };
+
+        season.ok_or_else(|| Error::SeasonNotFound {
+            description: format!("{}", moment),
+        })
+    }
+}
@@ -31,6 +31,9 @@ use crate::utils::{
 pub struct ActivityStatsContainer {
     pub activities: Vec<Activity>,
 
+    //lowest placement in free for all modes which is treated as a victory
+    ffa_win_placement: u32,
+
     assists: f32,
     score: f32,
     kills: f32,
@@ -59,9 +62,11 @@ pub struct ActivityStatsContainer {
 impl ActivityStatsContainer {
     pub fn with_activities(
         activities: Vec<Activity>,
+        ffa_win_placement: u32,
     ) -> ActivityStatsContainer {
         let mut a = ActivityStatsContainer {
             activities,
+            ffa_win_placement,
             assists: 0.0,
             score: 0.0,
             kills: 0.0,
@@ -127,8 +132,11 @@ impl ActivityStatsContainer {
             self.opponents_defeated += a.values.opponents_defeated;
             self.time_played_seconds += a.values.time_played_seconds;
 
-            let standing =
-                Standing::from_mode(a.values.standing, &a.details.mode);
+            let standing = Standing::from_mode(
+                a.values.standing,
+                &a.details.mode,
+                self.ffa_win_placement,
+            );
             match standing {
                 Standing::Victory => {
                     self.wins += 1.0;
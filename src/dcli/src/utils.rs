@@ -29,6 +29,8 @@ use std::path::PathBuf;
 use chrono::{DateTime, Datelike, Duration, Local, TimeZone, Timelike, Utc};
 use crossterm::{execute, terminal};
 
+use crate::enums::mode::Mode;
+use crate::enums::moment::{DateTimePeriod, Moment};
 use crate::error::Error;
 
 //use chrono::prelude::*;
@@ -180,6 +182,34 @@ pub fn human_date_format(start_time: &DateTime<Utc>) -> String {
     format!("{}", local.format(format_str))
 }
 
+/// Returns the title describing the mode and time period of the activities
+/// being displayed.
+pub fn build_activity_title(
+    mode: &Mode,
+    time_period: &DateTimePeriod,
+    moment: &Moment,
+    end_moment: Option<&Moment>,
+) -> String {
+    let mode = uppercase_first_char(&format!("{}", mode));
+    let start_time = human_date_format(&time_period.get_start());
+    let end_time = human_date_format(&time_period.get_end());
+
+    match end_moment {
+        //season moments default to the whole season
+        None if moment.is_season() => format!(
+            "{} activities from {} to {} ({})",
+            mode, start_time, end_time, moment
+        ),
+        None | Some(Moment::Now) => {
+            format!("{} activities since {} ({})", mode, start_time, moment)
+        }
+        Some(end_moment) => format!(
+            "{} activities from {} ({}) to {} ({})",
+            mode, start_time, moment, end_time, end_moment
+        ),
+    }
+}
+
 //this could use some more work and polish. Add "and" before the last item.
 pub fn human_duration(seconds: u32) -> String {
     let dt =
@@ -238,9 +268,15 @@ pub fn get_last_weekly_reset() -> DateTime<Utc> {
 }
 
 pub fn get_last_friday_reset() -> DateTime<Utc> {
+    get_friday_reset_before(&Utc::now())
+}
+
+/// Returns the most recent friday (weekend) reset at or before the specified
+/// date / time.
+pub fn get_friday_reset_before(date: &DateTime<Utc>) -> DateTime<Utc> {
     //get a hardcoded past reset date / time (17:00 UTC every friday)
     let past_reset: DateTime<Utc> = Utc.ymd(2020, 12, 4).and_hms(18, 0, 0);
-    find_previous_moment(past_reset, WEEK_IN_SECONDS)
+    find_previous_moment_from(past_reset, WEEK_IN_SECONDS, date)
 }
 
 pub fn get_last_daily_reset() -> DateTime<Utc> {
@@ -254,12 +290,22 @@ fn find_previous_moment(
     past_reset: DateTime<Utc>,
     interval: i64,
 ) -> DateTime<Utc> {
-    let now: DateTime<Utc> = Utc::now();
+    find_previous_moment_from(past_reset, interval, &Utc::now())
+}
 
-    //get total seconds between now and the past reset
+fn find_previous_moment_from(
+    past_reset: DateTime<Utc>,
+    interval: i64,
+    date: &DateTime<Utc>,
+) -> DateTime<Utc> {
+    //get total seconds between the date and the past reset
     //take the mod of that divided by a week in seconds
-    //subtract that amount from current date / time to find previous reset
-    now - Duration::seconds((now - past_reset).num_seconds() % interval)
+    //subtract that amount from the date / time to find previous reset
+    //(rem_euclid so dates before the past reset are handled)
+    *date
+        - Duration::seconds(
+            (*date - past_reset).num_seconds().rem_euclid(interval),
+        )
 }
 
 pub fn determine_data_dir(dir: Option<PathBuf>) -> Result<PathBuf, Error> {
@@ -303,6 +349,68 @@ pub fn calculate_percent(value: u32, total: u32) -> f32 {
     (value as f32 / total as f32) * 100.0
 }
 
+/// Returns the population standard deviation of the values
+pub fn calculate_standard_deviation(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let avg = calculate_avg(values.iter().sum(), values.len() as u32);
+    let variance: f32 = values.iter().map(|v| (v - avg).powi(2)).sum::<f32>()
+        / values.len() as f32;
+
+    variance.sqrt()
+}
+
+/// Returns the Pearson correlation coefficient (-1.0 to 1.0) between the two
+/// sets of values. Returns 0.0 if it cant be calculated.
+pub fn calculate_correlation(x: &[f32], y: &[f32]) -> f32 {
+    let n = std::cmp::min(x.len(), y.len());
+    if n < 2 {
+        return 0.0;
+    }
+
+    let x = &x[..n];
+    let y = &y[..n];
+
+    let x_avg = calculate_avg(x.iter().sum(), n as u32);
+    let y_avg = calculate_avg(y.iter().sum(), n as u32);
+
+    let mut covariance = 0.0;
+    let mut x_variance = 0.0;
+    let mut y_variance = 0.0;
+
+    for (xv, yv) in x.iter().zip(y.iter()) {
+        let dx = xv - x_avg;
+        let dy = yv - y_avg;
+
+        covariance += dx * dy;
+        x_variance += dx * dx;
+        y_variance += dy * dy;
+    }
+
+    let denominator = (x_variance * y_variance).sqrt();
+    if denominator == 0.0 {
+        return 0.0;
+    }
+
+    covariance / denominator
+}
+
+/// Lower cases the value, removes apostrophes and replaces other punctuation
+/// with spaces, so that "Javelin-4" and "javelin 4" are the same.
+pub fn normalize_name(value: &str) -> String {
+    value
+        .to_lowercase()
+        .chars()
+        .filter(|c| *c != '\'' && *c != '\u{2019}')
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
 pub fn truncate_ascii_string(input: &str, max_len: usize) -> String {
     if input.chars().count() <= max_len {
         return input.to_string();
@@ -27,6 +27,7 @@ use dcli::manifest::definitions::{
     ActivityDefinitionData, DestinationDefinitionData, PlaceDefinitionData,
 };
 //use dcli::error::Error;
+use dcli::enums::language::Language;
 use dcli::enums::mode::Mode;
 use dcli::enums::platform::Platform;
 use dcli::manifestinterface::ManifestInterface;
@@ -71,6 +72,14 @@ struct Opt {
     #[structopt(short = "v", long = "verbose")]
     verbose: bool,
 
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
     /// Directory where Destiny 2 manifest database file is stored. (optional)
     ///
     /// This will normally be downloaded using the dclim tool, and stored in a file
@@ -140,13 +149,14 @@ async fn main() {
         }
     };
 
-    let mut manifest = match ManifestInterface::new(&data_dir, false).await {
-        Ok(e) => e,
-        Err(e) => {
-            print_error("Manifest Error", e);
-            std::process::exit(EXIT_FAILURE);
-        }
-    };
+    let mut manifest =
+        match ManifestInterface::new(&data_dir, &opt.language, false).await {
+            Ok(e) => e,
+            Err(e) => {
+                print_error("Manifest Error", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
 
     print_verbose(
         &format!(
@@ -21,10 +21,12 @@
 */
 
 use std::str::FromStr;
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
 
 use dcli::{
-    apiinterface::ApiInterface,
     crucible::{
         AggregateCruciblePerformances, CrucibleActivity,
         CruciblePlayerPerformance, Player,
@@ -35,12 +37,18 @@ use dcli::{
 use dcli::{enums::platform::Platform, utils::truncate_ascii_string};
 
 use dcli::enums::mode::Mode;
+use dcli::enums::moment::{DateTimePeriod, Moment};
 use dcli::manifestinterface::ManifestInterface;
+use dcli::matchpredictor::{
+    predict_activity, MatchPrediction, SavedMatchPredictor,
+};
 
 use dcli::enums::character::CharacterClassSelection;
+use dcli::enums::language::Language;
 use dcli::error::Error;
 
 use dcli::activitystoreinterface::ActivityStoreInterface;
+use dcli::apiinterface::ApiInterface;
 
 use dcli::utils::{
     determine_data_dir, format_f32, human_date_format, human_duration,
@@ -77,42 +85,99 @@ fn generate_score(data: &CrucibleActivity) -> String {
 }
 
 async fn get_combat_ratings(
+    store: &mut ActivityStoreInterface,
     data: &CrucibleActivity,
     verbose: bool,
 ) -> HashMap<u64, f32> {
-    let mut players: Vec<&Player> = Vec::new();
+    //if ratings were snapshotted when the activity was synced, we dont need
+    //to make any api calls to display them
+    match store.retrieve_combat_ratings(data).await {
+        Ok(e) if !e.is_empty() => return e,
+        Ok(_e) => (),
+        Err(e) => {
+            print_verbose(
+                &format!("Could not retrieve stored combat ratings : {}", e),
+                verbose,
+            );
+        }
+    };
 
+    //activity was synced without ratings, so retrieve the current ratings
+    let api = match ApiInterface::new(verbose) {
+        Ok(e) => e,
+        Err(_e) => return HashMap::new(),
+    };
+
+    let mut player_refs: Vec<&Player> = Vec::new();
     for t in data.teams.values() {
         for p in &t.player_performances {
-            players.push(&p.player);
+            player_refs.push(&p.player);
         }
     }
 
-    let elo_hash: HashMap<u64, f32> = match ApiInterface::new(verbose) {
-        Ok(e) => {
-            let mut player_refs: Vec<&Player> = Vec::new();
-            for t in data.teams.values() {
-                for p in &t.player_performances {
-                    player_refs.push(&p.player);
-                }
-            }
+    api.retrieve_combat_ratings(&player_refs, &data.details.mode)
+        .await
+        .unwrap_or_default()
+}
 
-            match e
-                .retrieve_combat_ratings(&player_refs, &data.details.mode)
-                .await
-            {
-                Ok(e) => e,
-                Err(_e) => HashMap::new(),
-            }
+async fn get_match_prediction(
+    store: &mut ActivityStoreInterface,
+    manifest: &mut ManifestInterface,
+    data: &CrucibleActivity,
+    member_id: &str,
+    platform: &Platform,
+    data_dir: &Path,
+    verbose: bool,
+) -> Option<MatchPrediction> {
+    //the model is trained on the stored team based games for the member which
+    //were played before the activity
+    let time_period = DateTimePeriod::with_start_end_time(
+        Moment::AllTime.get_date_time()?,
+        Moment::Now.get_date_time()?,
+    )
+    .ok()?;
+
+    let activities = match store
+        .retrieve_crucible_activities_since(
+            member_id,
+            &CharacterClassSelection::All,
+            platform,
+            &Mode::AllPvP,
+            &time_period,
+            manifest,
+        )
+        .await
+    {
+        Ok(e) => e?,
+        Err(e) => {
+            print_verbose(
+                &format!(
+                    "Could not retrieve activities for prediction : {}",
+                    e
+                ),
+                verbose,
+            );
+            return None;
         }
-        Err(_e) => HashMap::new(),
     };
-    elo_hash
+
+    let path = SavedMatchPredictor::get_path(data_dir, member_id);
+    match predict_activity(&activities, member_id, data.details.id, &path) {
+        Ok(e) => e,
+        Err(e) => {
+            print_verbose(
+                &format!("Could not predict activity result : {}", e),
+                verbose,
+            );
+            None
+        }
+    }
 }
 
 fn print_default(
     data: &CrucibleActivity,
     elo_hash: &HashMap<u64, f32>,
+    prediction: Option<&MatchPrediction>,
     member_id: &str,
     details: bool,
     weapon_count: u32,
@@ -160,6 +225,18 @@ fn print_default(
     println!("{}", standing_str);
     println!("{} {}", generate_score(data), completion_reason);
 
+    if let Some(e) = data.get_member_round_results(member_id) {
+        println!("Rounds {} (won - lost)", e);
+    }
+
+    if let Some(e) = prediction {
+        println!(
+            "Expected win probability {}%{}",
+            format_f32(e.win_probability * 100.0, 1),
+            if e.is_upset() { " (UPSET!)" } else { "" }
+        );
+    }
+
     println!();
 
     let header = format!("{:<0name_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
@@ -193,7 +270,16 @@ fn print_default(
         let mut elo_team_count = 0;
         let mut elo_team_total = 0.0;
 
-        println!("[{}] {} Team {}!", v.score, v.display_name, v.standing);
+        match data.get_team_round_results(v.id) {
+            Some(e) => println!(
+                "[{}] {} Team {}! ({} rounds)",
+                v.score, v.display_name, v.standing, e
+            ),
+            None => println!(
+                "[{}] {} Team {}!",
+                v.score, v.display_name, v.standing
+            ),
+        };
         println!("{}", team_title_border);
         println!("{}", header);
         println!("{}", header_border);
@@ -528,6 +614,14 @@ struct Opt {
     #[structopt(short = "C", long = "class", default_value = "last_active")]
     character_class_selection: CharacterClassSelection,
 
+    /// Lowest placement counted as a win in free for all modes
+    ///
+    /// Free for all modes such as Rumble rank players by placement. Any
+    /// placement up to and including this value will be treated as a victory
+    /// when calculating wins, losses and win rates.
+    #[structopt(long = "ffa-win-placement", default_value = "3")]
+    ffa_win_placement: u32,
+
     ///Print out additional information
     ///
     ///Output is printed to stderr.
@@ -541,6 +635,25 @@ struct Opt {
     #[structopt(short = "N", long = "no-sync")]
     no_sync: bool,
 
+    /// Store the combat rating of each player in newly synced activities
+    ///
+    /// If flag is set, the current combat rating for the activity mode of each
+    /// player in the lobby will be retrieved and stored when new activities are
+    /// synced. Ratings are only stored for activities played within the last 48
+    /// hours. Activities synced with this flag set (via dcliad or dclias) will
+    /// display the stored ratings. Other activities display the current ratings
+    /// retrieved from the api.
+    #[structopt(short = "r", long = "sync-combat-ratings")]
+    sync_combat_ratings: bool,
+
+    /// Estimate the win probability for the activity
+    ///
+    /// If flag is set, a win probability model will be trained on all of the
+    /// team based games in the activity store, and used to display the expected
+    /// result for the activity, and whether the actual result was an upset.
+    #[structopt(long = "predict")]
+    predict: bool,
+
     /// Display extended activity details
     ///
     /// If flag is set, additional information will be displayed, including per
@@ -559,6 +672,14 @@ struct Opt {
     #[structopt(long = "activity-index", short = "a")]
     activity_index: Option<u32>,
 
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
     /// Directory where Destiny 2 manifest and activity database files are stored. (optional)
     ///
     /// This will normally be downloaded using the dclim and dclias tools, and uses
@@ -593,18 +714,22 @@ async fn main() {
             }
         };
 
-    let mut manifest = match ManifestInterface::new(&data_dir, false).await {
-        Ok(e) => e,
-        Err(e) => {
-            print_error(
-                "Could not initialize manifest. Have you run dclim?",
-                e,
-            );
-            std::process::exit(EXIT_FAILURE);
-        }
-    };
+    let mut manifest =
+        match ManifestInterface::new(&data_dir, &opt.language, false).await {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize manifest. Have you run dclim?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    store.set_ffa_win_placement(opt.ffa_win_placement);
 
     if !opt.no_sync {
+        store.set_sync_combat_ratings(opt.sync_combat_ratings);
         match store.sync(&opt.member_id, &opt.platform).await {
             Ok(_e) => (),
             Err(e) => {
@@ -642,11 +767,27 @@ async fn main() {
         }
     };
 
-    let elo_hash = get_combat_ratings(&data, opt.verbose).await;
+    let elo_hash = get_combat_ratings(&mut store, &data, opt.verbose).await;
+
+    let prediction = if opt.predict {
+        get_match_prediction(
+            &mut store,
+            &mut manifest,
+            &data,
+            &opt.member_id,
+            &opt.platform,
+            &data_dir,
+            opt.verbose,
+        )
+        .await
+    } else {
+        None
+    };
 
     print_default(
         &data,
         &elo_hash,
+        prediction.as_ref(),
         &opt.member_id,
         opt.details,
         opt.weapon_count,
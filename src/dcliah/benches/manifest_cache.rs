@@ -0,0 +1,191 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+// Benchmarks retrieving all time activity data (which does a manifest lookup
+// for each activity and weapon) with the manifest read from disk, and with the
+// manifest cached in memory.
+//
+// Requires an existing activity store and manifest (run dclias and dclim first).
+// Run with:
+//
+// DCLI_BENCH_MEMBER_ID=<member-id> DCLI_BENCH_PLATFORM=<platform> cargo bench -p dcliah
+//
+// DCLI_BENCH_DATA_DIR can be set to use a specific data directory, and
+// DCLI_BENCH_ITERATIONS to change the number of iterations (default 5).
+
+use std::env;
+use std::path::PathBuf;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+
+use dcli::activitystoreinterface::ActivityStoreInterface;
+use dcli::enums::character::CharacterClassSelection;
+use dcli::enums::language::Language;
+use dcli::enums::mode::Mode;
+use dcli::enums::moment::DateTimePeriod;
+use dcli::enums::platform::Platform;
+use dcli::manifestinterface::ManifestInterface;
+use dcli::utils::{determine_data_dir, get_destiny2_launch_date, EXIT_FAILURE};
+
+const DEFAULT_ITERATIONS: u32 = 5;
+
+struct BenchResult {
+    open: Duration,
+    retrieve: Duration,
+    activity_count: usize,
+}
+
+async fn run(
+    data_dir: &PathBuf,
+    member_id: &str,
+    platform: &Platform,
+    cache: bool,
+) -> BenchResult {
+    let mut store = ActivityStoreInterface::init_with_path(data_dir, false)
+        .await
+        .expect("Could not initialize activity store. Have you run dclias?");
+
+    let time_period = DateTimePeriod::with_start_end_time(
+        get_destiny2_launch_date(),
+        Utc::now(),
+    )
+    .unwrap();
+
+    let start = Instant::now();
+    let mut manifest =
+        ManifestInterface::new(data_dir, &Language::English, cache)
+            .await
+            .expect("Could not initialize manifest. Have you run dclim?");
+    let open = start.elapsed();
+
+    let start = Instant::now();
+    let activities = store
+        .retrieve_activities_since(
+            member_id,
+            &CharacterClassSelection::All,
+            platform,
+            &Mode::AllPvP,
+            &time_period,
+            &mut manifest,
+        )
+        .await
+        .expect("Could not retrieve data from activity store.");
+    let retrieve = start.elapsed();
+
+    let _ = manifest.close().await;
+
+    BenchResult {
+        open,
+        retrieve,
+        activity_count: activities.map(|e| e.len()).unwrap_or(0),
+    }
+}
+
+fn mean(durations: &[Duration]) -> Duration {
+    let total: Duration = durations.iter().sum();
+    total / durations.len().max(1) as u32
+}
+
+fn format_ms(d: Duration) -> String {
+    format!("{:.1} ms", d.as_secs_f64() * 1000.0)
+}
+
+#[tokio::main]
+async fn main() {
+    let member_id = env::var("DCLI_BENCH_MEMBER_ID").ok();
+    let platform = env::var("DCLI_BENCH_PLATFORM")
+        .ok()
+        .and_then(|e| Platform::from_str(&e).ok());
+
+    //fail, rather than skip, so a misconfigured run isnt mistaken for a
+    //benchmark result
+    if member_id.is_none() || platform.is_none() {
+        eprintln!(
+            "DCLI_BENCH_MEMBER_ID and DCLI_BENCH_PLATFORM must be set to run the manifest cache benchmark."
+        );
+        std::process::exit(EXIT_FAILURE);
+    }
+
+    let member_id = member_id.unwrap();
+    let platform = platform.unwrap();
+
+    let data_dir = determine_data_dir(
+        env::var("DCLI_BENCH_DATA_DIR").ok().map(PathBuf::from),
+    )
+    .expect("Could not determine data directory.");
+
+    let iterations: u32 = env::var("DCLI_BENCH_ITERATIONS")
+        .ok()
+        .and_then(|e| e.parse().ok())
+        .unwrap_or(DEFAULT_ITERATIONS)
+        .max(1);
+
+    println!(
+        "{:<12}{:>14}{:>14}{:>14}",
+        "MANIFEST", "OPEN", "RETRIEVE", "TOTAL"
+    );
+
+    let mut totals: Vec<Duration> = Vec::new();
+    let mut retrieves: Vec<Duration> = Vec::new();
+    let mut activity_count = 0;
+    for cache in [false, true].iter() {
+        let mut open: Vec<Duration> = Vec::new();
+        let mut retrieve: Vec<Duration> = Vec::new();
+
+        for _ in 0..iterations {
+            let r = run(&data_dir, &member_id, &platform, *cache).await;
+            open.push(r.open);
+            retrieve.push(r.retrieve);
+            activity_count = r.activity_count;
+        }
+
+        let label = if *cache { "Memory" } else { "Disk" };
+        let total = mean(&open) + mean(&retrieve);
+        println!(
+            "{:<12}{:>14}{:>14}{:>14}",
+            label,
+            format_ms(mean(&open)),
+            format_ms(mean(&retrieve)),
+            format_ms(total)
+        );
+
+        totals.push(total);
+        retrieves.push(mean(&retrieve));
+    }
+
+    println!();
+    println!(
+        "{} activities, mean of {} iterations",
+        activity_count, iterations
+    );
+    println!(
+        "Retrieve speedup : {:.2}x",
+        retrieves[0].as_secs_f64()
+            / retrieves[1].as_secs_f64().max(f64::EPSILON)
+    );
+    println!(
+        "Total speedup    : {:.2}x",
+        totals[0].as_secs_f64() / totals[1].as_secs_f64().max(f64::EPSILON)
+    );
+}
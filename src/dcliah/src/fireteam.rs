@@ -0,0 +1,118 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::BTreeMap;
+
+use dcli::crucible::{
+    AggregateCruciblePerformances, CruciblePlayerActivityPerformance,
+    CruciblePlayerPerformance,
+};
+use dcli::utils::{format_f32, repeat_str};
+
+pub fn format_fireteam_size(size: u32) -> String {
+    match size {
+        0 => "Unknown".to_string(),
+        1 => "Solo".to_string(),
+        2 => "Duo".to_string(),
+        3 => "Trio".to_string(),
+        _ => format!("{} Stack", size),
+    }
+}
+
+fn print_breakdown(
+    title: &str,
+    data: &[CruciblePlayerActivityPerformance],
+    get_size: fn(&CruciblePlayerActivityPerformance) -> u32,
+) {
+    let mut groups: BTreeMap<u32, Vec<&CruciblePlayerPerformance>> =
+        BTreeMap::new();
+
+    for d in data {
+        groups.entry(get_size(d)).or_default().push(&d.performance);
+    }
+
+    let col_w = 10;
+    let name_col_w = 24;
+
+    let header = format!(
+        "{:<0name_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        title,
+        "GAMES",
+        "WINS",
+        "LOSSES",
+        "WIN %",
+        "K/D",
+        "EFF",
+        "MERCY",
+        col_w = col_w,
+        name_col_w = name_col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for (size, performances) in &groups {
+        let aggregate =
+            AggregateCruciblePerformances::with_performances(performances);
+
+        println!(
+            "{:<0name_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            format_fireteam_size(*size),
+            aggregate.total_activities.to_string(),
+            aggregate.wins.to_string(),
+            aggregate.losses.to_string(),
+            format!("{}%", format_f32(aggregate.win_rate, 2)),
+            format_f32(aggregate.kills_deaths_ratio, 2),
+            format_f32(aggregate.efficiency, 2),
+            aggregate.total_mercy.to_string(),
+            col_w = col_w,
+            name_col_w = name_col_w,
+        );
+    }
+}
+
+pub fn print_fireteams(data: &[CruciblePlayerActivityPerformance]) {
+    //fireteam data is not available (i.e. data synced with an older version)
+    if data.iter().all(|d| d.performance.stats.fireteam_size == 0) {
+        return;
+    }
+
+    println!("FIRETEAMS");
+    println!("==================");
+
+    print_breakdown("OUR FIRETEAM", data, |d| {
+        d.performance.stats.fireteam_size
+    });
+    println!();
+
+    print_breakdown("OPPONENT LARGEST", data, |d| {
+        d.performance.stats.opponent_fireteam_size
+    });
+
+    println!();
+    println!("OUR FIRETEAM - Size of the fireteam you played in");
+    println!(
+        "OPPONENT LARGEST - Size of the largest fireteam on the opposing team"
+    );
+    println!("Unknown - Fireteam data is not available for the activity");
+    println!();
+}
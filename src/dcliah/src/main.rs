@@ -20,20 +20,27 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+mod fireteam;
+mod placement;
+mod rounds;
+mod trials;
+
 use std::path::PathBuf;
 use std::str::FromStr;
 
 use chrono::{DateTime, Utc};
+use dcli::enums::language::Language;
 use dcli::enums::standing::Standing;
 use dcli::enums::{
     completionreason::CompletionReason,
     moment::{DateTimePeriod, Moment},
 };
+use dcli::error::Error;
 use dcli::manifestinterface::ManifestInterface;
 use dcli::{
     crucible::{
         AggregateCruciblePerformances, CruciblePlayerActivityPerformance,
-        CruciblePlayerPerformance,
+        CruciblePlayerPerformance, Milestone,
     },
     enums::mode::Mode,
     utils::{calculate_ratio, human_duration},
@@ -46,8 +53,7 @@ use dcli::enums::weaponsort::WeaponSort;
 use dcli::activitystoreinterface::ActivityStoreInterface;
 
 use dcli::utils::{
-    determine_data_dir, format_f32, human_date_format, repeat_str,
-    uppercase_first_char,
+    build_activity_title, determine_data_dir, format_f32, repeat_str,
 };
 //use dcli::utils::EXIT_FAILURE;
 use dcli::utils::EXIT_FAILURE;
@@ -78,16 +84,13 @@ fn print_default(
     mode: &Mode,
     time_period: &DateTimePeriod,
     moment: &Moment,
-    end_moment: &Moment,
+    end_moment: Option<&Moment>,
     weapon_count: &u32,
     weapon_sort: &WeaponSort,
 ) {
     //todo: might want to look at buffering output
     //https://rust-cli.github.io/book/tutorial/output.html
 
-    let start_time = time_period.get_start();
-    let end_time = time_period.get_end();
-
     let performances = data;
 
     let cpp: Vec<&CruciblePlayerPerformance> =
@@ -99,29 +102,10 @@ fn print_default(
     let display_count = std::cmp::min(activity_count, *activity_limit as usize);
     let is_limited = activity_count != display_count;
 
-    let start_time_label = human_date_format(&start_time);
-    let end_time_label = human_date_format(&end_time);
-
     println!();
     println!();
 
-    let title = if end_moment == &Moment::Now {
-        format!(
-            "{mode} activities since {start_time} ({moment})",
-            mode = uppercase_first_char(&format!("{}", mode)),
-            start_time = start_time_label,
-            moment = moment,
-        )
-    } else {
-        format!(
-            "{mode} activities from {start_time} ({moment}) to {end_time} ({end_moment})",
-            mode = uppercase_first_char(&format!("{}", mode)),
-            start_time = start_time_label,
-            moment = moment,
-            end_time = end_time_label,
-            end_moment = end_moment,
-        )
-    };
+    let title = build_activity_title(mode, time_period, moment, end_moment);
 
     println!();
     println!("ACTIVITIES");
@@ -539,11 +523,9 @@ struct Opt {
     /// Valid values include daily (last daily reset), weekend
     /// (last weekend reset on Friday), weekly (last weekly reset on Tuesday),
     /// day (last day), week (last week), month (last month), all_time and custom
-    /// as well as the following season moments launch, curse_of_osiris, warmind,
-    /// season_of_the_outlaw, season_of_the_forge, season_of_the_drifter,
-    /// season_of_opulence, season_of_the_undying, season_of_dawn,
-    /// season_of_the_worthy, season_of_arrivals, season_of_the_hunt,
-    /// season_of_the_chosen.
+    /// as well as the season moments current_season, previous_season and
+    /// season:<name-or-number> (i.e. season:13 or season:chosen). Season
+    /// moments are the start of the season, loaded from the manifest.
     ///
     /// When custom is specified, the custom start date in RFC3339 format must
     /// be specified with the --custom-time argument.
@@ -566,11 +548,12 @@ struct Opt {
     /// Valid values include daily (last daily reset), weekend
     /// (last weekend reset on Friday), weekly (last weekly reset on Tuesday),
     /// day (last day), week (last week), month (last month), all_time and custom
-    /// as well as the following season moments launch, curse_of_osiris, warmind,
-    /// season_of_the_outlaw, season_of_the_forge, season_of_the_drifter,
-    /// season_of_opulence, season_of_the_undying, season_of_dawn,
-    /// season_of_the_worthy, season_of_arrivals, season_of_the_hunt,
-    /// season_of_the_chosen.
+    /// as well as the season moments current_season, previous_season and
+    /// season:<name-or-number> (i.e. season:13 or season:chosen). Season
+    /// moments are the start of the season, loaded from the manifest.
+    ///
+    /// Defaults to now, or to the end of the season when --moment is a season
+    /// moment.
     ///
     /// When custom is specified, the custom start date in RFC3339 format must
     /// be specified with the --end-custom-time argument.
@@ -578,8 +561,8 @@ struct Opt {
     /// For example:
     /// --moment custom --end-custom-time 2020-12-08T17:00:00.774187+00:00
     #[structopt(long = "end-moment", parse(try_from_str=parse_and_validate_moment), 
-        short = "E", default_value = "now")]
-    end_moment: Moment,
+        short = "E")]
+    end_moment: Option<Moment>,
 
     /// Activity mode to return stats for
     ///
@@ -605,6 +588,29 @@ struct Opt {
     #[structopt(long = "weapon-count", short = "w", default_value = "5")]
     weapon_count: u32,
 
+    /// Only include activities where your fireteam was the specified size
+    ///
+    /// For example, 1 will only include activities played solo, and 3 will
+    /// only include activities played in a fireteam of 3.
+    #[structopt(long = "fireteam-size")]
+    fireteam_size: Option<u32>,
+
+    /// Only include activities where the largest opposing fireteam was the
+    /// specified size
+    ///
+    /// For example, 1 will only include activities where all opponents
+    /// were playing solo.
+    #[structopt(long = "opponent-fireteam-size")]
+    opponent_fireteam_size: Option<u32>,
+
+    /// Lowest placement counted as a win in free for all modes
+    ///
+    /// Free for all modes such as Rumble rank players by placement. Any
+    /// placement up to and including this value will be treated as a victory
+    /// when calculating wins, losses and win rates.
+    #[structopt(long = "ffa-win-placement", default_value = "3")]
+    ffa_win_placement: u32,
+
     /// Character to retrieve data for
     ///
     /// Valid values include hunter, titan, warlock, last_active and all.
@@ -631,6 +637,14 @@ struct Opt {
     #[structopt(short = "N", long = "no-sync")]
     no_sync: bool,
 
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
     /// Directory where Destiny 2 manifest and activity database files are stored. (optional)
     ///
     /// This will normally be downloaded using the dclim and dclias tools, and uses
@@ -651,29 +665,44 @@ async fn main() {
         }
     };
 
-    let start_time = match opt.moment {
-        Moment::Custom => {
-            opt.custom_time.unwrap() //note, this should be ok, because struct opt should ensure valid value
-        }
-        _ => opt.moment.get_date_time(),
-    };
-
-    let end_time = match opt.end_moment {
-        Moment::Custom => {
-            opt.end_custom_time.unwrap() //note, this should be ok, because struct opt should ensure valid value
-        }
-        _ => opt.end_moment.get_date_time(),
-    };
-
-    let time_period =
-        match DateTimePeriod::with_start_end_time(start_time, end_time) {
+    //the manifest isnt cached in memory, since even for all time queries the
+    //faster lookups dont make up for the time to load it (see the
+    //manifest_cache benchmark)
+    let mut manifest =
+        match ManifestInterface::new(&data_dir, &opt.language, false).await {
             Ok(e) => e,
-            Err(_e) => {
-                eprintln!("--end-moment must be greater than --moment");
+            Err(e) => {
+                print_error(
+                    "Could not initialize manifest. Have you run dclim?",
+                    e,
+                );
                 std::process::exit(EXIT_FAILURE);
             }
         };
 
+    let time_period = match DateTimePeriod::with_moments(
+        &opt.moment,
+        opt.custom_time,
+        opt.end_moment.as_ref(),
+        opt.end_custom_time,
+        &mut manifest,
+    )
+    .await
+    {
+        Ok(e) => e,
+        Err(Error::DateTimePeriodOrder) => {
+            eprintln!("--end-moment must be greater than --moment");
+            std::process::exit(EXIT_FAILURE);
+        }
+        Err(e) => {
+            print_error(
+                "Could not determine --moment / --end-moment date / time.",
+                e,
+            );
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
     let mut store =
         match ActivityStoreInterface::init_with_path(&data_dir, opt.verbose)
             .await
@@ -688,16 +717,7 @@ async fn main() {
             }
         };
 
-    let mut manifest = match ManifestInterface::new(&data_dir, false).await {
-        Ok(e) => e,
-        Err(e) => {
-            print_error(
-                "Could not initialize manifest. Have you run dclim?",
-                e,
-            );
-            std::process::exit(EXIT_FAILURE);
-        }
-    };
+    store.set_ffa_win_placement(opt.ffa_win_placement);
 
     if !opt.no_sync {
         match store.sync(&opt.member_id, &opt.platform).await {
@@ -732,7 +752,15 @@ async fn main() {
         return;
     }
 
-    let data: Vec<CruciblePlayerActivityPerformance> = data.unwrap();
+    let mut data: Vec<CruciblePlayerActivityPerformance> = data.unwrap();
+
+    if let Some(e) = opt.fireteam_size {
+        data.retain(|d| d.performance.stats.fireteam_size == e);
+    }
+
+    if let Some(e) = opt.opponent_fireteam_size {
+        data.retain(|d| d.performance.stats.opponent_fireteam_size == e);
+    }
 
     if data.is_empty() {
         println!("No activities found");
@@ -745,8 +773,57 @@ async fn main() {
         &opt.mode,
         &time_period,
         &opt.moment,
-        &opt.end_moment,
+        opt.end_moment.as_ref(),
         &opt.weapon_count,
         &opt.weapon_sort,
     );
+
+    fireteam::print_fireteams(&data);
+    rounds::print_rounds(&data);
+    placement::print_placement(&data, opt.ffa_win_placement);
+
+    if opt.mode == Mode::TrialsOfOsiris {
+        trials::print_trials(&data, opt.activity_limit);
+    }
+
+    let milestones = match store
+        .retrieve_milestones(&opt.member_id, &time_period)
+        .await
+    {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Could not retrieve milestones.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    print_milestones(&milestones, &mut manifest).await;
+}
+
+async fn print_milestones(
+    milestones: &[Milestone],
+    manifest: &mut ManifestInterface,
+) {
+    if milestones.is_empty() {
+        return;
+    }
+
+    let date_col_w = 20;
+    let mode_col_w = 20;
+
+    println!("MILESTONES");
+    println!("==================");
+
+    for m in milestones.iter() {
+        let description = m.get_description(manifest).await;
+        println!(
+            "{:<0date_col_w$}{:<0mode_col_w$}{}",
+            m.period.format("%B %-d, %Y"),
+            format!("{}", m.mode),
+            description,
+            date_col_w = date_col_w,
+            mode_col_w = mode_col_w,
+        );
+    }
+    println!();
 }
@@ -0,0 +1,123 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use dcli::crucible::{
+    AggregatePlacements, CruciblePlayerActivityPerformance,
+    CruciblePlayerPerformance,
+};
+use dcli::utils::{calculate_percent, format_f32, repeat_str};
+
+fn format_placement(placement: u32) -> String {
+    let suffix = match (placement % 10, placement % 100) {
+        (_, 11) | (_, 12) | (_, 13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+
+    format!("{}{}", placement, suffix)
+}
+
+pub fn print_placement(
+    data: &[CruciblePlayerActivityPerformance],
+    win_placement: u32,
+) {
+    let cpp: Vec<&CruciblePlayerPerformance> =
+        data.iter().map(|x| &x.performance).collect();
+    let aggregate = AggregatePlacements::with_performances(&cpp);
+
+    //no free for all activities
+    if aggregate.total_activities == 0 {
+        return;
+    }
+
+    let label_col_w = 30;
+
+    println!("PLACEMENT");
+    println!("==================");
+    println!(
+        "{:<0label_col_w$}{}",
+        "Average placement",
+        format_f32(aggregate.average_placement, 2),
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{}",
+        "Best placement",
+        format_placement(aggregate.best_placement),
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{} of {} games ({}%)",
+        format!("Top {} finishes (wins)", win_placement),
+        aggregate.wins,
+        aggregate.total_activities,
+        format_f32(aggregate.win_rate, 2),
+        label_col_w = label_col_w,
+    );
+    println!();
+
+    let col_w = 10;
+    let bar_col_w = 30;
+    let header = format!(
+        "{:<0col_w$}{:>0col_w$}{:>0col_w$}  {:<0bar_col_w$}",
+        "PLACE",
+        "GAMES",
+        "GAMES %",
+        "",
+        col_w = col_w,
+        bar_col_w = bar_col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let max_count = aggregate.placements.iter().max().copied().unwrap_or(0);
+
+    for (i, count) in aggregate.placements.iter().enumerate() {
+        let placement = i as u32 + 1;
+        let percent = calculate_percent(*count, aggregate.total_activities);
+
+        let bar_len = if max_count == 0 {
+            0
+        } else {
+            ((*count as f32 / max_count as f32) * bar_col_w as f32).round()
+                as usize
+        };
+
+        let win_marker = if placement <= win_placement { "*" } else { "" };
+
+        println!(
+            "{:<0col_w$}{:>0col_w$}{:>0col_w$}  {}",
+            format!("{}{}", format_placement(placement), win_marker),
+            count.to_string(),
+            format!("{}%", format_f32(percent, 2)),
+            repeat_str("#", bar_len),
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!("* Placements counted as a win (set with --ffa-win-placement)");
+    println!();
+}
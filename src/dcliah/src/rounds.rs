@@ -0,0 +1,118 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use dcli::crucible::{
+    AggregateRoundResults, CruciblePlayerActivityPerformance,
+};
+use dcli::utils::{calculate_percent, format_f32, repeat_str};
+
+pub fn print_rounds(data: &[CruciblePlayerActivityPerformance]) {
+    let aggregate = AggregateRoundResults::with_performances(data);
+
+    //no round based activities
+    if aggregate.total_activities == 0 {
+        return;
+    }
+
+    let label_col_w = 30;
+
+    println!("ROUNDS");
+    println!("==================");
+    println!(
+        "{:<0label_col_w$}{} - {} ({}% round win rate)",
+        "Rounds won - lost",
+        aggregate.rounds_won,
+        aggregate.rounds_lost,
+        format_f32(aggregate.round_win_rate, 2),
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{} of {} wins",
+        "Wins conceding rounds",
+        aggregate.wins_conceding_rounds,
+        aggregate.wins,
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{} of {} losses",
+        "Losses winning rounds",
+        aggregate.losses_winning_rounds,
+        aggregate.losses,
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{} won, {} lost",
+        "Sweeps",
+        aggregate.sweeps_won,
+        aggregate.sweeps_lost,
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{} of {} games",
+        "Mercy",
+        aggregate.total_mercy,
+        aggregate.total_activities,
+        label_col_w = label_col_w,
+    );
+    println!();
+
+    let col_w = 10;
+    let header = format!(
+        "{:<0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "SCORE",
+        "GAMES",
+        "GAMES %",
+        "MERCY",
+        "MERCY %",
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for s in &aggregate.scores {
+        println!(
+            "{:<0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            s.results.to_string(),
+            s.count.to_string(),
+            format!(
+                "{}%",
+                format_f32(
+                    calculate_percent(s.count, aggregate.total_activities),
+                    2
+                )
+            ),
+            s.mercy_count.to_string(),
+            format!(
+                "{}%",
+                format_f32(calculate_percent(s.mercy_count, s.count), 2)
+            ),
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!("SCORE - Final score in rounds won - lost");
+    println!("Wins conceding rounds - Games won where the other team won at least one round");
+    println!("Losses winning rounds - Games lost where your team won at least one round");
+    println!();
+}
@@ -0,0 +1,303 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use chrono::{DateTime, Utc};
+use dcli::crucible::CruciblePlayerActivityPerformance;
+use dcli::enums::standing::Standing;
+use dcli::utils::{
+    calculate_efficiency, calculate_kills_deaths_ratio, format_f32,
+    get_friday_reset_before, get_last_friday_reset, repeat_str,
+};
+
+//a card ends once either of these are reached
+const CARD_MAX_WINS: u32 = 7;
+const CARD_MAX_LOSSES: u32 = 3;
+
+/// A Trials of Osiris card. The API doesnt expose card data, so cards are
+/// approximated from consecutive games within a weekend, with a new card
+/// starting once the previous one reached 7 wins or 3 losses.
+#[derive(Debug)]
+struct TrialsCard {
+    weekend: DateTime<Utc>,
+    wins: u32,
+    losses: u32,
+    games: u32,
+    kills: u32,
+    deaths: u32,
+    assists: u32,
+}
+
+impl TrialsCard {
+    fn new(weekend: DateTime<Utc>) -> TrialsCard {
+        TrialsCard {
+            weekend,
+            wins: 0,
+            losses: 0,
+            games: 0,
+            kills: 0,
+            deaths: 0,
+            assists: 0,
+        }
+    }
+
+    fn add(&mut self, activity: &CruciblePlayerActivityPerformance) {
+        let stats = &activity.performance.stats;
+
+        match stats.standing {
+            Standing::Victory => self.wins += 1,
+            Standing::Defeat => self.losses += 1,
+            Standing::Unknown => (),
+        };
+
+        self.games += 1;
+        self.kills += stats.kills;
+        self.deaths += stats.deaths;
+        self.assists += stats.assists;
+    }
+
+    fn is_complete(&self) -> bool {
+        self.wins >= CARD_MAX_WINS || self.losses >= CARD_MAX_LOSSES
+    }
+
+    fn is_flawless(&self) -> bool {
+        self.wins >= CARD_MAX_WINS && self.losses == 0
+    }
+
+    fn get_status(&self, current_weekend: &DateTime<Utc>) -> &str {
+        if self.is_flawless() {
+            "Flawless"
+        } else if self.is_complete() {
+            "Complete"
+        } else if self.weekend == *current_weekend {
+            "In Progress"
+        } else {
+            "Incomplete"
+        }
+    }
+
+    fn get_kills_deaths_ratio(&self) -> f32 {
+        calculate_kills_deaths_ratio(self.kills, self.deaths)
+    }
+
+    fn get_efficiency(&self) -> f32 {
+        calculate_efficiency(self.kills, self.deaths, self.assists)
+    }
+}
+
+/// Groups the activities into cards, oldest first.
+fn build_cards(data: &[CruciblePlayerActivityPerformance]) -> Vec<TrialsCard> {
+    let mut activities: Vec<&CruciblePlayerActivityPerformance> =
+        data.iter().collect();
+    activities.sort_by_key(|a| a.activity_detail.period);
+
+    let mut cards: Vec<TrialsCard> = Vec::new();
+    for activity in activities {
+        let weekend = get_friday_reset_before(&activity.activity_detail.period);
+
+        let new_card = match cards.last() {
+            Some(c) => c.weekend != weekend || c.is_complete(),
+            None => true,
+        };
+
+        if new_card {
+            cards.push(TrialsCard::new(weekend));
+        }
+
+        //we know there is a card since we just added one if there wasnt
+        cards.last_mut().unwrap().add(activity);
+    }
+
+    cards
+}
+
+fn format_weekend(weekend: &DateTime<Utc>) -> String {
+    weekend.format("%B %-d, %Y").to_string()
+}
+
+pub fn print_trials(data: &[CruciblePlayerActivityPerformance], limit: u32) {
+    let cards = build_cards(data);
+
+    let current_card = match cards.last() {
+        Some(e) => e,
+        None => return,
+    };
+
+    let current_weekend = get_last_friday_reset();
+
+    println!("TRIALS OF OSIRIS");
+    println!("==================");
+
+    let label = if current_card.weekend == current_weekend {
+        "Current card"
+    } else {
+        "Last card"
+    };
+
+    println!(
+        "{} (weekend of {}) : {} - {} {} (K/D {}, Efficiency {})",
+        label,
+        format_weekend(&current_card.weekend),
+        current_card.wins,
+        current_card.losses,
+        current_card.get_status(&current_weekend),
+        format_f32(current_card.get_kills_deaths_ratio(), 2),
+        format_f32(current_card.get_efficiency(), 2),
+    );
+    println!();
+
+    let col_w = 10;
+    let weekend_col_w = 22;
+
+    //weekends, oldest first
+    let mut weekends: Vec<(DateTime<Utc>, Vec<&TrialsCard>)> = Vec::new();
+    for c in &cards {
+        match weekends.last_mut() {
+            Some(e) if e.0 == c.weekend => e.1.push(c),
+            _ => weekends.push((c.weekend, vec![c])),
+        };
+    }
+
+    let header = format!(
+        "{:<0weekend_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "WEEKEND",
+        "CARDS",
+        "GAMES",
+        "WINS",
+        "LOSSES",
+        "FLAWLESS",
+        "K/D",
+        col_w = col_w,
+        weekend_col_w = weekend_col_w,
+    );
+
+    println!("FLAWLESS BY WEEKEND");
+    println!("------------------");
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let mut total_flawless = 0;
+    for (weekend, weekend_cards) in weekends.iter().rev() {
+        let mut games = 0;
+        let mut wins = 0;
+        let mut losses = 0;
+        let mut kills = 0;
+        let mut deaths = 0;
+        let mut flawless = 0;
+
+        for c in weekend_cards {
+            games += c.games;
+            wins += c.wins;
+            losses += c.losses;
+            kills += c.kills;
+            deaths += c.deaths;
+
+            if c.is_flawless() {
+                flawless += 1;
+            }
+        }
+
+        total_flawless += flawless;
+
+        println!(
+            "{:<0weekend_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            format_weekend(weekend),
+            weekend_cards.len().to_string(),
+            games.to_string(),
+            wins.to_string(),
+            losses.to_string(),
+            flawless.to_string(),
+            format_f32(calculate_kills_deaths_ratio(kills, deaths), 2),
+            col_w = col_w,
+            weekend_col_w = weekend_col_w,
+        );
+    }
+
+    println!("{}", repeat_str("-", header.chars().count()));
+    println!(
+        "{:<0weekend_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "TOTAL",
+        cards.len().to_string(),
+        "",
+        "",
+        "",
+        total_flawless.to_string(),
+        col_w = col_w,
+        weekend_col_w = weekend_col_w,
+    );
+
+    println!();
+    println!();
+
+    let status_col_w = 14;
+    let header = format!(
+        "{:<0weekend_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0status_col_w$}",
+        "WEEKEND",
+        "WINS",
+        "LOSSES",
+        "GAMES",
+        "KILLS",
+        "DEATHS",
+        "K/D",
+        "EFF",
+        "STATUS",
+        col_w = col_w,
+        weekend_col_w = weekend_col_w,
+        status_col_w = status_col_w,
+    );
+
+    println!("CARDS");
+    println!("------------------");
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let display_count = std::cmp::min(cards.len(), limit as usize);
+
+    for c in cards.iter().rev().take(display_count) {
+        println!(
+            "{:<0weekend_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0status_col_w$}",
+            format_weekend(&c.weekend),
+            c.wins.to_string(),
+            c.losses.to_string(),
+            c.games.to_string(),
+            c.kills.to_string(),
+            c.deaths.to_string(),
+            format_f32(c.get_kills_deaths_ratio(), 2),
+            format_f32(c.get_efficiency(), 2),
+            c.get_status(&current_weekend),
+            col_w = col_w,
+            weekend_col_w = weekend_col_w,
+            status_col_w = status_col_w,
+        );
+    }
+
+    if display_count != cards.len() {
+        println!("...");
+    }
+
+    println!();
+    println!(
+        "Cards are determined from consecutive games each weekend, and end at {} wins or {} losses.",
+        CARD_MAX_WINS, CARD_MAX_LOSSES
+    );
+    println!("Cards which were reset early by the player cannot be detected.");
+    println!();
+}
@@ -0,0 +1,171 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use dcli::crucible::{
+    AggregateClassComposition, ClassComposition, CrucibleActivity, ResultStat,
+};
+use dcli::enums::character::CharacterClass;
+use dcli::utils::{format_f32, repeat_str};
+
+//facing at least this many players of a class is summarized
+const STACKED_CLASS_COUNT: u32 = 2;
+
+const CLASSES: [CharacterClass; 3] = [
+    CharacterClass::Titan,
+    CharacterClass::Hunter,
+    CharacterClass::Warlock,
+];
+
+fn print_header(label: &str, label_col_w: usize, col_w: usize) {
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        "GAMES",
+        "WINS",
+        "LOSSES",
+        "WIN %",
+        "K/D",
+        "EFF",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+}
+
+fn print_row(label: &str, stat: &ResultStat, label_col_w: usize, col_w: usize) {
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        stat.total_activities.to_string(),
+        stat.wins.to_string(),
+        stat.losses.to_string(),
+        format!("{}%", format_f32(stat.win_rate(), 2)),
+        format_f32(stat.kills_deaths_ratio(), 2),
+        format_f32(stat.efficiency(), 2),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+}
+
+fn format_class_count(class_type: &CharacterClass, count: u32) -> String {
+    let s = if count == 1 { "" } else { "s" };
+    format!("{} {}{}", count, class_type, s)
+}
+
+pub fn print_classes(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    title: &str,
+    limit: u32,
+) {
+    let aggregate =
+        AggregateClassComposition::with_activities(activities, member_id);
+
+    println!();
+    println!("CLASS COMPOSITION");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    if aggregate.total_activities == 0 {
+        println!("No activities found");
+        return;
+    }
+
+    let label_col_w = 36;
+    let col_w = 10;
+
+    println!("YOUR CLASS");
+    println!("------------------");
+    print_header("CLASS", label_col_w, col_w);
+    for c in CLASSES.iter() {
+        if let Some(stat) = aggregate.member_classes.get(c) {
+            print_row(&c.to_string(), stat, label_col_w, col_w);
+        }
+    }
+
+    println!();
+    println!();
+    println!("OPPONENT CLASSES");
+    println!("------------------");
+    print_header("FACING", label_col_w, col_w);
+
+    for c in CLASSES.iter() {
+        let mut counts: Vec<u32> = aggregate
+            .opponent_class_counts
+            .keys()
+            .filter(|(class_type, _)| class_type == c)
+            .map(|(_, count)| *count)
+            .collect();
+        counts.sort_unstable();
+
+        for count in counts {
+            if let Some(stat) =
+                aggregate.opponent_class_counts.get(&(*c, count))
+            {
+                print_row(
+                    &format_class_count(c, count),
+                    stat,
+                    label_col_w,
+                    col_w,
+                );
+            }
+        }
+
+        let stacked =
+            aggregate.get_opponent_class_min_count(c, STACKED_CLASS_COUNT);
+        print_row(
+            &format!("{} or more", format_class_count(c, STACKED_CLASS_COUNT)),
+            &stacked,
+            label_col_w,
+            col_w,
+        );
+        println!();
+    }
+
+    println!();
+    println!("OPPONENT COMPOSITIONS");
+    println!("------------------");
+    print_header("COMPOSITION", label_col_w, col_w);
+
+    let mut compositions: Vec<(&ClassComposition, &ResultStat)> =
+        aggregate.opponent_compositions.iter().collect();
+    compositions.sort_by(|a, b| {
+        b.1.total_activities
+            .cmp(&a.1.total_activities)
+            .then(b.1.wins.cmp(&a.1.wins))
+    });
+
+    for (composition, stat) in compositions.iter().take(limit as usize) {
+        print_row(&composition.to_string(), stat, label_col_w, col_w);
+    }
+
+    if compositions.len() > limit as usize {
+        println!("...");
+    }
+
+    println!();
+    println!("GAMES, WINS, LOSSES, WIN %, K/D and EFF are for your performance in the games.");
+    println!();
+}
@@ -0,0 +1,247 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use dcli::crucible::{
+    calculate_light_level_kills_correlation, ActivityDetail, CrucibleActivity,
+    LightLevelAdvantage, ResultStat,
+};
+use dcli::enums::completionreason::CompletionReason;
+use dcli::enums::standing::Standing;
+use dcli::utils::{
+    calculate_avg, calculate_correlation, format_f32, repeat_str,
+};
+
+//upper bound (exclusive) of each light level advantage bucket
+const ADVANTAGE_BUCKETS: [(f32, &str); 7] = [
+    (-10.0, "Less than -10"),
+    (-5.0, "-10 to -5"),
+    (-1.0, "-5 to -1"),
+    (1.0, "-1 to 1"),
+    (5.0, "1 to 5"),
+    (10.0, "5 to 10"),
+    (f32::MAX, "10 or more"),
+];
+
+struct GameLightLevel<'a> {
+    detail: &'a ActivityDetail,
+    light_level: LightLevelAdvantage,
+    standing: Standing,
+    mercy: bool,
+}
+
+pub fn print_light(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    title: &str,
+    limit: u32,
+) {
+    //light level only matters in some modes (i.e. iron banner and trials)
+    let activities: Vec<&CrucibleActivity> = activities
+        .iter()
+        .filter(|a| a.details.mode.is_light_level_enabled())
+        .collect();
+
+    let mut games: Vec<GameLightLevel> = Vec::new();
+    let mut buckets: Vec<ResultStat> =
+        vec![ResultStat::default(); ADVANTAGE_BUCKETS.len()];
+
+    for &a in &activities {
+        let performance = match a.get_member_performance(member_id) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let light_level = match a.get_light_level_advantage(member_id) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let advantage = light_level.advantage();
+        let index = ADVANTAGE_BUCKETS
+            .iter()
+            .position(|b| advantage < b.0)
+            .unwrap_or(ADVANTAGE_BUCKETS.len() - 1);
+        buckets[index].add(performance);
+
+        games.push(GameLightLevel {
+            detail: &a.details,
+            light_level,
+            standing: performance.stats.standing,
+            mercy: performance.stats.completion_reason
+                == CompletionReason::Mercy,
+        });
+    }
+
+    games.sort_by_key(|g| g.detail.period);
+
+    println!();
+    println!("LIGHT LEVEL");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    if games.is_empty() {
+        println!("No Iron Banner or Trials of Osiris activities found");
+        return;
+    }
+
+    let advantages: Vec<f32> =
+        games.iter().map(|g| g.light_level.advantage()).collect();
+    let wins: Vec<f32> = games
+        .iter()
+        .map(|g| {
+            if g.standing == Standing::Victory {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+
+    let label_col_w = 48;
+    println!(
+        "{:<0label_col_w$}{}",
+        "Games",
+        games.len(),
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{}",
+        "Average light level advantage",
+        format_f32(
+            calculate_avg(advantages.iter().sum(), advantages.len() as u32),
+            2
+        ),
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{}",
+        "Correlation between light advantage and winning",
+        format_f32(calculate_correlation(&advantages, &wins), 3),
+        label_col_w = label_col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{}",
+        "Correlation between player light and kills",
+        format_f32(calculate_light_level_kills_correlation(&activities), 3),
+        label_col_w = label_col_w,
+    );
+    println!();
+
+    let bucket_col_w = 18;
+    let col_w = 10;
+
+    let header = format!(
+        "{:<0bucket_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "ADVANTAGE",
+        "GAMES",
+        "WINS",
+        "LOSSES",
+        "WIN %",
+        "K/D",
+        "EFF",
+        bucket_col_w = bucket_col_w,
+        col_w = col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for (bucket, stat) in ADVANTAGE_BUCKETS.iter().zip(buckets.iter()) {
+        if stat.total_activities == 0 {
+            continue;
+        }
+
+        println!(
+            "{:<0bucket_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            bucket.1,
+            stat.total_activities.to_string(),
+            stat.wins.to_string(),
+            stat.losses.to_string(),
+            format!("{}%", format_f32(stat.win_rate(), 2)),
+            format_f32(stat.kills_deaths_ratio(), 2),
+            format_f32(stat.efficiency(), 2),
+            bucket_col_w = bucket_col_w,
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!();
+
+    let date_col_w = 20;
+    let mode_col_w = 22;
+    let map_col_w = 18;
+
+    let header = format!(
+        "{:<0date_col_w$}{:<0mode_col_w$}{:<0map_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}  {:<0col_w$}",
+        "DATE",
+        "MODE",
+        "MAP",
+        "TEAM",
+        "OPPONENT",
+        "ADV",
+        "W/L",
+        date_col_w = date_col_w,
+        mode_col_w = mode_col_w,
+        map_col_w = map_col_w,
+        col_w = col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let display_count = std::cmp::min(limit as usize, games.len());
+    for g in games.iter().rev().take(display_count) {
+        let standing = if g.mercy {
+            format!("{} (M)", g.standing)
+        } else {
+            format!("{}", g.standing)
+        };
+
+        println!(
+            "{:<0date_col_w$}{:<0mode_col_w$}{:<0map_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}  {:<0col_w$}",
+            g.detail.period.format("%B %-d, %Y").to_string(),
+            g.detail.mode.to_string(),
+            g.detail.map_name,
+            format_f32(g.light_level.team_average, 1),
+            format_f32(g.light_level.opponent_average, 1),
+            format_f32(g.light_level.advantage(), 1),
+            standing,
+            date_col_w = date_col_w,
+            mode_col_w = mode_col_w,
+            map_col_w = map_col_w,
+            col_w = col_w,
+        );
+    }
+
+    if display_count < games.len() {
+        println!("...");
+    }
+
+    println!();
+    println!("TEAM / OPPONENT - Average light level of the team");
+    println!(
+        "ADV - Average light level advantage of your team over the opponents"
+    );
+    println!();
+    println!("Only Iron Banner and Trials of Osiris activities are included.");
+    println!();
+}
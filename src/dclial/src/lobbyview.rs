@@ -0,0 +1,89 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum LobbyView {
+    Meta,
+    Skill,
+    Classes,
+    Light,
+    Platforms,
+    Quitters,
+    Predict,
+}
+
+impl LobbyView {
+    /// Returns whether the view requires all activities prior to the time
+    /// period in order to generate its data (i.e. to calculate ratings).
+    pub fn requires_history(&self) -> bool {
+        match self {
+            LobbyView::Meta => false,
+            LobbyView::Skill => true,
+            LobbyView::Classes => false,
+            LobbyView::Light => false,
+            LobbyView::Platforms => false,
+            LobbyView::Quitters => false,
+            LobbyView::Predict => true,
+        }
+    }
+}
+
+impl FromStr for LobbyView {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        //wrap in String so we can convert to lower case
+        let s = String::from(s).to_lowercase();
+
+        //get a slice to get a &str for the match
+        match &s[..] {
+            "meta" => Ok(LobbyView::Meta),
+            "skill" => Ok(LobbyView::Skill),
+            "classes" => Ok(LobbyView::Classes),
+            "light" => Ok(LobbyView::Light),
+            "platforms" => Ok(LobbyView::Platforms),
+            "quitters" => Ok(LobbyView::Quitters),
+            "predict" => Ok(LobbyView::Predict),
+
+            _ => Err("Unknown LobbyView type"),
+        }
+    }
+}
+
+impl fmt::Display for LobbyView {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out = match self {
+            LobbyView::Meta => "Weapon Meta",
+            LobbyView::Skill => "Lobby Skill",
+            LobbyView::Classes => "Class Composition",
+            LobbyView::Light => "Light Level",
+            LobbyView::Platforms => "Platforms",
+            LobbyView::Quitters => "Late Joins and Early Leaves",
+            LobbyView::Predict => "Match Predictions",
+        };
+
+        write!(f, "{}", out)
+    }
+}
@@ -0,0 +1,442 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+mod classes;
+mod light;
+mod lobbyview;
+mod meta;
+mod platforms;
+mod predict;
+mod quitters;
+mod skill;
+
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use dcli::activitystoreinterface::ActivityStoreInterface;
+use dcli::crucible::CrucibleActivity;
+use dcli::enums::character::CharacterClassSelection;
+use dcli::enums::language::Language;
+use dcli::enums::mode::Mode;
+use dcli::enums::moment::{DateTimePeriod, Moment};
+use dcli::enums::platform::Platform;
+use dcli::error::Error;
+use dcli::manifestinterface::ManifestInterface;
+use dcli::matchpredictor::generate_player_ratings;
+use dcli::seasons::Seasons;
+use dcli::utils::{
+    build_activity_title, determine_data_dir, get_destiny2_launch_date,
+    print_error, print_verbose, EXIT_FAILURE,
+};
+use lobbyview::LobbyView;
+use structopt::StructOpt;
+
+fn parse_and_validate_mode(src: &str) -> Result<Mode, String> {
+    let mode = Mode::from_str(src)?;
+
+    if !mode.is_crucible() {
+        return Err(format!("Unsupported mode specified : {}", src));
+    }
+
+    Ok(mode)
+}
+
+fn parse_and_validate_moment(src: &str) -> Result<Moment, String> {
+    let moment = Moment::from_str(src)?;
+
+    Ok(moment)
+}
+
+fn parse_rfc3339(src: &str) -> Result<DateTime<Utc>, String> {
+    let d =
+        match DateTime::parse_from_rfc3339(src) {
+            Ok(e) => e,
+            Err(_e) => return Err(
+                "Invalid RFC 3339 Date / Time String : Example : 2020-12-08T17:00:00.774187+00:00"
+                    .to_string(),
+            ),
+        };
+
+    let d = d.with_timezone(&Utc);
+
+    if d > Utc::now() {
+        return Err("start-date must be in the past.".to_string());
+    }
+
+    Ok(d)
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(verbatim_doc_comment)]
+/// Command line tool for analyzing Destiny 2 Crucible lobbies.
+///
+/// Generates reports across all of the players in the games you have played,
+/// such as the weapon meta for a time period and mode.
+///
+/// Created by Mike Chambers.
+/// https://www.mikechambers.com
+///
+/// Get support, request features or just chat on the dcli Discord server:
+/// https://discord.gg/2Y8bV2Mq3p
+///
+/// Get the latest version, download the source and log issues at:
+/// https://github.com/mikechambers/dcli
+///
+/// Released under an MIT License.
+struct Opt {
+    /// Destiny 2 API member id
+    ///
+    /// This is not the user name, but the member id retrieved from the Destiny API.
+    #[structopt(short = "m", long = "member-id", required = true)]
+    member_id: String,
+
+    /// Platform for specified id
+    ///
+    /// Valid values are: xbox, playstation, stadia or steam.
+    #[structopt(short = "p", long = "platform", required = true)]
+    platform: Platform,
+
+    /// Analysis to display
+    ///
+    /// Valid values are meta (weapon usage across all players in the lobbies),
+    /// skill (team skill from stored or locally generated player ratings and
+    /// matchmaking balance), classes
+    /// (results by your class and the class composition of your opponents),
+    /// light (results by light level advantage in Iron Banner and Trials),
+    /// platforms (lobby composition and results by opponent platform),
+    /// quitters (results when players join late or leave early, and teammates
+    /// who repeatedly leave early) and predict (expected vs actual results
+    /// from a win probability model trained on your stored games).
+    #[structopt(long = "view", default_value = "meta")]
+    view: LobbyView,
+
+    /// Custom start time in RFC 3339 date / time format
+    ///
+    /// Must be a valid date in the past.
+    ///
+    /// Example RFC 3339 format: 2020-12-08T17:00:00.774187+00:00
+    ///
+    /// Required when --moment is set to custom, but otherwise not applicable.
+    #[structopt(short = "t", long = "custom-time", parse(try_from_str = parse_rfc3339), required_if("moment", "custom"))]
+    custom_time: Option<DateTime<Utc>>,
+
+    /// Custom end time in RFC 3339 date / time format
+    ///
+    /// Must be a valid date in the past.
+    ///
+    /// Example RFC 3339 format: 2020-12-08T17:00:00.774187+00:00
+    ///
+    /// Required when --end-moment is set to custom, but otherwise not applicable.
+    #[structopt(short = "e", long = "end-custom-time", parse(try_from_str = parse_rfc3339), required_if("end-moment", "custom"))]
+    end_custom_time: Option<DateTime<Utc>>,
+
+    /// Start moment from which to pull activities from
+    ///
+    /// Activities will be retrieved from moment to end-moment.
+    ///
+    /// Valid values include daily (last daily reset), weekend
+    /// (last weekend reset on Friday), weekly (last weekly reset on Tuesday),
+    /// day (last day), week (last week), month (last month), all_time and custom
+    /// as well as the season moments current_season, previous_season and
+    /// season:<name-or-number> (i.e. season:13 or season:chosen). Season
+    /// moments are the start of the season, loaded from the manifest.
+    ///
+    /// When custom is specified, the custom start date in RFC3339 format must
+    /// be specified with the --custom-time argument.
+    #[structopt(long = "moment", parse(try_from_str=parse_and_validate_moment), 
+        short = "T", default_value = "month")]
+    moment: Moment,
+
+    /// End moment from which to pull activities from
+    ///
+    /// Activities will be retrieved from moment to end-moment. End moment
+    /// must be greater than moment
+    ///
+    /// Valid values are the same as --moment.
+    ///
+    /// Defaults to now, or to the end of the season when --moment is a season
+    /// moment.
+    ///
+    /// When custom is specified, the custom start date in RFC3339 format must
+    /// be specified with the --end-custom-time argument.
+    #[structopt(long = "end-moment", parse(try_from_str=parse_and_validate_moment), 
+        short = "E")]
+    end_moment: Option<Moment>,
+
+    /// Activity mode to return stats for
+    ///
+    /// Supported values are all_pvp (default), control, clash, elimination,
+    /// mayhem, iron_banner, all_private, rumble, pvp_competitive,
+    /// quickplay and trials_of_osiris.
+    ///
+    /// Addition values available are crimsom_doubles, supremacy, survival,
+    /// countdown, all_doubles, doubles, private_clash, private_control,
+    /// private_survival, private_rumble, showdown, lockdown,
+    /// scorched, scorched_team, breakthrough, clash_quickplay, trials_of_the_nine
+    #[structopt(long = "mode", short = "M", 
+        parse(try_from_str=parse_and_validate_mode), default_value = "all_pvp")]
+    mode: Mode,
+
+    /// Character to retrieve data for
+    ///
+    /// Valid values include hunter, titan, warlock, last_active and all.
+    #[structopt(short = "C", long = "class", default_value = "all")]
+    character_class_selection: CharacterClassSelection,
+
+    /// Lowest placement counted as a win in free for all modes
+    ///
+    /// Free for all modes such as Rumble rank players by placement. Any
+    /// placement up to and including this value will be treated as a victory
+    /// when calculating wins, losses and win rates.
+    #[structopt(long = "ffa-win-placement", default_value = "3")]
+    ffa_win_placement: u32,
+
+    /// Limit the number of rows that will be displayed
+    ///
+    /// Reports will be generated based on all activities.
+    #[structopt(long = "limit", short = "L", default_value = "20")]
+    limit: u32,
+
+    ///Print out additional information
+    ///
+    ///Output is printed to stderr.
+    #[structopt(short = "v", long = "verbose")]
+    verbose: bool,
+
+    /// Don't sync activities
+    ///
+    /// If flag is set, activities will not be retrieved before displaying stats.
+    /// This is useful in case you are syncing activities in a seperate process.
+    #[structopt(short = "N", long = "no-sync")]
+    no_sync: bool,
+
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
+    /// Directory where Destiny 2 manifest and activity database files are stored. (optional)
+    ///
+    /// This will normally be downloaded using the dclim and dclias tools, and uses
+    /// a system appropriate directory by default.
+    #[structopt(short = "D", long = "data-dir", parse(from_os_str))]
+    data_dir: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+    let opt = Opt::from_args();
+    print_verbose(&format!("{:#?}", opt), opt.verbose);
+
+    let data_dir = match determine_data_dir(opt.data_dir) {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Error initializing manifest directory.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut manifest =
+        match ManifestInterface::new(&data_dir, &opt.language, false).await {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize manifest. Have you run dclim?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    let time_period = match DateTimePeriod::with_moments(
+        &opt.moment,
+        opt.custom_time,
+        opt.end_moment.as_ref(),
+        opt.end_custom_time,
+        &mut manifest,
+    )
+    .await
+    {
+        Ok(e) => e,
+        Err(Error::DateTimePeriodOrder) => {
+            eprintln!("--end-moment must be greater than --moment");
+            std::process::exit(EXIT_FAILURE);
+        }
+        Err(e) => {
+            print_error(
+                "Could not determine --moment / --end-moment date / time.",
+                e,
+            );
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut store =
+        match ActivityStoreInterface::init_with_path(&data_dir, opt.verbose)
+            .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize activity store. Have you run dclias?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    store.set_ffa_win_placement(opt.ffa_win_placement);
+
+    if !opt.no_sync {
+        match store.sync(&opt.member_id, &opt.platform).await {
+            Ok(_e) => (),
+            Err(e) => {
+                eprintln!("Could not sync activity store {}", e);
+                eprintln!("Using existing data");
+            }
+        };
+    }
+
+    //some views need all of the previous activities in order to generate
+    //their data, but will only display data for the time period
+    let data_start_time = if opt.view.requires_history() {
+        std::cmp::min(get_destiny2_launch_date(), time_period.get_start())
+    } else {
+        time_period.get_start()
+    };
+
+    let data_period = match DateTimePeriod::with_start_end_time(
+        data_start_time,
+        time_period.get_end(),
+    ) {
+        Ok(e) => e,
+        Err(_e) => {
+            eprintln!("--end-moment must be greater than --moment");
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let activities = match store
+        .retrieve_crucible_activities_since(
+            &opt.member_id,
+            &opt.character_class_selection,
+            &opt.platform,
+            &opt.mode,
+            &data_period,
+            &mut manifest,
+        )
+        .await
+    {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Could not retrieve data from activity store.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let activities: Vec<CrucibleActivity> = match activities {
+        Some(e) if !e.is_empty() => e,
+        _ => {
+            println!("No activities found");
+            return;
+        }
+    };
+
+    let title = build_activity_title(
+        &opt.mode,
+        &time_period,
+        &opt.moment,
+        opt.end_moment.as_ref(),
+    );
+
+    match opt.view {
+        LobbyView::Meta => meta::print_meta(&activities, &title, opt.limit),
+        LobbyView::Skill => {
+            //activities include all previous activities in order to generate
+            //local ratings, but stored ratings are only needed for the period
+            let period_activities: Vec<CrucibleActivity> = activities
+                .iter()
+                .filter(|a| a.details.period > time_period.get_start())
+                .cloned()
+                .collect();
+
+            let combat_ratings = match store
+                .retrieve_combat_ratings_for_activities(&period_activities)
+                .await
+            {
+                Ok(e) => e,
+                Err(e) => {
+                    print_error(
+                        "Could not retrieve combat ratings from activity store.",
+                        e,
+                    );
+                    std::process::exit(EXIT_FAILURE);
+                }
+            };
+
+            let local_ratings = generate_player_ratings(&activities);
+
+            //if the manifest doesnt contain season data, results are grouped
+            //by month instead
+            let seasons = Seasons::with_manifest(&mut manifest).await.ok();
+
+            skill::print_skill(
+                &period_activities,
+                &opt.member_id,
+                &combat_ratings,
+                &local_ratings,
+                seasons.as_ref(),
+                &title,
+                opt.limit,
+            )
+        }
+        LobbyView::Classes => classes::print_classes(
+            &activities,
+            &opt.member_id,
+            &title,
+            opt.limit,
+        ),
+        LobbyView::Light => {
+            light::print_light(&activities, &opt.member_id, &title, opt.limit)
+        }
+        LobbyView::Platforms => {
+            platforms::print_platforms(&activities, &opt.member_id, &title)
+        }
+        LobbyView::Quitters => quitters::print_quitters(
+            &activities,
+            &opt.member_id,
+            &title,
+            opt.limit,
+        ),
+        LobbyView::Predict => predict::print_predict(
+            &activities,
+            &opt.member_id,
+            &time_period.get_start(),
+            &title,
+            opt.limit,
+        ),
+    }
+}
@@ -0,0 +1,285 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::{HashMap, HashSet};
+
+use dcli::crucible::{CrucibleActivity, CruciblePlayerPerformance};
+use dcli::enums::standing::Standing;
+use dcli::utils::{calculate_percent, calculate_ratio, format_f32, repeat_str};
+use num_format::{Locale, ToFormattedString};
+
+#[derive(Debug, Default)]
+struct MetaStat {
+    name: String,
+    item_type: String,
+
+    //number of player performances where it was used
+    uses: u32,
+    kills: u32,
+    precision_kills: u32,
+
+    //number of teams (or players for modes without teams) which used it and
+    //had a result
+    team_uses: u32,
+    team_wins: u32,
+}
+
+impl MetaStat {
+    fn new(name: &str, item_type: &str) -> MetaStat {
+        MetaStat {
+            name: name.to_string(),
+            item_type: item_type.to_string(),
+            ..Default::default()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct MetaReport {
+    activity_count: u32,
+    player_count: u32,
+    kills: u32,
+
+    weapons: Vec<MetaStat>,
+    types: Vec<MetaStat>,
+}
+
+//groups the players in the activity into units with a result. For team modes
+//this is the team, for modes without teams (i.e. rumble) each player is their
+//own unit
+fn get_units(
+    activity: &CrucibleActivity,
+) -> Vec<(Standing, Vec<&CruciblePlayerPerformance>)> {
+    let mut out: Vec<(Standing, Vec<&CruciblePlayerPerformance>)> = Vec::new();
+
+    for team in activity.teams.values() {
+        if team.standing == Standing::Unknown {
+            for p in &team.player_performances {
+                out.push((p.stats.standing, vec![p]));
+            }
+        } else {
+            out.push((
+                team.standing,
+                team.player_performances.iter().collect(),
+            ));
+        }
+    }
+
+    out
+}
+
+fn generate_report(activities: &[CrucibleActivity]) -> MetaReport {
+    let mut report = MetaReport::default();
+
+    let mut weapons: HashMap<u32, MetaStat> = HashMap::new();
+    let mut types: HashMap<String, MetaStat> = HashMap::new();
+
+    for activity in activities {
+        report.activity_count += 1;
+
+        for (standing, players) in get_units(activity) {
+            let mut unit_weapons: HashSet<u32> = HashSet::new();
+            let mut unit_types: HashSet<String> = HashSet::new();
+
+            for p in players {
+                report.player_count += 1;
+                report.kills += p.stats.kills;
+
+                let extended = match p.stats.extended.as_ref() {
+                    Some(e) => e,
+                    None => continue,
+                };
+
+                for w in &extended.weapons {
+                    let type_name = format!("{}", w.weapon.item_sub_type);
+
+                    let ws = weapons.entry(w.weapon.id).or_insert_with(|| {
+                        MetaStat::new(&w.weapon.name, &type_name)
+                    });
+                    ws.uses += 1;
+                    ws.kills += w.kills;
+                    ws.precision_kills += w.precision_kills;
+
+                    let ts = types
+                        .entry(type_name.clone())
+                        .or_insert_with(|| MetaStat::new(&type_name, ""));
+                    ts.kills += w.kills;
+                    ts.precision_kills += w.precision_kills;
+
+                    unit_weapons.insert(w.weapon.id);
+                    unit_types.insert(type_name);
+                }
+
+                //if a player used multiple weapons of the same type, only
+                //count it as a single use of the type
+                let player_types: HashSet<String> = extended
+                    .weapons
+                    .iter()
+                    .map(|w| format!("{}", w.weapon.item_sub_type))
+                    .collect();
+
+                for t in player_types {
+                    if let Some(ts) = types.get_mut(&t) {
+                        ts.uses += 1;
+                    }
+                }
+            }
+
+            if standing == Standing::Unknown {
+                continue;
+            }
+
+            let won = standing == Standing::Victory;
+            for id in unit_weapons {
+                if let Some(ws) = weapons.get_mut(&id) {
+                    ws.team_uses += 1;
+                    if won {
+                        ws.team_wins += 1;
+                    }
+                }
+            }
+
+            for t in unit_types {
+                if let Some(ts) = types.get_mut(&t) {
+                    ts.team_uses += 1;
+                    if won {
+                        ts.team_wins += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    let mut weapons: Vec<MetaStat> = weapons.into_iter().map(|e| e.1).collect();
+    weapons.sort_by(|a, b| b.uses.cmp(&a.uses).then(b.kills.cmp(&a.kills)));
+
+    let mut types: Vec<MetaStat> = types.into_iter().map(|e| e.1).collect();
+    types.sort_by(|a, b| b.uses.cmp(&a.uses).then(b.kills.cmp(&a.kills)));
+
+    report.weapons = weapons;
+    report.types = types;
+
+    report
+}
+
+pub fn print_meta(activities: &[CrucibleActivity], title: &str, limit: u32) {
+    let report = generate_report(activities);
+
+    println!();
+    println!("WEAPON META");
+    println!("==================");
+    println!("{}", title);
+    println!(
+        "{} games with {} player performances",
+        report.activity_count.to_formatted_string(&Locale::en),
+        report.player_count.to_formatted_string(&Locale::en),
+    );
+    println!();
+
+    let name_col_w = 26;
+    let type_col_w = 20;
+    let col_w = 10;
+
+    let header = format!(
+        "{:<0name_col_w$}{:<0type_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "WEAPON",
+        "TYPE",
+        "USES",
+        "USAGE %",
+        "KILLS",
+        "% KILLS",
+        "K/USE",
+        "% PREC",
+        "WIN %",
+        name_col_w = name_col_w,
+        type_col_w = type_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let max = std::cmp::min(limit as usize, report.weapons.len());
+    for w in &report.weapons[..max] {
+        println!(
+            "{:<0name_col_w$}{:<0type_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            w.name,
+            w.item_type,
+            w.uses.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(w.uses, report.player_count), 2)),
+            w.kills.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(w.kills, report.kills), 2)),
+            format_f32(calculate_ratio(w.kills, w.uses), 2),
+            format!("{}%", format_f32(calculate_percent(w.precision_kills, w.kills), 2)),
+            format!("{}%", format_f32(calculate_percent(w.team_wins, w.team_uses), 2)),
+            name_col_w = name_col_w,
+            type_col_w = type_col_w,
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!();
+
+    let header = format!(
+        "{:<0type_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "ARCHETYPE",
+        "USES",
+        "USAGE %",
+        "KILLS",
+        "% KILLS",
+        "K/USE",
+        "% PREC",
+        "WIN %",
+        type_col_w = type_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for t in &report.types {
+        println!(
+            "{:<0type_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            t.name,
+            t.uses.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(t.uses, report.player_count), 2)),
+            t.kills.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(t.kills, report.kills), 2)),
+            format_f32(calculate_ratio(t.kills, t.uses), 2),
+            format!("{}%", format_f32(calculate_percent(t.precision_kills, t.kills), 2)),
+            format!("{}%", format_f32(calculate_percent(t.team_wins, t.team_uses), 2)),
+            type_col_w = type_col_w,
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!("USES - Number of player performances where the weapon was used");
+    println!("USAGE % - Percentage of all player performances where the weapon was used");
+    println!("% KILLS - Percentage of all kills by all players");
+    println!("K/USE - Kills per player performance where the weapon was used");
+    println!(
+        "WIN % - Win rate of teams with at least one player using the weapon"
+    );
+    println!();
+}
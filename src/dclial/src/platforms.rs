@@ -0,0 +1,285 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::{BTreeMap, HashMap};
+
+use dcli::crucible::{CrucibleActivity, ResultStat};
+use dcli::enums::platform::Platform;
+use dcli::utils::{calculate_percent, format_f32, repeat_str};
+
+const PLATFORMS: [Platform; 4] = [
+    Platform::Xbox,
+    Platform::Playstation,
+    Platform::Steam,
+    Platform::Stadia,
+];
+
+//percent of opponents which must be on a platform for it to dominate a lobby
+const DOMINANT_PERCENT: f32 = 50.0;
+
+#[derive(Default)]
+struct PlatformCounts {
+    games: u32,
+    players: u32,
+    counts: HashMap<Platform, u32>,
+}
+
+impl PlatformCounts {
+    fn percent(&self, platform: &Platform) -> f32 {
+        calculate_percent(
+            *self.counts.get(platform).unwrap_or(&0),
+            self.players,
+        )
+    }
+}
+
+fn print_result_header(label: &str, label_col_w: usize, col_w: usize) {
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        "GAMES",
+        "WINS",
+        "LOSSES",
+        "WIN %",
+        "K/D",
+        "EFF",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+}
+
+fn print_result_row(
+    label: &str,
+    stat: &ResultStat,
+    label_col_w: usize,
+    col_w: usize,
+) {
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        stat.total_activities.to_string(),
+        stat.wins.to_string(),
+        stat.losses.to_string(),
+        format!("{}%", format_f32(stat.win_rate(), 2)),
+        format_f32(stat.kills_deaths_ratio(), 2),
+        format_f32(stat.efficiency(), 2),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+}
+
+pub fn print_platforms(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    title: &str,
+) {
+    //keyed by year / month so they are sorted chronologically
+    let mut months: BTreeMap<String, PlatformCounts> = BTreeMap::new();
+
+    //member results by the platform which dominated the opponents
+    let mut dominant: HashMap<Option<Platform>, ResultStat> = HashMap::new();
+
+    //opponent results by their platform
+    let mut opponents: HashMap<Platform, ResultStat> = HashMap::new();
+
+    let mut total_games = 0;
+
+    for a in activities {
+        let performance = match a.get_member_performance(member_id) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let opponent_performances = a.get_opponent_performances(member_id);
+        if opponent_performances.is_empty() {
+            continue;
+        }
+
+        total_games += 1;
+
+        let month = months
+            .entry(a.details.period.format("%Y-%m").to_string())
+            .or_default();
+        month.games += 1;
+
+        //all players in the lobby, except for the member
+        for t in a.teams.values() {
+            for p in &t.player_performances {
+                if p.player.member_id == member_id {
+                    continue;
+                }
+
+                month.players += 1;
+                *month.counts.entry(p.player.platform).or_default() += 1;
+            }
+        }
+
+        let mut opponent_counts: HashMap<Platform, u32> = HashMap::new();
+        for p in &opponent_performances {
+            *opponent_counts.entry(p.player.platform).or_default() += 1;
+
+            opponents.entry(p.player.platform).or_default().add(p);
+        }
+
+        let dominant_platform = opponent_counts
+            .iter()
+            .find(|(_, count)| {
+                calculate_percent(**count, opponent_performances.len() as u32)
+                    > DOMINANT_PERCENT
+            })
+            .map(|(platform, _)| *platform);
+
+        dominant
+            .entry(dominant_platform)
+            .or_default()
+            .add(performance);
+    }
+
+    println!();
+    println!("PLATFORMS");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    if total_games == 0 {
+        println!("No activities found");
+        return;
+    }
+
+    let col_w = 12;
+    let month_col_w = 18;
+
+    println!("LOBBY COMPOSITION");
+    println!("------------------");
+
+    let mut header = format!(
+        "{:<0month_col_w$}{:>0col_w$}",
+        "MONTH",
+        "GAMES",
+        month_col_w = month_col_w,
+        col_w = col_w,
+    );
+    for p in PLATFORMS.iter() {
+        header.push_str(&format!(
+            "{:>0col_w$}",
+            p.to_string().to_uppercase(),
+            col_w = col_w
+        ));
+    }
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let mut all = PlatformCounts::default();
+    for (month, counts) in &months {
+        let mut row = format!(
+            "{:<0month_col_w$}{:>0col_w$}",
+            month,
+            counts.games.to_string(),
+            month_col_w = month_col_w,
+            col_w = col_w,
+        );
+
+        for p in PLATFORMS.iter() {
+            row.push_str(&format!(
+                "{:>0col_w$}",
+                format!("{}%", format_f32(counts.percent(p), 1)),
+                col_w = col_w
+            ));
+        }
+        println!("{}", row);
+
+        all.games += counts.games;
+        all.players += counts.players;
+        for (platform, count) in &counts.counts {
+            *all.counts.entry(*platform).or_default() += count;
+        }
+    }
+
+    println!("{}", repeat_str("-", header.chars().count()));
+    let mut row = format!(
+        "{:<0month_col_w$}{:>0col_w$}",
+        "ALL",
+        all.games.to_string(),
+        month_col_w = month_col_w,
+        col_w = col_w,
+    );
+    for p in PLATFORMS.iter() {
+        row.push_str(&format!(
+            "{:>0col_w$}",
+            format!("{}%", format_f32(all.percent(p), 1)),
+            col_w = col_w
+        ));
+    }
+    println!("{}", row);
+
+    let label_col_w = 24;
+    let col_w = 10;
+
+    println!();
+    println!();
+    println!("YOUR RESULTS BY OPPONENT PLATFORM");
+    println!("------------------");
+    print_result_header("OPPONENTS", label_col_w, col_w);
+
+    for p in PLATFORMS.iter() {
+        if let Some(stat) = dominant.get(&Some(*p)) {
+            print_result_row(
+                &format!("Mostly {}", p),
+                stat,
+                label_col_w,
+                col_w,
+            );
+        }
+    }
+
+    if let Some(stat) = dominant.get(&None) {
+        print_result_row("Mixed", stat, label_col_w, col_w);
+    }
+
+    println!();
+    println!();
+    println!("OPPONENT PERFORMANCE BY PLATFORM");
+    println!("------------------");
+    print_result_header("PLATFORM", label_col_w, col_w);
+
+    for p in PLATFORMS.iter() {
+        if let Some(stat) = opponents.get(p) {
+            print_result_row(&p.to_string(), stat, label_col_w, col_w);
+        }
+    }
+
+    println!();
+    println!(
+        "MONTH - Percent of the players in your lobbies (excluding you) on each platform"
+    );
+    println!(
+        "Mostly - Games where more than {}% of your opponents were on the platform",
+        DOMINANT_PERCENT
+    );
+    println!("OPPONENT PERFORMANCE - Combined results of opponents on each platform (GAMES is the number of player performances)");
+    println!();
+    println!("Platform is the player's primary account platform, which may not be the platform they played on if they use cross save.");
+    println!();
+}
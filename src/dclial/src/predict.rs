@@ -0,0 +1,258 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use chrono::{DateTime, Utc};
+use dcli::crucible::CrucibleActivity;
+use dcli::matchpredictor::{
+    MatchPrediction, MatchPredictions, CROSS_VALIDATION_FOLDS, FEATURE_NAMES,
+    HOLDOUT_FRACTION, MIN_TRAINING_GAMES, UPSET_PROBABILITY,
+};
+use dcli::utils::{calculate_percent, format_f32, repeat_str};
+
+fn format_probability(value: f32) -> String {
+    format!("{}%", format_f32(value * 100.0, 1))
+}
+
+pub fn print_predict(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    start: &DateTime<Utc>,
+    title: &str,
+    limit: u32,
+) {
+    println!();
+    println!("MATCH PREDICTIONS");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    let predictions =
+        match MatchPredictions::with_activities(activities, member_id) {
+            Some(e) => e,
+            None => {
+                println!(
+                "At least {} team based games are required to train the model",
+                MIN_TRAINING_GAMES
+            );
+                return;
+            }
+        };
+
+    let label_col_w = 24;
+    let col_w = 12;
+
+    println!("MODEL EVALUATION");
+    println!("------------------");
+    match &predictions.evaluation {
+        Some(e) => {
+            println!(
+                "Trained on older games and tested on the most recent {}% ({} games)",
+                format_f32(HOLDOUT_FRACTION * 100.0, 0),
+                e.total_activities
+            );
+            println!();
+            println!(
+                "{:<0label_col_w$}{:>0col_w$}",
+                "Accuracy",
+                format!("{}%", format_f32(e.accuracy(), 2)),
+                label_col_w = label_col_w,
+                col_w = col_w,
+            );
+            println!(
+                "{:<0label_col_w$}{:>0col_w$}",
+                "Baseline Accuracy",
+                format!("{}%", format_f32(e.baseline_accuracy(), 2)),
+                label_col_w = label_col_w,
+                col_w = col_w,
+            );
+            println!(
+                "{:<0label_col_w$}{:>0col_w$}",
+                "Log Loss",
+                format_f32(e.log_loss(), 3),
+                label_col_w = label_col_w,
+                col_w = col_w,
+            );
+            println!(
+                "{:<0label_col_w$}{:>0col_w$}",
+                "Brier Score",
+                format_f32(e.brier_score(), 3),
+                label_col_w = label_col_w,
+                col_w = col_w,
+            );
+        }
+        None => println!("Not enough games to evaluate the model"),
+    }
+
+    println!();
+    println!();
+    println!("FEATURE WEIGHTS");
+    println!("------------------");
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}",
+        "FEATURE",
+        "WEIGHT",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+    for (name, weight) in
+        FEATURE_NAMES.iter().zip(predictions.model.weights.iter())
+    {
+        println!(
+            "{:<0label_col_w$}{:>0col_w$}",
+            name,
+            format_f32(*weight, 3),
+            label_col_w = label_col_w,
+            col_w = col_w,
+        );
+    }
+
+    let games: Vec<&MatchPrediction> = predictions
+        .predictions
+        .iter()
+        .filter(|p| p.details.period > *start)
+        .collect();
+
+    println!();
+    println!();
+    println!("EXPECTED VS ACTUAL");
+    println!("------------------");
+
+    if games.is_empty() {
+        println!("No team based games found");
+        println!();
+        return;
+    }
+
+    let expected_wins: f32 = games.iter().map(|g| g.win_probability).sum();
+    let wins = games.iter().filter(|g| g.won).count() as u32;
+    let upsets: Vec<&&MatchPrediction> =
+        games.iter().filter(|g| g.is_upset()).collect();
+    let upset_wins = upsets.iter().filter(|g| g.won).count();
+
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}",
+        "Games",
+        games.len().to_string(),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}",
+        "Expected Wins",
+        format_f32(expected_wins, 1),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}",
+        "Actual Wins",
+        wins.to_string(),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}",
+        "Upsets",
+        format!(
+            "{} ({}%)",
+            upsets.len(),
+            format_f32(
+                calculate_percent(upsets.len() as u32, games.len() as u32),
+                1
+            )
+        ),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}",
+        "Upset Wins / Losses",
+        format!("{} / {}", upset_wins, upsets.len() - upset_wins),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+
+    let date_col_w = 22;
+    let mode_col_w = 26;
+    let map_col_w = 22;
+    let col_w = 10;
+
+    println!();
+    let header = format!(
+        "{:<0date_col_w$}{:<0mode_col_w$}{:<0map_col_w$}{:>0col_w$}  {:<0col_w$}{:<0col_w$}",
+        "DATE",
+        "MODE",
+        "MAP",
+        "EXPECTED",
+        "W/L",
+        "UPSET",
+        date_col_w = date_col_w,
+        mode_col_w = mode_col_w,
+        map_col_w = map_col_w,
+        col_w = col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let display_count = std::cmp::min(limit as usize, games.len());
+    for g in games.iter().rev().take(display_count) {
+        println!(
+            "{:<0date_col_w$}{:<0mode_col_w$}{:<0map_col_w$}{:>0col_w$}  {:<0col_w$}{:<0col_w$}",
+            g.details.period.format("%B %-d, %Y").to_string(),
+            g.details.mode.to_string(),
+            g.details.map_name,
+            format_probability(g.win_probability),
+            if g.won { "Victory" } else { "Defeat" },
+            if g.is_upset() { "UPSET" } else { "" },
+            date_col_w = date_col_w,
+            mode_col_w = mode_col_w,
+            map_col_w = map_col_w,
+            col_w = col_w,
+        );
+    }
+
+    if display_count < games.len() {
+        println!("...");
+    }
+
+    println!();
+    println!("Accuracy - Percent of games where the team given the better chance won");
+    println!("Baseline Accuracy - Accuracy from always predicting your most common result");
+    println!("Log Loss / Brier Score - Lower is better");
+    println!("WEIGHT - Influence of the difference between the teams for the feature (standardized)");
+    println!(
+        "EXPECTED - Estimated probability that your team would win, from a model which was not trained on the game"
+    );
+    println!(
+        "UPSET - The model gave the actual result less than a {}% chance",
+        format_f32(UPSET_PROBABILITY * 100.0, 0)
+    );
+    println!();
+    println!("The model is trained locally on all of the team based games in your activity store. Player history only includes games in the store.");
+    println!(
+        "Expected results are generated with {} fold cross validation, where the games in each fold are predicted by a model trained on the other games.",
+        CROSS_VALIDATION_FOLDS
+    );
+    println!();
+}
@@ -0,0 +1,172 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use dcli::crucible::{AggregateEarlyLeaves, CrucibleActivity, ResultStat};
+use dcli::utils::{calculate_percent, format_f32, repeat_str};
+
+//teammates who left early at least this many times are listed
+const REPEAT_QUITTER_COUNT: u32 = 2;
+
+fn print_header(label: &str, label_col_w: usize, col_w: usize) {
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        "GAMES",
+        "RATE",
+        "WINS",
+        "LOSSES",
+        "WIN %",
+        "K/D",
+        "EFF",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+}
+
+fn print_row(
+    label: &str,
+    stat: &ResultStat,
+    total: u32,
+    label_col_w: usize,
+    col_w: usize,
+) {
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        stat.total_activities.to_string(),
+        format!(
+            "{}%",
+            format_f32(calculate_percent(stat.total_activities, total), 2)
+        ),
+        stat.wins.to_string(),
+        stat.losses.to_string(),
+        format!("{}%", format_f32(stat.win_rate(), 2)),
+        format_f32(stat.kills_deaths_ratio(), 2),
+        format_f32(stat.efficiency(), 2),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+}
+
+pub fn print_quitters(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    title: &str,
+    limit: u32,
+) {
+    let aggregate =
+        AggregateEarlyLeaves::with_activities(activities, member_id);
+
+    println!();
+    println!("LATE JOINS AND EARLY LEAVES");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    let total = aggregate.all.total_activities;
+    if total == 0 {
+        println!("No activities found");
+        return;
+    }
+
+    let label_col_w = 28;
+    let col_w = 10;
+
+    print_header("GAMES WHERE", label_col_w, col_w);
+
+    let rows = [
+        ("All games", &aggregate.all),
+        ("You joined in progress", &aggregate.member_joined_late),
+        ("You left early", &aggregate.member_left_early),
+        ("Teammate joined late", &aggregate.teammate_joined_late),
+        ("Teammate left early", &aggregate.teammate_left_early),
+        ("Opponent left early", &aggregate.opponent_left_early),
+        ("No one left early", &aggregate.no_early_leaves),
+    ];
+
+    for (label, stat) in rows.iter() {
+        print_row(label, stat, total, label_col_w, col_w);
+    }
+
+    let quitters = aggregate.get_repeat_quitters(REPEAT_QUITTER_COUNT);
+
+    let name_col_w = 32;
+    let platform_col_w = 14;
+    let col_w = 10;
+
+    println!();
+    println!();
+    println!("REPEAT QUITTERS");
+    println!("------------------");
+
+    if quitters.is_empty() {
+        println!(
+            "No teammates left early in {} or more of your games",
+            REPEAT_QUITTER_COUNT
+        );
+    } else {
+        let header = format!(
+            "{:<0name_col_w$}{:>0platform_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            "NAME",
+            "PLATFORM",
+            "GAMES",
+            "LEFT",
+            "LEFT %",
+            name_col_w = name_col_w,
+            platform_col_w = platform_col_w,
+            col_w = col_w,
+        );
+
+        println!("{}", header);
+        println!("{}", repeat_str("=", header.chars().count()));
+
+        for q in quitters.iter().take(limit as usize) {
+            println!(
+                "{:<0name_col_w$}{:>0platform_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+                q.player.display_name,
+                q.player.platform.to_string(),
+                q.total_activities.to_string(),
+                q.left_early.to_string(),
+                format!("{}%", format_f32(q.left_early_rate(), 2)),
+                name_col_w = name_col_w,
+                platform_col_w = platform_col_w,
+                col_w = col_w,
+            );
+        }
+
+        if quitters.len() > limit as usize {
+            println!("...");
+        }
+    }
+
+    println!();
+    println!("RATE - Percent of all games");
+    println!("WINS, LOSSES, WIN %, K/D and EFF are for your performance in the games.");
+    println!(
+        "REPEAT QUITTERS - Teammates who left early in {} or more of your games together",
+        REPEAT_QUITTER_COUNT
+    );
+    println!();
+}
@@ -0,0 +1,484 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use std::fmt;
+
+use dcli::crucible::{ActivityDetail, CrucibleActivity, Team};
+use dcli::enums::completionreason::CompletionReason;
+use dcli::enums::standing::Standing;
+use dcli::seasons::Seasons;
+use dcli::utils::{
+    calculate_avg, calculate_correlation, calculate_percent,
+    calculate_standard_deviation, format_f32, repeat_str,
+};
+use num_format::{Locale, ToFormattedString};
+
+#[derive(Debug, Clone, Copy)]
+struct TeamSkill {
+    average: f32,
+    spread: f32,
+}
+
+//where the player ratings for a game came from
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum RatingSource {
+    //combat ratings stored when the game was synced
+    Stored,
+
+    //generated from the player's performance in earlier stored games
+    Local,
+}
+
+impl fmt::Display for RatingSource {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let out = match self {
+            RatingSource::Stored => "Stored",
+            RatingSource::Local => "Local",
+        };
+
+        write!(f, "{}", out)
+    }
+}
+
+#[derive(Debug)]
+struct GameSkill {
+    detail: ActivityDetail,
+    lobby: TeamSkill,
+    member_team: TeamSkill,
+    opponent_team: TeamSkill,
+    standing: Standing,
+    mercy: bool,
+    source: RatingSource,
+}
+
+impl GameSkill {
+    //positive if the member's team was rated higher than the opponents
+    fn advantage(&self) -> f32 {
+        self.member_team.average - self.opponent_team.average
+    }
+
+    fn imbalance(&self) -> f32 {
+        self.advantage().abs()
+    }
+}
+
+#[derive(Debug, Default)]
+struct GroupSummary {
+    games: u32,
+    stored: u32,
+    wins: u32,
+    mercies: u32,
+    lobby_total: f32,
+    imbalance_total: f32,
+    advantage_total: f32,
+}
+
+impl GroupSummary {
+    fn add(&mut self, game: &GameSkill) {
+        self.games += 1;
+        if game.source == RatingSource::Stored {
+            self.stored += 1;
+        }
+
+        if game.standing == Standing::Victory {
+            self.wins += 1;
+        }
+
+        if game.mercy {
+            self.mercies += 1;
+        }
+
+        self.lobby_total += game.lobby.average;
+        self.imbalance_total += game.imbalance();
+        self.advantage_total += game.advantage();
+    }
+
+    fn source(&self) -> &str {
+        if self.games == 0 {
+            ""
+        } else if self.stored == self.games {
+            "Stored"
+        } else if self.stored == 0 {
+            "Local"
+        } else {
+            "Mixed"
+        }
+    }
+}
+
+//players without a stored rating are not included
+fn get_ratings(teams: &[&Team], ratings: &HashMap<u64, f32>) -> Vec<f32> {
+    teams
+        .iter()
+        .flat_map(|t| t.player_performances.iter())
+        .filter_map(|p| ratings.get(&p.player.calculate_hash()).copied())
+        .collect()
+}
+
+fn get_skill(values: &[f32]) -> Option<TeamSkill> {
+    if values.is_empty() {
+        return None;
+    }
+
+    Some(TeamSkill {
+        average: calculate_avg(values.iter().sum(), values.len() as u32),
+        spread: calculate_standard_deviation(values),
+    })
+}
+
+//returns the skill of the member's team, the opponent team and the lobby, or
+//None if either team doesnt have any rated players
+fn get_team_skills(
+    member_team: &Team,
+    opponent_team: &Team,
+    ratings: &HashMap<u64, f32>,
+) -> Option<(TeamSkill, TeamSkill, TeamSkill)> {
+    Some((
+        get_skill(&get_ratings(&[member_team], ratings))?,
+        get_skill(&get_ratings(&[opponent_team], ratings))?,
+        get_skill(&get_ratings(&[member_team, opponent_team], ratings))?,
+    ))
+}
+
+/// Generates the skill of each team for games with two teams. The combat
+/// ratings which were stored when the activity was synced are used when
+/// available, otherwise the locally generated player ratings are used. Both
+/// are keyed by activity index id, and then by player hash.
+fn generate_game_skills(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    combat_ratings: &HashMap<u32, HashMap<u64, f32>>,
+    local_ratings: &HashMap<u32, HashMap<u64, f32>>,
+) -> Vec<GameSkill> {
+    let mut out: Vec<GameSkill> = Vec::new();
+
+    //activities are sorted most recent first
+    for activity in activities.iter().rev() {
+        let member = match activity.get_member_performance(member_id) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let teams: Vec<&Team> = activity.teams.values().collect();
+        if teams.len() != 2
+            || teams.iter().any(|t| t.standing == Standing::Unknown)
+        {
+            continue;
+        }
+
+        let (member_team, opponent_team) = if teams[0].id == member.stats.team {
+            (teams[0], teams[1])
+        } else {
+            (teams[1], teams[0])
+        };
+
+        let index_id = &activity.details.index_id;
+        let skills = combat_ratings
+            .get(index_id)
+            .and_then(|r| get_team_skills(member_team, opponent_team, r))
+            .map(|e| (e, RatingSource::Stored))
+            .or_else(|| {
+                local_ratings
+                    .get(index_id)
+                    .and_then(|r| {
+                        get_team_skills(member_team, opponent_team, r)
+                    })
+                    .map(|e| (e, RatingSource::Local))
+            });
+
+        if let Some(((member_skill, opponent_skill, lobby_skill), source)) =
+            skills
+        {
+            out.push(GameSkill {
+                detail: activity.details.clone(),
+                lobby: lobby_skill,
+                member_team: member_skill,
+                opponent_team: opponent_skill,
+                standing: member_team.standing,
+                mercy: member.stats.completion_reason
+                    == CompletionReason::Mercy,
+                source,
+            });
+        }
+    }
+
+    out
+}
+
+fn print_group_header(label: &str, label_col_w: usize, col_w: usize) {
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        "GAMES",
+        "LOBBY",
+        "IMBAL",
+        "ADV",
+        "WIN %",
+        "MERCY %",
+        "SOURCE",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+}
+
+fn print_group_row(
+    label: &str,
+    g: &GroupSummary,
+    label_col_w: usize,
+    col_w: usize,
+) {
+    println!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        label,
+        g.games.to_formatted_string(&Locale::en),
+        format_f32(calculate_avg(g.lobby_total, g.games), 0),
+        format_f32(calculate_avg(g.imbalance_total, g.games), 0),
+        format_f32(calculate_avg(g.advantage_total, g.games), 0),
+        format!("{}%", format_f32(calculate_percent(g.wins, g.games), 2)),
+        format!("{}%", format_f32(calculate_percent(g.mercies, g.games), 2)),
+        g.source(),
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+}
+
+//groups the games by the key, maintaining the order in which keys are first
+//found
+fn group_games<F>(games: &[GameSkill], key: F) -> Vec<(String, GroupSummary)>
+where
+    F: Fn(&GameSkill) -> String,
+{
+    let mut out: Vec<(String, GroupSummary)> = Vec::new();
+
+    for g in games {
+        let k = key(g);
+
+        match out.iter_mut().find(|e| e.0 == k) {
+            Some(e) => e.1.add(g),
+            None => {
+                let mut summary = GroupSummary::default();
+                summary.add(g);
+                out.push((k, summary));
+            }
+        }
+    }
+
+    out
+}
+
+pub fn print_skill(
+    activities: &[CrucibleActivity],
+    member_id: &str,
+    combat_ratings: &HashMap<u32, HashMap<u64, f32>>,
+    local_ratings: &HashMap<u32, HashMap<u64, f32>>,
+    seasons: Option<&Seasons>,
+    title: &str,
+    limit: u32,
+) {
+    let games = generate_game_skills(
+        activities,
+        member_id,
+        combat_ratings,
+        local_ratings,
+    );
+
+    println!();
+    println!("LOBBY SKILL");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    if games.is_empty() {
+        println!("No team based activities found.");
+        return;
+    }
+
+    let mut overall = GroupSummary::default();
+    for g in &games {
+        overall.add(g);
+    }
+
+    let label_col_w = 22;
+    let col_w = 10;
+
+    print_group_header("", label_col_w, col_w);
+    print_group_row("All Games", &overall, label_col_w, col_w);
+
+    //seasons are loaded from the manifest, so fall back to months if they
+    //are not available
+    println!();
+    println!();
+    match seasons {
+        Some(seasons) => {
+            print_group_header("SEASON", label_col_w, col_w);
+            for (season, g) in group_games(&games, |g| {
+                match seasons.get_season_at(&g.detail.period) {
+                    Some(e) => e.name.clone(),
+                    None => "Unknown".to_string(),
+                }
+            }) {
+                print_group_row(&season, &g, label_col_w, col_w);
+            }
+        }
+        None => {
+            print_group_header("MONTH", label_col_w, col_w);
+            for (month, g) in group_games(&games, |g| {
+                g.detail.period.format("%Y-%m").to_string()
+            }) {
+                print_group_row(&month, &g, label_col_w, col_w);
+            }
+        }
+    }
+
+    println!();
+    println!();
+    print_group_header("MODE", label_col_w, col_w);
+    for (mode, g) in group_games(&games, |g| format!("{}", g.detail.mode)) {
+        print_group_row(&mode, &g, label_col_w, col_w);
+    }
+
+    //bucket games by how much our team was favoured / not favoured
+    let buckets: [(f32, f32, &str); 5] = [
+        (f32::MIN, -30.0, "Underdog (30+)"),
+        (-30.0, -10.0, "Underdog (10 - 30)"),
+        (-10.0, 10.0, "Even (< 10)"),
+        (10.0, 30.0, "Favored (10 - 30)"),
+        (30.0, f32::MAX, "Favored (30+)"),
+    ];
+
+    println!();
+    println!();
+    print_group_header("TEAM ADVANTAGE", label_col_w, col_w);
+    for (min, max, label) in buckets.iter() {
+        let mut g = GroupSummary::default();
+        for game in games
+            .iter()
+            .filter(|e| e.advantage() >= *min && e.advantage() < *max)
+        {
+            g.add(game);
+        }
+
+        print_group_row(label, &g, label_col_w, col_w);
+    }
+
+    let advantages: Vec<f32> = games.iter().map(|g| g.advantage()).collect();
+    let imbalances: Vec<f32> = games.iter().map(|g| g.imbalance()).collect();
+    let wins: Vec<f32> = games
+        .iter()
+        .map(|g| {
+            if g.standing == Standing::Victory {
+                1.0
+            } else {
+                0.0
+            }
+        })
+        .collect();
+    let mercies: Vec<f32> = games
+        .iter()
+        .map(|g| if g.mercy { 1.0 } else { 0.0 })
+        .collect();
+
+    println!();
+    println!(
+        "Correlation between team advantage and winning : {}",
+        format_f32(calculate_correlation(&advantages, &wins), 3)
+    );
+    println!(
+        "Correlation between team imbalance and mercy   : {}",
+        format_f32(calculate_correlation(&imbalances, &mercies), 3)
+    );
+
+    println!();
+    println!();
+
+    let date_col_w = 20;
+    let map_col_w = 18;
+    let team_col_w = 14;
+    let standing_col_w = 13;
+
+    let header = format!(
+        "{:<0date_col_w$}{:<0map_col_w$}{:>0team_col_w$}{:>0team_col_w$}{:>0col_w$}{:>0col_w$}  {:<0standing_col_w$}{:<0col_w$}",
+        "DATE",
+        "MAP",
+        "TEAM",
+        "OPPONENT",
+        "ADV",
+        "LOBBY",
+        "W/L",
+        "SOURCE",
+        date_col_w = date_col_w,
+        map_col_w = map_col_w,
+        team_col_w = team_col_w,
+        standing_col_w = standing_col_w,
+        col_w = col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let display_count = std::cmp::min(limit as usize, games.len());
+    for g in games.iter().rev().take(display_count) {
+        let standing = if g.mercy {
+            format!("{} (M)", g.standing)
+        } else {
+            format!("{}", g.standing)
+        };
+
+        println!(
+            "{:<0date_col_w$}{:<0map_col_w$}{:>0team_col_w$}{:>0team_col_w$}{:>0col_w$}{:>0col_w$}  {:<0standing_col_w$}{:<0col_w$}",
+            g.detail.period.format("%B %-d, %Y").to_string(),
+            g.detail.map_name,
+            format!("{} ±{}", format_f32(g.member_team.average, 0), format_f32(g.member_team.spread, 0)),
+            format!("{} ±{}", format_f32(g.opponent_team.average, 0), format_f32(g.opponent_team.spread, 0)),
+            format_f32(g.advantage(), 0),
+            format_f32(g.lobby.average, 0),
+            standing,
+            format!("{}", g.source),
+            date_col_w = date_col_w,
+            map_col_w = map_col_w,
+            team_col_w = team_col_w,
+            standing_col_w = standing_col_w,
+            col_w = col_w,
+        );
+    }
+
+    if display_count < games.len() {
+        println!("...");
+    }
+
+    println!();
+    println!("LOBBY - Average skill rating of all players in the lobby");
+    println!("IMBAL - Average difference between the team skill ratings");
+    println!("ADV - Average skill advantage of your team over the opponents");
+    println!(
+        "TEAM / OPPONENT - Average team skill rating ± standard deviation"
+    );
+    println!();
+    println!("SOURCE - Where the player ratings for the games came from");
+    println!("    Stored - Combat ratings stored when the game was synced");
+    println!("    Local - Generated from each player's efficiency in earlier stored games");
+    println!("    Mixed - Group contains games from both sources");
+    println!();
+}
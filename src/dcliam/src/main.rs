@@ -0,0 +1,569 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use dcli::activitystoreinterface::{ActivityStoreInterface, LobbyMedalCounts};
+use dcli::crucible::{CruciblePlayerActivityPerformance, Medal};
+use dcli::enums::character::CharacterClassSelection;
+use dcli::enums::language::Language;
+use dcli::enums::medaltier::MedalTier;
+use dcli::enums::mode::Mode;
+use dcli::enums::moment::{DateTimePeriod, Moment};
+use dcli::enums::platform::Platform;
+use dcli::error::Error;
+use dcli::manifest::definitions::HistoricalStatsDefinition;
+use dcli::manifestinterface::ManifestInterface;
+use dcli::utils::{
+    build_activity_title, calculate_percent, calculate_ratio,
+    determine_data_dir, format_f32, print_error, print_verbose, repeat_str,
+    EXIT_FAILURE,
+};
+use num_format::{Locale, ToFormattedString};
+use structopt::StructOpt;
+
+fn parse_and_validate_mode(src: &str) -> Result<Mode, String> {
+    let mode = Mode::from_str(src)?;
+
+    if !mode.is_crucible() {
+        return Err(format!("Unsupported mode specified : {}", src));
+    }
+
+    Ok(mode)
+}
+
+fn parse_and_validate_moment(src: &str) -> Result<Moment, String> {
+    let moment = Moment::from_str(src)?;
+
+    Ok(moment)
+}
+
+fn parse_rfc3339(src: &str) -> Result<DateTime<Utc>, String> {
+    let d =
+        match DateTime::parse_from_rfc3339(src) {
+            Ok(e) => e,
+            Err(_e) => return Err(
+                "Invalid RFC 3339 Date / Time String : Example : 2020-12-08T17:00:00.774187+00:00"
+                    .to_string(),
+            ),
+        };
+
+    let d = d.with_timezone(&Utc);
+
+    if d > Utc::now() {
+        return Err("start-date must be in the past.".to_string());
+    }
+
+    Ok(d)
+}
+
+#[derive(Debug)]
+struct MedalSummary {
+    medal: Medal,
+    count: u32,
+    activity_count: u32,
+    first_earned: DateTime<Utc>,
+    last_earned: DateTime<Utc>,
+}
+
+fn summarize_medals(
+    data: &[CruciblePlayerActivityPerformance],
+) -> Vec<MedalSummary> {
+    let mut medals: HashMap<String, MedalSummary> = HashMap::new();
+
+    for activity in data {
+        let extended = match activity.performance.stats.extended.as_ref() {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let period = activity.activity_detail.period;
+
+        for m in &extended.medals {
+            if !m.medal.is_medal() || m.count == 0 {
+                continue;
+            }
+
+            let summary =
+                medals.entry(m.medal.id.clone()).or_insert(MedalSummary {
+                    medal: m.medal.clone(),
+                    count: 0,
+                    activity_count: 0,
+                    first_earned: period,
+                    last_earned: period,
+                });
+
+            summary.count += m.count;
+            summary.activity_count += 1;
+
+            if period < summary.first_earned {
+                summary.first_earned = period;
+            }
+
+            if period > summary.last_earned {
+                summary.last_earned = period;
+            }
+        }
+    }
+
+    let mut out: Vec<MedalSummary> = medals.into_iter().map(|e| e.1).collect();
+
+    //rarest tiers first, then by most earned
+    out.sort_by(|a, b| {
+        b.medal
+            .tier
+            .get_order()
+            .cmp(&a.medal.tier.get_order())
+            .then(b.count.cmp(&a.count))
+    });
+
+    out
+}
+
+//returns the percentage of all player performances in the lobbies in which
+//the medal was earned
+fn get_rarity(lobby: &LobbyMedalCounts, medal_id: &str) -> f32 {
+    let count = lobby.medal_counts.get(medal_id).copied().unwrap_or(0);
+    calculate_percent(count, lobby.performance_count)
+}
+
+fn print_default(
+    data: &[CruciblePlayerActivityPerformance],
+    lobby: &LobbyMedalCounts,
+    definitions: &[HistoricalStatsDefinition],
+    title: &str,
+) {
+    let medals = summarize_medals(data);
+    let activity_count = data.len() as u32;
+    let total_medals: u32 = medals.iter().map(|m| m.count).sum();
+
+    println!();
+    println!("MEDALS");
+    println!("==================");
+    println!("{}", title);
+    println!(
+        "{} medals earned ({} unique) across {} games",
+        total_medals.to_formatted_string(&Locale::en),
+        medals.len().to_formatted_string(&Locale::en),
+        activity_count.to_formatted_string(&Locale::en),
+    );
+    println!();
+
+    let name_col_w = 30;
+    let col_w = 10;
+    let date_col_w = 20;
+
+    let header = format!(
+        "{:<0name_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}    {:<0date_col_w$}{:<0date_col_w$}",
+        "MEDAL",
+        "COUNT",
+        "GAMES",
+        "PER GAME",
+        "RARITY",
+        "FIRST EARNED",
+        "LAST EARNED",
+        name_col_w = name_col_w,
+        col_w = col_w,
+        date_col_w = date_col_w,
+    );
+    let divider = repeat_str("=", header.chars().count());
+
+    let mut last_tier: Option<MedalTier> = None;
+    for m in &medals {
+        if last_tier.as_ref() != Some(&m.medal.tier) {
+            if last_tier.is_some() {
+                println!();
+            }
+
+            println!("{}", format!("{}", m.medal.tier).to_uppercase());
+            println!("{}", header);
+            println!("{}", divider);
+            last_tier = Some(m.medal.tier.clone());
+        }
+
+        println!(
+            "{:<0name_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}    {:<0date_col_w$}{:<0date_col_w$}",
+            m.medal.name,
+            m.count.to_formatted_string(&Locale::en),
+            m.activity_count.to_formatted_string(&Locale::en),
+            format_f32(calculate_ratio(m.count, activity_count), 2),
+            format!("{}%", format_f32(get_rarity(lobby, &m.medal.id), 2)),
+            m.first_earned.format("%B %-d, %Y").to_string(),
+            m.last_earned.format("%B %-d, %Y").to_string(),
+            name_col_w = name_col_w,
+            col_w = col_w,
+            date_col_w = date_col_w,
+        );
+    }
+
+    let mut unearned: Vec<&HistoricalStatsDefinition> = definitions
+        .iter()
+        .filter(|d| !medals.iter().any(|m| m.medal.id == d.id))
+        .collect();
+
+    unearned.sort_by(|a, b| {
+        let a_tier = a.medal_tier.as_ref().map(|e| e.get_order()).unwrap_or(0);
+        let b_tier = b.medal_tier.as_ref().map(|e| e.get_order()).unwrap_or(0);
+
+        b_tier.cmp(&a_tier).then(a.name.cmp(&b.name))
+    });
+
+    println!();
+    println!();
+    println!("MEDALS NOT EARNED");
+    println!("==================");
+
+    if unearned.is_empty() {
+        println!("All medals have been earned.");
+    }
+
+    let tier_col_w = 10;
+    for d in &unearned {
+        let tier = d.medal_tier.clone().unwrap_or(MedalTier::Unknown);
+        println!(
+            "[ ] {:<0name_col_w$}{:<0tier_col_w$}{:>0col_w$}",
+            d.name,
+            format!("{}", tier),
+            format!("{}%", format_f32(get_rarity(lobby, &d.id), 2)),
+            name_col_w = name_col_w,
+            tier_col_w = tier_col_w,
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!("PER GAME - Number of times earned per game played");
+    println!(
+        "RARITY - Percentage of all player performances in your games where the medal was earned"
+    );
+    println!();
+}
+
+//returns all of the Crucible medal definitions from the manifest
+async fn get_medal_definitions(
+    manifest: &mut ManifestInterface,
+) -> Result<Vec<HistoricalStatsDefinition>, Error> {
+    let definitions = manifest.get_historical_stats_definitions().await?;
+
+    let out: Vec<HistoricalStatsDefinition> = definitions
+        .into_iter()
+        .filter(|d| match d.medal_tier {
+            Some(MedalTier::Unknown) | None => false,
+            Some(_) => {
+                //medals without any modes listed apply everywhere
+                d.modes.is_empty()
+                    || d.modes.iter().any(|m| match Mode::from_id(*m) {
+                        Ok(e) => e.is_crucible(),
+                        Err(_e) => false,
+                    })
+            }
+        })
+        .collect();
+
+    Ok(out)
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(verbatim_doc_comment)]
+/// Command line tool for viewing Destiny 2 Crucible medal history and stats.
+///
+/// Displays all of the medals earned within a time period grouped by tier,
+/// including when each was first and last earned, and how rare each medal is
+/// across all of the players in your games. Medals which have not been earned
+/// are displayed as a checklist.
+///
+/// Created by Mike Chambers.
+/// https://www.mikechambers.com
+///
+/// Get support, request features or just chat on the dcli Discord server:
+/// https://discord.gg/2Y8bV2Mq3p
+///
+/// Get the latest version, download the source and log issues at:
+/// https://github.com/mikechambers/dcli
+///
+/// Released under an MIT License.
+struct Opt {
+    /// Destiny 2 API member id
+    ///
+    /// This is not the user name, but the member id retrieved from the Destiny API.
+    #[structopt(short = "m", long = "member-id", required = true)]
+    member_id: String,
+
+    /// Platform for specified id
+    ///
+    /// Valid values are: xbox, playstation, stadia or steam.
+    #[structopt(short = "p", long = "platform", required = true)]
+    platform: Platform,
+
+    /// Custom start time in RFC 3339 date / time format
+    ///
+    /// Must be a valid date in the past.
+    ///
+    /// Example RFC 3339 format: 2020-12-08T17:00:00.774187+00:00
+    ///
+    /// Required when --moment is set to custom, but otherwise not applicable.
+    #[structopt(short = "t", long = "custom-time", parse(try_from_str = parse_rfc3339), required_if("moment", "custom"))]
+    custom_time: Option<DateTime<Utc>>,
+
+    /// Custom end time in RFC 3339 date / time format
+    ///
+    /// Must be a valid date in the past.
+    ///
+    /// Example RFC 3339 format: 2020-12-08T17:00:00.774187+00:00
+    ///
+    /// Required when --end-moment is set to custom, but otherwise not applicable.
+    #[structopt(short = "e", long = "end-custom-time", parse(try_from_str = parse_rfc3339), required_if("end-moment", "custom"))]
+    end_custom_time: Option<DateTime<Utc>>,
+
+    /// Start moment from which to pull activities from
+    ///
+    /// Activities will be retrieved from moment to end-moment.
+    ///
+    /// Valid values include daily (last daily reset), weekend
+    /// (last weekend reset on Friday), weekly (last weekly reset on Tuesday),
+    /// day (last day), week (last week), month (last month), all_time and custom
+    /// as well as the season moments current_season, previous_season and
+    /// season:<name-or-number> (i.e. season:13 or season:chosen). Season
+    /// moments are the start of the season, loaded from the manifest.
+    ///
+    /// When custom is specified, the custom start date in RFC3339 format must
+    /// be specified with the --custom-time argument.
+    #[structopt(long = "moment", parse(try_from_str=parse_and_validate_moment), 
+        short = "T", default_value = "all_time")]
+    moment: Moment,
+
+    /// End moment from which to pull activities from
+    ///
+    /// Activities will be retrieved from moment to end-moment. End moment
+    /// must be greater than moment
+    ///
+    /// Valid values are the same as --moment.
+    ///
+    /// Defaults to now, or to the end of the season when --moment is a season
+    /// moment.
+    ///
+    /// When custom is specified, the custom start date in RFC3339 format must
+    /// be specified with the --end-custom-time argument.
+    #[structopt(long = "end-moment", parse(try_from_str=parse_and_validate_moment), 
+        short = "E")]
+    end_moment: Option<Moment>,
+
+    /// Activity mode to return stats for
+    ///
+    /// Supported values are all_pvp (default), control, clash, elimination,
+    /// mayhem, iron_banner, all_private, rumble, pvp_competitive,
+    /// quickplay and trials_of_osiris.
+    ///
+    /// Addition values available are crimsom_doubles, supremacy, survival,
+    /// countdown, all_doubles, doubles, private_clash, private_control,
+    /// private_survival, private_rumble, showdown, lockdown,
+    /// scorched, scorched_team, breakthrough, clash_quickplay, trials_of_the_nine
+    #[structopt(long = "mode", short = "M", 
+        parse(try_from_str=parse_and_validate_mode), default_value = "all_pvp")]
+    mode: Mode,
+
+    /// Character to retrieve data for
+    ///
+    /// Valid values include hunter, titan, warlock, last_active and all.
+    #[structopt(short = "C", long = "class", default_value = "all")]
+    character_class_selection: CharacterClassSelection,
+
+    /// Lowest placement counted as a win in free for all modes
+    ///
+    /// Free for all modes such as Rumble rank players by placement. Any
+    /// placement up to and including this value will be treated as a victory
+    /// when calculating wins, losses and win rates.
+    #[structopt(long = "ffa-win-placement", default_value = "3")]
+    ffa_win_placement: u32,
+
+    ///Print out additional information
+    ///
+    ///Output is printed to stderr.
+    #[structopt(short = "v", long = "verbose")]
+    verbose: bool,
+
+    /// Don't sync activities
+    ///
+    /// If flag is set, activities will not be retrieved before displaying stats.
+    /// This is useful in case you are syncing activities in a seperate process.
+    #[structopt(short = "N", long = "no-sync")]
+    no_sync: bool,
+
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
+    /// Directory where Destiny 2 manifest and activity database files are stored. (optional)
+    ///
+    /// This will normally be downloaded using the dclim and dclias tools, and uses
+    /// a system appropriate directory by default.
+    #[structopt(short = "D", long = "data-dir", parse(from_os_str))]
+    data_dir: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+    let opt = Opt::from_args();
+    print_verbose(&format!("{:#?}", opt), opt.verbose);
+
+    let data_dir = match determine_data_dir(opt.data_dir) {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Error initializing manifest directory.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut manifest =
+        match ManifestInterface::new(&data_dir, &opt.language, false).await {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize manifest. Have you run dclim?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    let time_period = match DateTimePeriod::with_moments(
+        &opt.moment,
+        opt.custom_time,
+        opt.end_moment.as_ref(),
+        opt.end_custom_time,
+        &mut manifest,
+    )
+    .await
+    {
+        Ok(e) => e,
+        Err(Error::DateTimePeriodOrder) => {
+            eprintln!("--end-moment must be greater than --moment");
+            std::process::exit(EXIT_FAILURE);
+        }
+        Err(e) => {
+            print_error(
+                "Could not determine --moment / --end-moment date / time.",
+                e,
+            );
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut store =
+        match ActivityStoreInterface::init_with_path(&data_dir, opt.verbose)
+            .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize activity store. Have you run dclias?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    store.set_ffa_win_placement(opt.ffa_win_placement);
+
+    if !opt.no_sync {
+        match store.sync(&opt.member_id, &opt.platform).await {
+            Ok(_e) => (),
+            Err(e) => {
+                eprintln!("Could not sync activity store {}", e);
+                eprintln!("Using existing data");
+            }
+        };
+    }
+
+    let data = match store
+        .retrieve_activities_since(
+            &opt.member_id,
+            &opt.character_class_selection,
+            &opt.platform,
+            &opt.mode,
+            &time_period,
+            &mut manifest,
+        )
+        .await
+    {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Could not retrieve data from activity store.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let data: Vec<CruciblePlayerActivityPerformance> = match data {
+        Some(e) if !e.is_empty() => e,
+        _ => {
+            println!("No activities found");
+            return;
+        }
+    };
+
+    let lobby = match store
+        .retrieve_lobby_medal_counts(
+            &opt.member_id,
+            &opt.character_class_selection,
+            &opt.platform,
+            &opt.mode,
+            &time_period,
+        )
+        .await
+    {
+        Ok(e) => e,
+        Err(e) => {
+            print_error(
+                "Could not retrieve medal data from activity store.",
+                e,
+            );
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let definitions = match get_medal_definitions(&mut manifest).await {
+        Ok(e) => e,
+        Err(e) => {
+            print_error(
+                "Could not retrieve medal definitions from manifest.",
+                e,
+            );
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let title = build_activity_title(
+        &opt.mode,
+        &time_period,
+        &opt.moment,
+        opt.end_moment.as_ref(),
+    );
+
+    print_default(&data, &lobby, &definitions, &title);
+}
@@ -23,7 +23,9 @@
 use std::path::PathBuf;
 
 use dcli::activitystoreinterface::ActivityStoreInterface;
+use dcli::enums::language::Language;
 use dcli::enums::platform::Platform;
+use dcli::manifestinterface::ManifestInterface;
 use dcli::output::Output;
 use dcli::utils::{
     build_tsv, determine_data_dir, print_error, print_verbose, EXIT_FAILURE,
@@ -67,6 +69,22 @@ struct Opt {
     )]
     output: Output,
 
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
+    /// Lowest placement counted as a win in free for all modes
+    ///
+    /// Free for all modes such as Rumble rank players by placement. Any
+    /// placement up to and including this value will be treated as a victory
+    /// when detecting win streaks after a sync.
+    #[structopt(long = "ffa-win-placement", default_value = "3")]
+    ffa_win_placement: u32,
+
     /// Directory where activity sqlite3 database will be stored. (optional)
     ///
     /// By default data will be loaded from and stored in the appropriate system
@@ -87,6 +105,19 @@ struct Opt {
     /// retrieved from the Destiny API.
     #[structopt(short = "m", long = "member-id", required = true)]
     member_id: String,
+
+    /// Store the combat rating of each player in newly synced activities
+    ///
+    /// If flag is set, the current combat rating for the activity mode of each
+    /// player in the lobby will be retrieved and stored with each new activity
+    /// that is synced, allowing tools such as dcliad to display them without
+    /// having to make additional api calls. Ratings are only stored for
+    /// activities played within the last 48 hours, since older activities
+    /// would be stored with the player's current rating. Each rating records
+    /// the time it was synced. This makes an additional api call per player
+    /// per activity, so can increase sync times.
+    #[structopt(short = "r", long = "sync-combat-ratings")]
+    sync_combat_ratings: bool,
 }
 
 #[tokio::main]
@@ -113,6 +144,9 @@ async fn main() {
             }
         };
 
+    store.set_sync_combat_ratings(opt.sync_combat_ratings);
+    store.set_ffa_win_placement(opt.ffa_win_placement);
+
     let results = match store.sync(&opt.member_id, &opt.platform).await {
         Ok(e) => e,
         Err(e) => {
@@ -123,7 +157,23 @@ async fn main() {
 
     match opt.output {
         Output::Default => {
-            print_default(&results, &store);
+            //manifest is only used to display milestone names, so we dont
+            //require it in order to sync
+            let mut manifest =
+                match ManifestInterface::new(&data_dir, &opt.language, false)
+                    .await
+                {
+                    Ok(e) => Some(e),
+                    Err(e) => {
+                        print_verbose(
+                            &format!("Could not initialize manifest : {}", e),
+                            opt.verbose,
+                        );
+                        None
+                    }
+                };
+
+            print_default(&results, &store, &mut manifest).await;
         }
         Output::Tsv => {
             print_tsv(&results, &store);
@@ -137,11 +187,16 @@ fn print_tsv(results: &SyncResult, store: &ActivityStoreInterface) {
     name_values.push(("total_synced", results.total_synced.to_string()));
     name_values.push(("total_available", results.total_available.to_string()));
     name_values.push(("path", store.get_storage_path()));
+    name_values.push(("milestones", results.milestones.len().to_string()));
 
     print!("{}", build_tsv(name_values));
 }
 
-fn print_default(results: &SyncResult, store: &ActivityStoreInterface) {
+async fn print_default(
+    results: &SyncResult,
+    store: &ActivityStoreInterface,
+    manifest: &mut Option<ManifestInterface>,
+) {
     println!();
     println!("{}", "Activity sync complete".to_string().to_uppercase());
     println!("------------------------------------------------");
@@ -170,4 +225,25 @@ fn print_default(results: &SyncResult, store: &ActivityStoreInterface) {
     println!("{}", queue_str);
 
     println!("Database stored at: {}", store.get_storage_path());
+
+    if results.milestones.is_empty() {
+        return;
+    }
+
+    println!();
+    println!("{}", "Milestones".to_string().to_uppercase());
+    println!("------------------------------------------------");
+
+    for m in results.milestones.iter() {
+        let description = match manifest {
+            Some(manifest) => m.get_description(manifest).await,
+            None => format!(
+                "New {} : {}",
+                m.milestone_type,
+                m.period.format("%B %-d, %Y")
+            ),
+        };
+
+        println!("{}", description);
+    }
 }
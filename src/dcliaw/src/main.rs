@@ -0,0 +1,830 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use dcli::activitystoreinterface::ActivityStoreInterface;
+use dcli::crucible::{
+    AggregateCruciblePerformances, CruciblePlayerActivityPerformance,
+    CruciblePlayerPerformance, Item, WeaponStat,
+};
+use dcli::enums::character::CharacterClassSelection;
+use dcli::enums::itemtype::ItemType;
+use dcli::enums::language::Language;
+use dcli::enums::mode::Mode;
+use dcli::enums::moment::{DateTimePeriod, Moment};
+use dcli::enums::platform::Platform;
+use dcli::enums::standing::Standing;
+use dcli::error::Error;
+use dcli::manifestinterface::ManifestInterface;
+use dcli::utils::{
+    build_activity_title, calculate_percent, calculate_ratio,
+    determine_data_dir, format_f32, print_error, print_verbose, repeat_str,
+    EXIT_FAILURE,
+};
+use num_format::{Locale, ToFormattedString};
+use structopt::StructOpt;
+
+fn parse_and_validate_mode(src: &str) -> Result<Mode, String> {
+    let mode = Mode::from_str(src)?;
+
+    if !mode.is_crucible() {
+        return Err(format!("Unsupported mode specified : {}", src));
+    }
+
+    Ok(mode)
+}
+
+fn parse_and_validate_moment(src: &str) -> Result<Moment, String> {
+    let moment = Moment::from_str(src)?;
+
+    Ok(moment)
+}
+
+fn parse_rfc3339(src: &str) -> Result<DateTime<Utc>, String> {
+    let d =
+        match DateTime::parse_from_rfc3339(src) {
+            Ok(e) => e,
+            Err(_e) => return Err(
+                "Invalid RFC 3339 Date / Time String : Example : 2020-12-08T17:00:00.774187+00:00"
+                    .to_string(),
+            ),
+        };
+
+    let d = d.with_timezone(&Utc);
+
+    if d > Utc::now() {
+        return Err("start-date must be in the past.".to_string());
+    }
+
+    Ok(d)
+}
+
+/// Stats for a single weapon (or group of weapons) across a set of activities.
+#[derive(Debug, Default)]
+struct WeaponUsage {
+    activity_count: u32,
+    wins: u32,
+    kills: u32,
+    precision_kills: u32,
+}
+
+impl WeaponUsage {
+    fn add(&mut self, kills: u32, precision_kills: u32) {
+        self.kills += kills;
+        self.precision_kills += precision_kills;
+    }
+
+    fn add_activity(&mut self, standing: Standing) {
+        self.activity_count += 1;
+        if standing == Standing::Victory {
+            self.wins += 1;
+        }
+    }
+
+    fn win_rate(&self) -> f32 {
+        calculate_percent(self.wins, self.activity_count)
+    }
+
+    fn precision_percent(&self) -> f32 {
+        calculate_percent(self.precision_kills, self.kills)
+    }
+
+    fn kills_per_game(&self) -> f32 {
+        calculate_ratio(self.kills, self.activity_count)
+    }
+}
+
+fn find_weapon_stat(
+    performance: &CruciblePlayerPerformance,
+    weapon_id: u32,
+) -> Option<&WeaponStat> {
+    match performance.stats.extended.as_ref() {
+        Some(e) => e.weapons.iter().find(|w| w.weapon.id == weapon_id),
+        None => None,
+    }
+}
+
+/// Finds the weapon specified by the user. Numeric values are treated as item
+/// hashes and looked up in the manifest, otherwise we match against the names
+/// of the weapons used within the activities.
+async fn resolve_weapon(
+    weapon: &str,
+    data: &[CruciblePlayerActivityPerformance],
+    manifest: &mut ManifestInterface,
+) -> Result<Item, String> {
+    if let Ok(hash) = weapon.parse::<u32>() {
+        return match manifest.get_iventory_item_definition(hash).await {
+            Ok(Some(e)) => {
+                if e.item_type != ItemType::Weapon {
+                    return Err(format!(
+                        "Item {} ({}) is not a weapon.",
+                        e.display_properties.name, hash
+                    ));
+                }
+
+                Ok(Item {
+                    id: e.id,
+                    name: e.display_properties.name,
+                    description: e
+                        .display_properties
+                        .description
+                        .unwrap_or_else(|| "".to_string()),
+                    item_type: e.item_type,
+                    item_sub_type: e.item_sub_type,
+                })
+            }
+            Ok(None) => {
+                Err(format!("Could not find weapon in manifest : {}", hash))
+            }
+            Err(e) => Err(format!("Error searching manifest : {}", e)),
+        };
+    }
+
+    let needle = weapon.to_lowercase();
+    let mut matches: HashMap<u32, Item> = HashMap::new();
+
+    for activity in data {
+        if let Some(e) = activity.performance.stats.extended.as_ref() {
+            for w in &e.weapons {
+                let name = w.weapon.name.to_lowercase();
+
+                //exact match always wins
+                if name == needle {
+                    return Ok(w.weapon.clone());
+                }
+
+                if name.contains(&needle) {
+                    matches.insert(w.weapon.id, w.weapon.clone());
+                }
+            }
+        }
+    }
+
+    match matches.len() {
+        0 => Err(format!(
+            "No weapon matching \"{}\" was used in the specified activities.",
+            weapon
+        )),
+        1 => Ok(matches.into_iter().next().unwrap().1),
+        _ => {
+            let mut names: Vec<String> = matches
+                .values()
+                .map(|w| format!("{} ({})", w.name, w.id))
+                .collect();
+            names.sort();
+
+            Err(format!(
+                "Multiple weapons match \"{}\". Please be more specific, or specify the weapon hash:\n{}",
+                weapon,
+                names.join("\n")
+            ))
+        }
+    }
+}
+
+fn print_weapon_types(
+    data: &[CruciblePlayerActivityPerformance],
+    aggregate: &AggregateCruciblePerformances,
+    title: &str,
+) {
+    //we key on the display name, since ItemSubType isnt hashable
+    let mut types: HashMap<String, WeaponUsage> = HashMap::new();
+    let mut weapon_counts: HashMap<String, HashSet<u32>> = HashMap::new();
+
+    for activity in data {
+        let extended = match activity.performance.stats.extended.as_ref() {
+            Some(e) => e,
+            None => continue,
+        };
+
+        let mut types_in_activity: HashSet<String> = HashSet::new();
+        for w in &extended.weapons {
+            let key = format!("{}", w.weapon.item_sub_type);
+
+            types
+                .entry(key.clone())
+                .or_default()
+                .add(w.kills, w.precision_kills);
+
+            weapon_counts
+                .entry(key.clone())
+                .or_default()
+                .insert(w.weapon.id);
+
+            types_in_activity.insert(key);
+        }
+
+        for key in types_in_activity {
+            if let Some(e) = types.get_mut(&key) {
+                e.add_activity(activity.performance.stats.standing);
+            }
+        }
+    }
+
+    let mut types: Vec<(String, WeaponUsage)> = types.into_iter().collect();
+    types.sort_by_key(|t| std::cmp::Reverse(t.1.kills));
+
+    println!();
+    println!("WEAPONS BY TYPE");
+    println!("==================");
+    println!("{}", title);
+    println!();
+
+    let col_w = 10;
+    let type_col_w = 22;
+
+    let header = format!(
+        "{:<0type_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "TYPE",
+        "WEAPONS",
+        "GAMES",
+        "KILLS",
+        "% TOTAL",
+        "K/Gu",
+        "PREC",
+        "% PREC",
+        "WIN %",
+        col_w = col_w,
+        type_col_w = type_col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for (name, t) in &types {
+        println!(
+            "{:<0type_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            name,
+            weapon_counts
+                .get(name)
+                .map(|e| e.len())
+                .unwrap_or(0)
+                .to_formatted_string(&Locale::en),
+            t.activity_count.to_formatted_string(&Locale::en),
+            t.kills.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(t.kills, aggregate.kills), 2)),
+            format_f32(t.kills_per_game(), 2),
+            t.precision_kills.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(t.precision_percent(), 2)),
+            format!("{}%", format_f32(t.win_rate(), 2)),
+            col_w = col_w,
+            type_col_w = type_col_w,
+        );
+    }
+
+    println!();
+    println!("% TOTAL - Percentage of all kills");
+    println!("K/Gu - Kills per game where a weapon of the type was used");
+    println!("WIN % - Win rate in games where a weapon of the type was used");
+    println!();
+}
+
+fn print_weapon(
+    weapon: &Item,
+    data: &[CruciblePlayerActivityPerformance],
+    aggregate: &AggregateCruciblePerformances,
+    title: &str,
+    activity_limit: u32,
+) {
+    let mut usage = WeaponUsage::default();
+
+    //month label, (weapon usage, total activities in month)
+    let mut months: Vec<(String, WeaponUsage, u32)> = Vec::new();
+
+    //data is sorted with most recent activities first, so we walk it backwards
+    //to build the usage over time
+    for activity in data.iter().rev() {
+        let month = activity.activity_detail.period.format("%Y-%m").to_string();
+
+        let needs_month = match months.last() {
+            Some(e) => e.0 != month,
+            None => true,
+        };
+
+        if needs_month {
+            months.push((month, WeaponUsage::default(), 0));
+        }
+
+        let m = months.last_mut().unwrap();
+        m.2 += 1;
+
+        if let Some(w) = find_weapon_stat(&activity.performance, weapon.id) {
+            let standing = activity.performance.stats.standing;
+
+            usage.add(w.kills, w.precision_kills);
+            usage.add_activity(standing);
+
+            m.1.add(w.kills, w.precision_kills);
+            m.1.add_activity(standing);
+        }
+    }
+
+    println!();
+    println!("{}", weapon.name.to_uppercase());
+    println!("==================");
+    println!("{} ({})", weapon.item_sub_type, weapon.id);
+    println!("{}", title);
+    println!();
+
+    if usage.activity_count == 0 {
+        println!("Weapon was not used in any of the activities.");
+        return;
+    }
+
+    let overall_precision_kills = match aggregate.extended.as_ref() {
+        Some(e) => e.precision_kills,
+        None => 0,
+    };
+
+    let label_col_w = 18;
+    let col_w = 12;
+
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}",
+        "",
+        "WEAPON",
+        "OVERALL",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let rows = [
+        (
+            "Games",
+            usage.activity_count.to_formatted_string(&Locale::en),
+            aggregate.total_activities.to_formatted_string(&Locale::en),
+        ),
+        (
+            "Usage",
+            format!(
+                "{}%",
+                format_f32(
+                    calculate_percent(
+                        usage.activity_count,
+                        aggregate.total_activities
+                    ),
+                    2
+                )
+            ),
+            "".to_string(),
+        ),
+        (
+            "Win Rate",
+            format!("{}%", format_f32(usage.win_rate(), 2)),
+            format!("{}%", format_f32(aggregate.win_rate, 2)),
+        ),
+        (
+            "Kills",
+            usage.kills.to_formatted_string(&Locale::en),
+            aggregate.kills.to_formatted_string(&Locale::en),
+        ),
+        (
+            "Kills / Game",
+            format_f32(usage.kills_per_game(), 2),
+            format_f32(aggregate.stat_per_game(aggregate.kills), 2),
+        ),
+        (
+            "Precision %",
+            format!("{}%", format_f32(usage.precision_percent(), 2)),
+            format!(
+                "{}%",
+                format_f32(
+                    calculate_percent(overall_precision_kills, aggregate.kills),
+                    2
+                )
+            ),
+        ),
+        (
+            "% Of All Kills",
+            format!(
+                "{}%",
+                format_f32(calculate_percent(usage.kills, aggregate.kills), 2)
+            ),
+            "".to_string(),
+        ),
+    ];
+
+    for (label, weapon_value, overall_value) in rows.iter() {
+        println!(
+            "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}",
+            label,
+            weapon_value,
+            overall_value,
+            label_col_w = label_col_w,
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!("USAGE OVER TIME");
+    println!("------------------");
+
+    let header = format!(
+        "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+        "MONTH",
+        "GAMES",
+        "USAGE %",
+        "KILLS",
+        "K/G",
+        "WIN %",
+        label_col_w = label_col_w,
+        col_w = col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for (month, m, total) in months.iter() {
+        println!(
+            "{:<0label_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}",
+            month,
+            m.activity_count.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(m.activity_count, *total), 2)),
+            m.kills.to_formatted_string(&Locale::en),
+            format_f32(m.kills_per_game(), 2),
+            format!("{}%", format_f32(m.win_rate(), 2)),
+            label_col_w = label_col_w,
+            col_w = col_w,
+        );
+    }
+
+    println!();
+    println!("ACTIVITIES");
+    println!("------------------");
+
+    let map_col_w = 18;
+    let wl_col_w = 10;
+    let date_col_w = 20;
+    let id_col_w = 8;
+    let col_w = 8;
+
+    let header = format!(
+        "{:<0date_col_w$}{:<0map_col_w$}{:<0wl_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0id_col_w$}",
+        "DATE",
+        "MAP",
+        "W/L",
+        "KILLS",
+        "PREC",
+        "% PREC",
+        "TOTAL",
+        "INDEX",
+        date_col_w = date_col_w,
+        map_col_w = map_col_w,
+        wl_col_w = wl_col_w,
+        col_w = col_w,
+        id_col_w = id_col_w,
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    let mut displayed = 0;
+    for activity in data {
+        let w = match find_weapon_stat(&activity.performance, weapon.id) {
+            Some(e) => e,
+            None => continue,
+        };
+
+        if displayed >= activity_limit {
+            println!("...");
+            println!();
+            println!(
+                "Displaying the last {} of {} activities (use --activity-limit to display more)",
+                activity_limit, usage.activity_count
+            );
+            break;
+        }
+
+        println!(
+            "{:<0date_col_w$}{:<0map_col_w$}{:<0wl_col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0col_w$}{:>0id_col_w$}",
+            activity.activity_detail.period.format("%B %-d, %Y"),
+            activity.activity_detail.map_name,
+            format!("{}", activity.performance.stats.standing),
+            w.kills.to_formatted_string(&Locale::en),
+            w.precision_kills.to_formatted_string(&Locale::en),
+            format!("{}%", format_f32(calculate_percent(w.precision_kills, w.kills), 2)),
+            activity.performance.stats.kills.to_formatted_string(&Locale::en),
+            activity.activity_detail.index_id.to_string(),
+            date_col_w = date_col_w,
+            map_col_w = map_col_w,
+            wl_col_w = wl_col_w,
+            col_w = col_w,
+            id_col_w = id_col_w,
+        );
+
+        displayed += 1;
+    }
+
+    println!();
+    println!("TOTAL - Total kills (all sources) in the activity");
+    println!();
+}
+
+#[derive(StructOpt, Debug)]
+#[structopt(verbatim_doc_comment)]
+/// Command line tool for viewing Destiny 2 Crucible weapon history and stats.
+///
+/// Displays every game a specific weapon was used in, along with how it
+/// performs compared to your overall stats. If no weapon is specified, stats
+/// will be displayed rolled up by weapon type.
+///
+/// Created by Mike Chambers.
+/// https://www.mikechambers.com
+///
+/// Get support, request features or just chat on the dcli Discord server:
+/// https://discord.gg/2Y8bV2Mq3p
+///
+/// Get the latest version, download the source and log issues at:
+/// https://github.com/mikechambers/dcli
+///
+/// Released under an MIT License.
+struct Opt {
+    /// Destiny 2 API member id
+    ///
+    /// This is not the user name, but the member id retrieved from the Destiny API.
+    #[structopt(short = "m", long = "member-id", required = true)]
+    member_id: String,
+
+    /// Platform for specified id
+    ///
+    /// Valid values are: xbox, playstation, stadia or steam.
+    #[structopt(short = "p", long = "platform", required = true)]
+    platform: Platform,
+
+    /// Weapon to display history for
+    ///
+    /// Can be either the weapon name (or part of the name), or the weapon
+    /// item hash from the Destiny 2 manifest. If not specified, stats will be
+    /// displayed by weapon type.
+    #[structopt(short = "w", long = "weapon")]
+    weapon: Option<String>,
+
+    /// Custom start time in RFC 3339 date / time format
+    ///
+    /// Must be a valid date in the past.
+    ///
+    /// Example RFC 3339 format: 2020-12-08T17:00:00.774187+00:00
+    ///
+    /// Required when --moment is set to custom, but otherwise not applicable.
+    #[structopt(short = "t", long = "custom-time", parse(try_from_str = parse_rfc3339), required_if("moment", "custom"))]
+    custom_time: Option<DateTime<Utc>>,
+
+    /// Custom end time in RFC 3339 date / time format
+    ///
+    /// Must be a valid date in the past.
+    ///
+    /// Example RFC 3339 format: 2020-12-08T17:00:00.774187+00:00
+    ///
+    /// Required when --end-moment is set to custom, but otherwise not applicable.
+    #[structopt(short = "e", long = "end-custom-time", parse(try_from_str = parse_rfc3339), required_if("end-moment", "custom"))]
+    end_custom_time: Option<DateTime<Utc>>,
+
+    /// Start moment from which to pull activities from
+    ///
+    /// Activities will be retrieved from moment to end-moment.
+    ///
+    /// Valid values include daily (last daily reset), weekend
+    /// (last weekend reset on Friday), weekly (last weekly reset on Tuesday),
+    /// day (last day), week (last week), month (last month), all_time and custom
+    /// as well as the season moments current_season, previous_season and
+    /// season:<name-or-number> (i.e. season:13 or season:chosen). Season
+    /// moments are the start of the season, loaded from the manifest.
+    ///
+    /// When custom is specified, the custom start date in RFC3339 format must
+    /// be specified with the --custom-time argument.
+    #[structopt(long = "moment", parse(try_from_str=parse_and_validate_moment), 
+        short = "T", default_value = "month")]
+    moment: Moment,
+
+    /// End moment from which to pull activities from
+    ///
+    /// Activities will be retrieved from moment to end-moment. End moment
+    /// must be greater than moment
+    ///
+    /// Valid values are the same as --moment.
+    ///
+    /// Defaults to now, or to the end of the season when --moment is a season
+    /// moment.
+    ///
+    /// When custom is specified, the custom start date in RFC3339 format must
+    /// be specified with the --end-custom-time argument.
+    #[structopt(long = "end-moment", parse(try_from_str=parse_and_validate_moment), 
+        short = "E")]
+    end_moment: Option<Moment>,
+
+    /// Activity mode to return stats for
+    ///
+    /// Supported values are all_pvp (default), control, clash, elimination,
+    /// mayhem, iron_banner, all_private, rumble, pvp_competitive,
+    /// quickplay and trials_of_osiris.
+    ///
+    /// Addition values available are crimsom_doubles, supremacy, survival,
+    /// countdown, all_doubles, doubles, private_clash, private_control,
+    /// private_survival, private_rumble, showdown, lockdown,
+    /// scorched, scorched_team, breakthrough, clash_quickplay, trials_of_the_nine
+    #[structopt(long = "mode", short = "M", 
+        parse(try_from_str=parse_and_validate_mode), default_value = "all_pvp")]
+    mode: Mode,
+
+    /// Character to retrieve data for
+    ///
+    /// Valid values include hunter, titan, warlock, last_active and all.
+    #[structopt(short = "C", long = "class", default_value = "all")]
+    character_class_selection: CharacterClassSelection,
+
+    /// Lowest placement counted as a win in free for all modes
+    ///
+    /// Free for all modes such as Rumble rank players by placement. Any
+    /// placement up to and including this value will be treated as a victory
+    /// when calculating wins, losses and win rates.
+    #[structopt(long = "ffa-win-placement", default_value = "3")]
+    ffa_win_placement: u32,
+
+    /// Limit the number of activities that will be displayed for the weapon
+    ///
+    /// Summary information will be generated based on all activities.
+    #[structopt(long = "activity-limit", short = "L", default_value = "10")]
+    activity_limit: u32,
+
+    ///Print out additional information
+    ///
+    ///Output is printed to stderr.
+    #[structopt(short = "v", long = "verbose")]
+    verbose: bool,
+
+    /// Don't sync activities
+    ///
+    /// If flag is set, activities will not be retrieved before displaying stats.
+    /// This is useful in case you are syncing activities in a seperate process.
+    #[structopt(short = "N", long = "no-sync")]
+    no_sync: bool,
+
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
+    /// Directory where Destiny 2 manifest and activity database files are stored. (optional)
+    ///
+    /// This will normally be downloaded using the dclim and dclias tools, and uses
+    /// a system appropriate directory by default.
+    #[structopt(short = "D", long = "data-dir", parse(from_os_str))]
+    data_dir: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() {
+    let opt = Opt::from_args();
+    print_verbose(&format!("{:#?}", opt), opt.verbose);
+
+    let data_dir = match determine_data_dir(opt.data_dir) {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Error initializing manifest directory.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut manifest =
+        match ManifestInterface::new(&data_dir, &opt.language, false).await {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize manifest. Have you run dclim?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    let time_period = match DateTimePeriod::with_moments(
+        &opt.moment,
+        opt.custom_time,
+        opt.end_moment.as_ref(),
+        opt.end_custom_time,
+        &mut manifest,
+    )
+    .await
+    {
+        Ok(e) => e,
+        Err(Error::DateTimePeriodOrder) => {
+            eprintln!("--end-moment must be greater than --moment");
+            std::process::exit(EXIT_FAILURE);
+        }
+        Err(e) => {
+            print_error(
+                "Could not determine --moment / --end-moment date / time.",
+                e,
+            );
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let mut store =
+        match ActivityStoreInterface::init_with_path(&data_dir, opt.verbose)
+            .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                print_error(
+                    "Could not initialize activity store. Have you run dclias?",
+                    e,
+                );
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+    store.set_ffa_win_placement(opt.ffa_win_placement);
+
+    if !opt.no_sync {
+        match store.sync(&opt.member_id, &opt.platform).await {
+            Ok(_e) => (),
+            Err(e) => {
+                eprintln!("Could not sync activity store {}", e);
+                eprintln!("Using existing data");
+            }
+        };
+    }
+
+    let data = match store
+        .retrieve_activities_since(
+            &opt.member_id,
+            &opt.character_class_selection,
+            &opt.platform,
+            &opt.mode,
+            &time_period,
+            &mut manifest,
+        )
+        .await
+    {
+        Ok(e) => e,
+        Err(e) => {
+            print_error("Could not retrieve data from activity store.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    };
+
+    let data: Vec<CruciblePlayerActivityPerformance> = match data {
+        Some(e) if !e.is_empty() => e,
+        _ => {
+            println!("No activities found");
+            return;
+        }
+    };
+
+    let cpp: Vec<&CruciblePlayerPerformance> =
+        data.iter().map(|x| &x.performance).collect();
+    let aggregate = AggregateCruciblePerformances::with_performances(&cpp);
+
+    let title = build_activity_title(
+        &opt.mode,
+        &time_period,
+        &opt.moment,
+        opt.end_moment.as_ref(),
+    );
+
+    match opt.weapon {
+        Some(weapon) => {
+            let item = match resolve_weapon(&weapon, &data, &mut manifest).await
+            {
+                Ok(e) => e,
+                Err(e) => {
+                    eprintln!("{}", e);
+                    std::process::exit(EXIT_FAILURE);
+                }
+            };
+
+            print_weapon(&item, &data, &aggregate, &title, opt.activity_limit);
+        }
+        None => {
+            print_weapon_types(&data, &aggregate, &title);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use dcli::manifestdiff::{DefinitionSummary, ManifestDiff, TableDiff};
+use dcli::utils::{repeat_str, truncate_ascii_string, TSV_DELIM, TSV_EOL};
+
+//maximum number of changed fields displayed per definition
+const MAX_FIELD_COUNT: usize = 10;
+
+//maximum length of values displayed for changed fields
+const MAX_VALUE_LENGTH: usize = 80;
+
+fn format_summary(d: &DefinitionSummary) -> String {
+    if d.name.is_empty() {
+        d.key.to_string()
+    } else {
+        format!("{} {}", d.key, d.name)
+    }
+}
+
+fn format_value(value: &str) -> String {
+    if value.is_empty() {
+        "(none)".to_string()
+    } else {
+        truncate_ascii_string(value, MAX_VALUE_LENGTH)
+    }
+}
+
+fn print_table_counts(t: &TableDiff) {
+    println!(
+        "{:<50}{:>10}{:>10}{:>10}",
+        t.table_name,
+        t.added.len().to_string(),
+        t.removed.len().to_string(),
+        t.changed.len().to_string(),
+    );
+}
+
+fn print_counts_header() {
+    let header = format!(
+        "{:<50}{:>10}{:>10}{:>10}",
+        "TABLE", "ADDED", "REMOVED", "CHANGED"
+    );
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+}
+
+fn print_definitions(
+    label: &str,
+    definitions: &[DefinitionSummary],
+    limit: usize,
+) {
+    if definitions.is_empty() {
+        return;
+    }
+
+    println!("  {}", label);
+    for d in definitions.iter().take(limit) {
+        println!("    {}", format_summary(d));
+    }
+
+    if definitions.len() > limit {
+        println!("    ... and {} more", definitions.len() - limit);
+    }
+}
+
+/// Prints the number of added, removed and changed definitions in each table
+pub fn print_diff_summary(diff: &ManifestDiff) {
+    if diff.is_empty() {
+        println!("No definitions were added, removed or changed.");
+        return;
+    }
+
+    print_counts_header();
+    for t in &diff.tables {
+        print_table_counts(t);
+    }
+}
+
+/// Prints the counts for each table, followed by the added, removed and
+/// changed definitions (up to limit for each) and their changed fields.
+pub fn print_diff(diff: &ManifestDiff, limit: usize) {
+    print_diff_summary(diff);
+
+    for t in &diff.tables {
+        println!();
+        println!("{}", t.table_name);
+        println!("{}", repeat_str("-", t.table_name.chars().count()));
+
+        print_definitions("ADDED", &t.added, limit);
+        print_definitions("REMOVED", &t.removed, limit);
+
+        if t.changed.is_empty() {
+            continue;
+        }
+
+        println!("  CHANGED");
+        for c in t.changed.iter().take(limit) {
+            println!("    {}", format_summary(&c.definition));
+
+            for f in c.fields.iter().take(MAX_FIELD_COUNT) {
+                println!(
+                    "      {} : {} -> {}",
+                    f.path,
+                    format_value(&f.old_value),
+                    format_value(&f.new_value),
+                );
+            }
+
+            if c.fields.len() > MAX_FIELD_COUNT {
+                println!(
+                    "      ... and {} more fields",
+                    c.fields.len() - MAX_FIELD_COUNT
+                );
+            }
+        }
+
+        if t.changed.len() > limit {
+            println!("    ... and {} more", t.changed.len() - limit);
+        }
+    }
+}
+
+/// Prints a row for every added or removed definition, and every changed
+/// field, in the format:
+/// table, change, hash (or key), name, field, old value, new value
+pub fn print_diff_tsv(diff: &ManifestDiff) {
+    let print_row = |table: &str,
+                     change: &str,
+                     d: &DefinitionSummary,
+                     field: &str,
+                     old: &str,
+                     new: &str| {
+        print!(
+            "{t}{delim}{c}{delim}{h}{delim}{n}{delim}{f}{delim}{o}{delim}{nv}{eol}",
+            t = table,
+            c = change,
+            h = d.key,
+            n = d.name,
+            f = field,
+            o = old,
+            nv = new,
+            delim = TSV_DELIM,
+            eol = TSV_EOL,
+        );
+    };
+
+    for t in &diff.tables {
+        for d in &t.added {
+            print_row(&t.table_name, "added", d, "", "", "");
+        }
+
+        for d in &t.removed {
+            print_row(&t.table_name, "removed", d, "", "", "");
+        }
+
+        for c in &t.changed {
+            for f in &c.fields {
+                print_row(
+                    &t.table_name,
+                    "changed",
+                    &c.definition,
+                    &f.path,
+                    &f.old_value,
+                    &f.new_value,
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,264 @@
+/*
+* Copyright 2021 Mike Chambers
+* https://github.com/mikechambers/dcli
+*
+* Permission is hereby granted, free of charge, to any person obtaining a copy of
+* this software and associated documentation files (the "Software"), to deal in
+* the Software without restriction, including without limitation the rights to
+* use, copy, modify, merge, publish, distribute, sublicense, and/or sell copies
+* of the Software, and to permit persons to whom the Software is furnished to do
+* so, subject to the following conditions:
+*
+* The above copyright notice and this permission notice shall be included in all
+* copies or substantial portions of the Software.
+*
+* THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+* IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY, FITNESS
+* FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR
+* COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER
+* IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN
+* CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+*/
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use dcli::apiclient::ApiClient;
+use dcli::error::Error;
+use dcli::manifestinterface::validate_manifest;
+use dcli::utils::print_verbose;
+use reqwest::StatusCode;
+use tokio::io::AsyncWriteExt;
+
+//number of times we will try to resume an interrupted download before giving up
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
+
+const DOWNLOAD_EXTENSION: &str = ".zip.part";
+
+/// Returns the path the compressed manifest is downloaded to. The name is
+/// based on the manifest file name and the remote file name (which changes
+/// with each version), so that a partial download is only resumed for the
+/// same manifest version.
+fn get_download_path(url: &str, manifest_path: &Path) -> PathBuf {
+    let file_name = url.rsplit('/').next().unwrap_or_default();
+
+    //i.e. manifest.world_sql_content_1234.content.zip.part
+    manifest_path.with_extension(format!("{}{}", file_name, DOWNLOAD_EXTENSION))
+}
+
+/// Removes partial downloads of previous versions of the manifest, which will
+/// never be resumed.
+fn remove_stale_downloads(
+    manifest_path: &Path,
+    download_path: &Path,
+) -> Result<(), Error> {
+    let dir = match manifest_path.parent() {
+        Some(e) if !e.as_os_str().is_empty() => e,
+        _ => Path::new("."),
+    };
+
+    let prefix = match manifest_path.file_stem().and_then(|e| e.to_str()) {
+        Some(e) => format!("{}.", e),
+        None => return Ok(()),
+    };
+
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .and_then(|e| e.to_str())
+            .unwrap_or_default();
+
+        if name.starts_with(&prefix)
+            && name.ends_with(DOWNLOAD_EXTENSION)
+            && path.file_name() != download_path.file_name()
+        {
+            fs::remove_file(&path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Returns true if the error occured while connecting, or while reading or
+/// writing the download, in which case the download can be resumed. Other
+/// errors (such as the file not being found) will not be fixed by retrying.
+fn is_resumable(e: &Error) -> bool {
+    matches!(
+        e,
+        Error::Request | Error::RequestTimedOut | Error::IoError { .. }
+    )
+}
+
+fn print_progress(downloaded: u64, total: Option<u64>) {
+    const MB: f64 = 1024.0 * 1024.0;
+
+    match total {
+        Some(total) if total > 0 => eprint!(
+            "\r{:.1} MB / {:.1} MB ({:.0}%)",
+            downloaded as f64 / MB,
+            total as f64 / MB,
+            (downloaded as f64 / total as f64) * 100.0
+        ),
+        _ => eprint!("\r{:.1} MB", downloaded as f64 / MB),
+    }
+}
+
+/// Downloads the compressed manifest to the specified path, resuming from any
+/// data which has already been downloaded.
+async fn download_file(
+    client: &ApiClient,
+    url: &str,
+    path: &Path,
+    show_progress: bool,
+) -> Result<(), Error> {
+    let offset = match fs::metadata(path) {
+        Ok(e) => e.len(),
+        Err(_) => 0,
+    };
+
+    let mut response = client.download(url, offset).await?;
+
+    let mut file = match response.status() {
+        //server is sending the rest of the file
+        StatusCode::PARTIAL_CONTENT => {
+            tokio::fs::OpenOptions::new()
+                .append(true)
+                .open(path)
+                .await?
+        }
+        //server is sending the entire file (new download, or range not supported)
+        StatusCode::OK => tokio::fs::File::create(path).await?,
+        //we already have the entire file
+        StatusCode::RANGE_NOT_SATISFIABLE if offset > 0 => return Ok(()),
+        status => {
+            return Err(Error::ApiRequest {
+                description: format!(
+                    "Unexpected status downloading manifest : {}",
+                    status
+                ),
+            });
+        }
+    };
+
+    let mut downloaded = if response.status() == StatusCode::PARTIAL_CONTENT {
+        offset
+    } else {
+        0
+    };
+
+    let total = response.content_length().map(|e| e + downloaded);
+    let mut last_percent = None;
+
+    //errors reading the response body (i.e. the connection dropping) are io
+    //errors, so the download is resumed
+    while let Some(chunk) =
+        response.chunk().await.map_err(|e| Error::IoError {
+            description: format!("Error reading manifest download : {}", e),
+        })?
+    {
+        file.write_all(&chunk).await?;
+        downloaded += chunk.len() as u64;
+
+        if show_progress {
+            //only update the display when the percent changes
+            let percent = total.map(|t| downloaded * 100 / t.max(1));
+            if percent.is_none() || percent != last_percent {
+                print_progress(downloaded, total);
+                last_percent = percent;
+            }
+        }
+    }
+
+    file.flush().await?;
+
+    if show_progress {
+        eprintln!();
+    }
+
+    if let Some(total) = total {
+        if downloaded < total {
+            return Err(Error::IoError {
+                description: format!(
+                    "Download incomplete. Received {} of {} bytes.",
+                    downloaded, total
+                ),
+            });
+        }
+    }
+
+    Ok(())
+}
+
+/// Downloads and decompresses the manifest to a temporary file next to the
+/// manifest, and verifies that it is a valid manifest database. Interrupted
+/// downloads are resumed, both within the call, and the next time it is called
+/// for the same url. Returns the path to the verified manifest, which can then
+/// be moved into place.
+pub async fn download_manifest(
+    url: &str,
+    manifest_path: &Path,
+    verbose: bool,
+    show_progress: bool,
+) -> Result<PathBuf, Error> {
+    let client: ApiClient = ApiClient::new(verbose)?;
+    let download_path = get_download_path(url, manifest_path);
+
+    if let Err(e) = remove_stale_downloads(manifest_path, &download_path) {
+        print_verbose(
+            &format!("Could not remove previous downloads : {}", e),
+            verbose,
+        );
+    }
+
+    let mut attempt = 1;
+    loop {
+        match download_file(&client, url, &download_path, show_progress).await {
+            Ok(_) => break,
+            Err(e) => {
+                if show_progress {
+                    eprintln!();
+                }
+
+                if attempt >= MAX_DOWNLOAD_ATTEMPTS || !is_resumable(&e) {
+                    return Err(e);
+                }
+
+                print_verbose(&format!("{}", e), verbose);
+                eprintln!("Download interrupted. Resuming.");
+                attempt += 1;
+            }
+        }
+    }
+
+    let tmp_path = manifest_path.with_extension("download.tmp");
+    let result = decompress_manifest(&download_path, &tmp_path);
+
+    //if the download cant be decompressed, it is likely corrupt, so we remove it
+    //so the next attempt starts again from scratch
+    let _ = fs::remove_file(&download_path);
+    result?;
+
+    if let Err(e) = validate_manifest(&tmp_path).await {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(e);
+    }
+
+    Ok(tmp_path)
+}
+
+fn decompress_manifest(zip_path: &Path, out_path: &Path) -> Result<(), Error> {
+    let mut zip = zip::ZipArchive::new(fs::File::open(zip_path)?)?;
+
+    //get a reference to the first file in the zip (there should only be one)
+    let mut manifest = zip.by_index(0)?;
+
+    //reference to file we are going to write the ucompressed manifest to
+    let mut outfile = fs::File::create(out_path)?;
+
+    //save the uncompressed / unzipped manifest to the file system
+    std::io::copy(&mut manifest, &mut outfile)?;
+    outfile.sync_all()?;
+
+    Ok(())
+}
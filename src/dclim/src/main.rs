@@ -20,25 +20,44 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+mod diff;
+mod download;
 mod manifest_info;
 
 use std::fs;
-use std::path::PathBuf;
+use std::io::Read;
+use std::path::{Path, PathBuf};
 
 use dcli::apiclient::ApiClient;
+use dcli::enums::language::Language;
 use dcli::error::Error;
-use dcli::manifestinterface::MANIFEST_FILE_NAME;
+use dcli::imagecache::ImageCache;
+use dcli::manifestdiff::ManifestDiff;
+use dcli::manifestexport;
+use dcli::manifestinterface::{
+    get_manifest_file_name, validate_manifest, ManifestInterface,
+};
+use dcli::manifestsearch::{get_search_index_file_name, ManifestSearchIndex};
 use dcli::output::Output;
 use dcli::response::manifest::ManifestResponse;
 use dcli::utils::EXIT_FAILURE;
 use dcli::utils::{build_tsv, determine_data_dir, print_error, print_verbose};
 use manifest_info::ManifestInfo;
 use structopt::StructOpt;
-use tokio::io::AsyncWriteExt;
 
 pub const MANIFEST_INFO_FILE_NAME: &str = "manifest_info.json";
 
+/// Returns the file name for the manifest info for the specified language.
+/// Info for non English manifests includes the language code.
+fn get_manifest_info_file_name(language: &Language) -> String {
+    match language {
+        Language::English => MANIFEST_INFO_FILE_NAME.to_string(),
+        _ => format!("manifest_info_{}.json", language.to_code()),
+    }
+}
+
 async fn retrieve_manifest_info(
+    language: &Language,
     print_url: bool,
 ) -> Result<ManifestInfo, Error> {
     let client: ApiClient = ApiClient::new(print_url)?;
@@ -51,7 +70,8 @@ async fn retrieve_manifest_info(
         None => return Err(Error::ApiResponseMissing), //we should never get here as this will be caught earlier
     };
 
-    let m_info: ManifestInfo = ManifestInfo::from_manifest(&manifest);
+    let m_info: ManifestInfo =
+        ManifestInfo::from_manifest(&manifest, language)?;
 
     Ok(m_info)
 }
@@ -76,43 +96,296 @@ fn load_manifest_info(path: &PathBuf) -> Result<ManifestInfo, Error> {
     Ok(m)
 }
 
-//should this move to ApiClient?
-async fn download_manifest(
-    url: &str,
-    path: &PathBuf,
-    print_url: bool,
+/// Returns the path the previous version of the manifest is kept at when a new
+/// version is downloaded (i.e. manifest.previous.sqlite3)
+fn get_previous_manifest_path(manifest_path: &Path) -> PathBuf {
+    manifest_path.with_extension("previous.sqlite3")
+}
+
+/// Returns the path the info for the previous version of the manifest is kept
+/// at (i.e. manifest_info.previous.json)
+fn get_previous_manifest_info_path(manifest_info_path: &Path) -> PathBuf {
+    manifest_info_path.with_extension("previous.json")
+}
+
+/// Moves a new, verified manifest into place, keeping the current manifest
+/// (if it exists) and its info at the previous paths so it can be compared or
+/// rolled back. The current manifest is linked (or copied) rather than moved,
+/// and the new manifest is then renamed over it, so there is always a valid
+/// manifest in place, even if the process is interrupted. Returns whether
+/// there was a manifest to keep.
+fn install_manifest(
+    new_path: &Path,
+    manifest_path: &Path,
+    manifest_info_path: &Path,
+) -> Result<bool, Error> {
+    let has_previous = manifest_path.exists();
+
+    if has_previous {
+        let previous_path = get_previous_manifest_path(manifest_path);
+        if previous_path.exists() {
+            fs::remove_file(&previous_path)?;
+        }
+
+        //hard links are not supported on all file systems
+        if fs::hard_link(manifest_path, &previous_path).is_err() {
+            fs::copy(manifest_path, &previous_path)?;
+        }
+
+        if manifest_info_path.exists() {
+            fs::copy(
+                manifest_info_path,
+                get_previous_manifest_info_path(manifest_info_path),
+            )?;
+        }
+    }
+
+    fs::rename(new_path, manifest_path)?;
+
+    Ok(has_previous)
+}
+
+/// Restores the previous version of the manifest, and its info. Returns an
+/// error if there is no previous version.
+fn rollback_manifest(
+    manifest_path: &Path,
+    manifest_info_path: &Path,
 ) -> Result<(), Error> {
-    let client: ApiClient = ApiClient::new(print_url)?;
+    let previous_path = get_previous_manifest_path(manifest_path);
+    if !previous_path.exists() {
+        return Err(Error::IoFileDoesNotExist {
+            description: format!(
+                "No previous manifest found at {}",
+                previous_path.display()
+            ),
+        });
+    }
+
+    fs::rename(&previous_path, manifest_path)?;
+
+    let previous_info_path =
+        get_previous_manifest_info_path(manifest_info_path);
+    if previous_info_path.exists() {
+        fs::rename(&previous_info_path, manifest_info_path)?;
+    }
+
+    Ok(())
+}
+
+//copies the manifest being imported to tmp_path (extracting it if it is a
+//zip file) and validates it. Returns the file name of the manifest.
+async fn extract_import(
+    import_path: &Path,
+    tmp_path: &Path,
+) -> Result<String, Error> {
+    //zip files start with PK\x03\x04
+    let mut signature = [0; 4];
+    let is_zip = {
+        let mut f = fs::File::open(import_path)?;
+        f.read_exact(&mut signature).is_ok() && signature == *b"PK\x03\x04"
+    };
+
+    let file_name = if is_zip {
+        let mut zip = zip::ZipArchive::new(fs::File::open(import_path)?)?;
+
+        //get a reference to the first file in the zip (there should only be one)
+        let mut manifest = zip.by_index(0)?;
+        let name = manifest.name().to_string();
+
+        let mut outfile = fs::File::create(tmp_path)?;
+        std::io::copy(&mut manifest, &mut outfile)?;
+
+        name
+    } else {
+        fs::copy(import_path, tmp_path)?;
+
+        import_path
+            .file_name()
+            .map(|e| e.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+
+    validate_manifest(tmp_path).await?;
+
+    Ok(file_name)
+}
+
+/// Imports a manifest from a local file, which can either be the uncompressed
+/// sqlite3 .content file, or the zip file downloaded from Bungie. The file is
+/// validated before replacing the current manifest. Returns the info for the
+/// imported manifest, and whether the previous manifest was kept.
+async fn import_manifest(
+    import_path: &Path,
+    manifest_path: &Path,
+    manifest_info_path: &Path,
+    language: &Language,
+) -> Result<(ManifestInfo, bool), Error> {
+    let tmp_path = manifest_path.with_extension("import.tmp");
+
+    //make sure we dont leave a partial or invalid file in the data dir if the
+    //import fails
+    let file_name = match extract_import(import_path, &tmp_path).await {
+        Ok(e) => e,
+        Err(e) => {
+            let _ = fs::remove_file(&tmp_path);
+            return Err(e);
+        }
+    };
+
+    let has_previous =
+        match install_manifest(&tmp_path, manifest_path, manifest_info_path) {
+            Ok(e) => e,
+            Err(e) => {
+                let _ = fs::remove_file(&tmp_path);
+                return Err(e);
+            }
+        };
+
+    Ok((
+        ManifestInfo::from_import(&file_name, language),
+        has_previous,
+    ))
+}
+
+async fn print_previous_diff(previous_path: &Path, manifest_path: &Path) {
+    match ManifestDiff::with_manifests(previous_path, manifest_path).await {
+        Ok(e) => {
+            println!();
+            println!("Changes from previous manifest");
+            diff::print_diff_summary(&e);
+            if !e.is_empty() {
+                println!();
+                println!(
+                    "Run dclim --diff {} {} to view details",
+                    previous_path.display(),
+                    manifest_path.display()
+                );
+            }
+            println!();
+        }
+        Err(e) => {
+            eprintln!("Could not compare to previous manifest : {}", e);
+        }
+    }
+}
+
+async fn update_search_index(
+    data_dir: &PathBuf,
+    language: &Language,
+    verbose: bool,
+) {
+    eprintln!("Building manifest search index.");
+    match build_search_index(data_dir, language).await {
+        Ok(e) => {
+            print_verbose(
+                &format!("Search index built with {} entries.", e),
+                verbose,
+            );
+        }
+        Err(e) => {
+            //search index is only used by dclims, so we dont fail
+            eprintln!("Could not build manifest search index : {}", e);
+        }
+    }
+}
+
+/// Creates the convenience views in the manifest, and exports the manifest
+/// tables to json if they were requested.
+async fn export_manifest(
+    manifest_path: &Path,
+    export_dir: &Option<PathBuf>,
+    create_views: bool,
+    verbose: bool,
+) -> Result<(), Error> {
+    if create_views {
+        let views = manifestexport::create_views(manifest_path).await?;
+        eprintln!("Created manifest views : {}", views.join(", "));
+    }
 
-    //Download the manifest
-    let mut response = client.call(url).await?;
+    if let Some(dir) = export_dir {
+        eprintln!("Exporting manifest tables to {}", dir.display());
+        let tables = manifestexport::export_json(manifest_path, dir).await?;
 
-    //create a Vector to store the bytes for the download
-    let mut out: Vec<u8> = Vec::new();
+        for t in tables.iter() {
+            print_verbose(
+                &format!("{} : {} definitions", t.path.display(), t.count),
+                verbose,
+            );
+        }
 
-    //read of the bytes into the vector
-    while let Some(chunk) = response.chunk().await? {
-        out.write_all(&chunk).await?;
+        eprintln!("Exported {} tables.", tables.len());
     }
 
-    //create a cursor so we can move through the bytes
-    let cursor = std::io::Cursor::new(out);
+    Ok(())
+}
+
+/// Checks the images in the local image cache, and removes any which are
+/// missing or corrupt.
+fn verify_images(data_dir: &Path, verbose: bool) -> Result<(), Error> {
+    let mut cache = ImageCache::new(data_dir, verbose)?;
+    let removed = cache.verify()?;
 
-    //create a new zip archive from the bytes (since the download is compressed)
-    let mut zip = zip::ZipArchive::new(cursor)?;
+    eprintln!("{} missing or corrupt images removed from cache.", removed);
 
-    //get a reference to the first file in the zip (there should only be one)
-    let mut manifest = zip.by_index(0)?;
+    Ok(())
+}
 
-    //reference to file we are going to write the ucompressed manifest to
-    let mut outfile = fs::File::create(&path)?;
+/// Downloads the medal icons, activity PGCR images and emblem images
+/// referenced by the manifest into the local image cache.
+async fn cache_images(
+    data_dir: &PathBuf,
+    language: &Language,
+    base_url: Option<&str>,
+    verbose: bool,
+) -> Result<(), Error> {
+    let mut manifest =
+        ManifestInterface::new(data_dir, language, false).await?;
+
+    let mut images = manifest
+        .get_image_paths("DestinyHistoricalStatsDefinition", "$.iconImage")
+        .await?;
+    images.append(
+        &mut manifest
+            .get_image_paths("DestinyActivityDefinition", "$.pgcrImage")
+            .await?,
+    );
+    images.append(&mut manifest.get_emblem_image_paths().await?);
+    manifest.close().await?;
+
+    let mut cache = match base_url {
+        Some(e) => ImageCache::with_base_url(data_dir, e, verbose)?,
+        None => ImageCache::new(data_dir, verbose)?,
+    };
 
-    //save the uncompressed / unzipped manifest to the file system
-    std::io::copy(&mut manifest, &mut outfile)?;
+    eprintln!("Caching {} images.", images.len());
+    let results = cache.retrieve_all(&images).await?;
+
+    eprintln!(
+        "{} images downloaded, {} already cached, {} failed.",
+        results.retrieved,
+        results.cached,
+        results.failed.len()
+    );
+
+    for (image, e) in results.failed.iter() {
+        print_verbose(&format!("{} : {}", image, e), verbose);
+    }
 
     Ok(())
 }
 
+async fn build_search_index(
+    data_dir: &PathBuf,
+    language: &Language,
+) -> Result<u32, Error> {
+    let mut manifest =
+        ManifestInterface::new(data_dir, language, false).await?;
+    let count =
+        ManifestSearchIndex::build(&mut manifest, data_dir, language).await?;
+
+    Ok(count)
+}
+
 #[derive(StructOpt, Debug)]
 #[structopt(verbatim_doc_comment)]
 /// Command line tool for retrieving and managing the Destiny 2 manifest database.
@@ -121,6 +394,17 @@ async fn download_manifest(
 /// manifest.sqlite3, along with meta-data with information about the downloaded
 /// version. This is used to to determine whether the remote version has been updated.
 ///
+/// Manifests for languages other than English are stored side by side, with the
+/// language code included in the file name (i.e. manifest_de.sqlite3).
+///
+/// When a new version is downloaded, the previous version is kept (i.e.
+/// manifest.previous.sqlite3), and a summary of the definitions which were
+/// added, removed or changed is displayed. Use --diff to view the details, and
+/// --rollback to restore the previous version.
+///
+/// Downloads are written to a temporary file, and are resumed if interrupted.
+/// The new manifest is verified before it replaces the current version.
+///
 /// Created by Mike Chambers.
 /// https://www.mikechambers.com
 ///
@@ -154,6 +438,115 @@ struct Opt {
     #[structopt(short = "K", long = "check")]
     check: bool,
 
+    /// Compare two manifest database files, and display the definitions which
+    /// were added, removed or changed
+    ///
+    /// Takes the path to the old and new manifests. For example:
+    /// --diff manifest.previous.sqlite3 manifest.sqlite3
+    ///
+    /// No manifest will be downloaded.
+    #[structopt(
+        long = "diff",
+        number_of_values = 2,
+        value_names = &["old", "new"],
+        parse(from_os_str),
+        conflicts_with_all = &["check", "force"]
+    )]
+    diff: Vec<PathBuf>,
+
+    /// Maximum number of definitions displayed per table and change type when
+    /// displaying a diff
+    #[structopt(long = "diff-limit", default_value = "25")]
+    diff_limit: usize,
+
+    /// Import the manifest from a local file instead of downloading it
+    ///
+    /// The file can either be the uncompressed manifest sqlite3 database
+    /// (.content file) or the zip file downloaded from Bungie. The file is
+    /// validated before it replaces the current manifest. This is useful for
+    /// seeding machines without network access, or CI jobs, with a known
+    /// manifest.
+    #[structopt(
+        long = "import",
+        parse(from_os_str),
+        conflicts_with_all = &["check", "force", "diff"]
+    )]
+    import: Option<PathBuf>,
+
+    /// Restore the previous version of the manifest
+    ///
+    /// The previous version is kept whenever a new manifest is downloaded or
+    /// imported. Note that running dclim again will download the current
+    /// remote version.
+    #[structopt(
+        long = "rollback",
+        conflicts_with_all = &["check", "force", "diff", "import"]
+    )]
+    rollback: bool,
+
+    /// Download the medal icons, activity PGCR images and emblem images
+    /// referenced by the manifest into a local image cache
+    ///
+    /// Images are stored in the images directory in the data directory, so
+    /// they can be used without a network connection. Images which have
+    /// already been cached are not downloaded again.
+    #[structopt(
+        long = "cache-images",
+        conflicts_with_all = &["check", "diff", "import", "rollback"]
+    )]
+    cache_images: bool,
+
+    /// Check the images in the local image cache, and remove any which are
+    /// missing or corrupt
+    ///
+    /// When used with --cache-images, the removed images are downloaded again.
+    #[structopt(
+        long = "verify-images",
+        conflicts_with_all = &["check", "diff", "import", "rollback"]
+    )]
+    verify_images: bool,
+
+    /// Base url images are downloaded from when caching images
+    ///
+    /// Defaults to https://www.bungie.net
+    #[structopt(long = "image-base-url")]
+    image_base_url: Option<String>,
+
+    /// Export each manifest table to a pretty printed json file in the
+    /// specified directory
+    ///
+    /// Files are named after the table (i.e. DestinyInventoryItemDefinition.json)
+    /// and contain an object with the definitions keyed by their hash. Export
+    /// runs after the manifest has been updated.
+    #[structopt(
+        long = "export-json",
+        parse(from_os_str),
+        conflicts_with_all = &["check", "diff", "rollback"]
+    )]
+    export_json: Option<PathBuf>,
+
+    /// Create convenience SQL views in the manifest database
+    ///
+    /// Creates weapons (hash, name, type, sub_type, tier) and activities (hash,
+    /// name, mode, place) views, which can be used to join data in the activity
+    /// store against readable names. Views are created after the manifest has
+    /// been updated, and need to be recreated each time a new manifest is
+    /// downloaded.
+    #[structopt(
+        long = "create-views",
+        conflicts_with_all = &["check", "diff", "rollback"]
+    )]
+    create_views: bool,
+
+    /// Language of the manifest to download
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. Manifests for each language are stored side by
+    /// side, so multiple languages can be downloaded by running dclim for each
+    /// language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
     /// Format for command output
     ///
     /// Valid values are default (Default) and tsv.
@@ -172,6 +565,36 @@ async fn main() {
     let opt = Opt::from_args();
     print_verbose(&format!("{:#?}", opt), opt.verbose);
 
+    if !opt.diff.is_empty() {
+        let diff = match ManifestDiff::with_manifests(
+            &opt.diff[0],
+            &opt.diff[1],
+        )
+        .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                print_error("Could not compare manifests.", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+        match opt.output {
+            Output::Default => {
+                println!(
+                    "Comparing {} to {}",
+                    opt.diff[0].display(),
+                    opt.diff[1].display()
+                );
+                println!();
+                diff::print_diff(&diff, opt.diff_limit);
+            }
+            Output::Tsv => diff::print_diff_tsv(&diff),
+        }
+
+        return;
+    }
+
     let data_dir = match determine_data_dir(opt.data_dir) {
         Ok(e) => e,
         Err(e) => {
@@ -180,19 +603,117 @@ async fn main() {
         }
     };
 
-    let m_path = data_dir.join(MANIFEST_FILE_NAME);
-    let m_info_path = data_dir.join(MANIFEST_INFO_FILE_NAME);
+    let m_path = data_dir.join(get_manifest_file_name(&opt.language));
+    let m_info_path = data_dir.join(get_manifest_info_file_name(&opt.language));
 
-    let remote_manifest_info = match retrieve_manifest_info(opt.verbose).await {
-        Ok(e) => e,
-        Err(e) => {
-            print_error("Could not retrieve manifest info from Bungie.", e);
+    if opt.rollback {
+        if let Err(e) = rollback_manifest(&m_path, &m_info_path) {
+            print_error("Could not roll back manifest.", e);
             std::process::exit(EXIT_FAILURE);
         }
-    };
+
+        update_search_index(&data_dir, &opt.language, opt.verbose).await;
+
+        match opt.output {
+            Output::Default => {
+                println!("Manifest rolled back to previous version.");
+                println!("{}", m_path.display());
+            }
+            Output::Tsv => {
+                let mut name_values: Vec<(&str, String)> = Vec::new();
+                name_values
+                    .push(("local_path", format!("{}", m_path.display())));
+                name_values.push(("updated", format!("{}", true)));
+                name_values
+                    .push(("language", opt.language.to_code().to_string()));
+
+                print!("{}", build_tsv(name_values));
+            }
+        }
+
+        return;
+    }
+
+    if let Some(import_path) = &opt.import {
+        let previous_path = get_previous_manifest_path(&m_path);
+
+        let (manifest_info, has_previous) = match import_manifest(
+            import_path,
+            &m_path,
+            &m_info_path,
+            &opt.language,
+        )
+        .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                print_error("Could not import manifest.", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+        if let Err(e) = save_manifest_info(&manifest_info, &m_info_path) {
+            print_error("Could not save manifest info.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+
+        if has_previous && opt.output == Output::Default {
+            print_previous_diff(&previous_path, &m_path).await;
+        }
+
+        update_search_index(&data_dir, &opt.language, opt.verbose).await;
+
+        if let Err(e) = export_manifest(
+            &m_path,
+            &opt.export_json,
+            opt.create_views,
+            opt.verbose,
+        )
+        .await
+        {
+            print_error("Could not export manifest.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+
+        match opt.output {
+            Output::Default => {
+                println!("Manifest imported.");
+                println!("{}", m_path.display());
+            }
+            Output::Tsv => {
+                let mut name_values: Vec<(&str, String)> = Vec::new();
+                name_values
+                    .push(("local_path", format!("{}", m_path.display())));
+                name_values.push(("updated", format!("{}", true)));
+                name_values.push(("version", manifest_info.version));
+                name_values.push(("url", manifest_info.url));
+                name_values
+                    .push(("language", opt.language.to_code().to_string()));
+
+                print!("{}", build_tsv(name_values));
+            }
+        }
+
+        return;
+    }
+
+    let remote_manifest_info =
+        match retrieve_manifest_info(&opt.language, opt.verbose).await {
+            Ok(e) => e,
+            Err(e) => {
+                print_error("Could not retrieve manifest info from Bungie.", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
 
     let col_w = 30;
     if opt.output == Output::Default {
+        println!(
+            "{:<0col_w$}{}",
+            "Manifest language",
+            opt.language,
+            col_w = col_w
+        );
         println!(
             "{:<0col_w$}{}",
             "Remote Manifest version",
@@ -266,6 +787,8 @@ async fn main() {
                 name_values.push(("updated", format!("{}", false)));
                 name_values.push(("version", remote_manifest_info.version));
                 name_values.push(("url", remote_manifest_info.url));
+                name_values
+                    .push(("language", opt.language.to_code().to_string()));
 
                 print!("{}", build_tsv(name_values));
             }
@@ -276,8 +799,15 @@ async fn main() {
     if opt.force || manifest_needs_updating {
         //print to stderr so user can redirect other output (such as tsv) to stdout
         eprintln!("Downloading manifest. This may take a bit of time.");
-        match download_manifest(&remote_manifest_info.url, &m_path, opt.verbose)
-            .await
+
+        //download and verify the new manifest before touching the current one
+        let tmp_path = match download::download_manifest(
+            &remote_manifest_info.url,
+            &m_path,
+            opt.verbose,
+            opt.output == Output::Default,
+        )
+        .await
         {
             Ok(e) => e,
             Err(e) => {
@@ -286,6 +816,17 @@ async fn main() {
             }
         };
 
+        let previous_path = get_previous_manifest_path(&m_path);
+        let has_previous =
+            match install_manifest(&tmp_path, &m_path, &m_info_path) {
+                Ok(e) => e,
+                Err(e) => {
+                    let _ = fs::remove_file(&tmp_path);
+                    print_error("Could not save manifest.", e);
+                    std::process::exit(EXIT_FAILURE);
+                }
+            };
+
         print_verbose("Download and save complete.", opt.verbose);
         print_verbose("Saving manifest info.", opt.verbose);
 
@@ -300,10 +841,54 @@ async fn main() {
         if opt.output == Output::Default {
             println!("Manifest info saved.");
         }
+
+        if has_previous && opt.output == Output::Default {
+            print_previous_diff(&previous_path, &m_path).await;
+        }
     } else if opt.output == Output::Default {
         println!("No new manifest available");
     }
 
+    //the search index is rebuilt whenever a new manifest is downloaded, or
+    //if it doesnt exist (i.e. manifest downloaded with an older version)
+    let index_path = data_dir.join(get_search_index_file_name(&opt.language));
+    if opt.force || manifest_needs_updating || !index_path.exists() {
+        update_search_index(&data_dir, &opt.language, opt.verbose).await;
+    }
+
+    if let Err(e) = export_manifest(
+        &m_path,
+        &opt.export_json,
+        opt.create_views,
+        opt.verbose,
+    )
+    .await
+    {
+        print_error("Could not export manifest.", e);
+        std::process::exit(EXIT_FAILURE);
+    }
+
+    if opt.verify_images {
+        if let Err(e) = verify_images(&data_dir, opt.verbose) {
+            print_error("Could not verify images.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    }
+
+    if opt.cache_images {
+        if let Err(e) = cache_images(
+            &data_dir,
+            &opt.language,
+            opt.image_base_url.as_deref(),
+            opt.verbose,
+        )
+        .await
+        {
+            print_error("Could not cache images.", e);
+            std::process::exit(EXIT_FAILURE);
+        }
+    }
+
     match opt.output {
         Output::Default => {
             println!("{}", m_path.display());
@@ -315,6 +900,7 @@ async fn main() {
                 .push(("updated", format!("{}", manifest_needs_updating)));
             name_values.push(("version", remote_manifest_info.version));
             name_values.push(("url", remote_manifest_info.url));
+            name_values.push(("language", opt.language.to_code().to_string()));
 
             print!("{}", build_tsv(name_values));
         }
@@ -20,6 +20,8 @@
 * CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 */
 
+use dcli::apiutils::RESOURCE_BASE_URL;
+use dcli::enums::language::Language;
 use dcli::error::Error;
 use dcli::response::manifest::ManifestData;
 use serde_derive::{Deserialize, Serialize};
@@ -31,10 +33,33 @@ pub struct ManifestInfo {
 }
 
 impl ManifestInfo {
-    pub fn from_manifest(manifest: &ManifestData) -> ManifestInfo {
-        ManifestInfo {
+    pub fn from_manifest(
+        manifest: &ManifestData,
+        language: &Language,
+    ) -> Result<ManifestInfo, Error> {
+        let url = manifest
+            .mobile_world_content_paths
+            .get_url(language)
+            .ok_or(Error::ManifestLanguageNotAvailable)?;
+
+        Ok(ManifestInfo {
             version: String::from(&manifest.version),
-            url: String::from(&manifest.mobile_world_content_paths.en),
+            url,
+        })
+    }
+
+    /// Creates info for a manifest imported from a local file. The url is built
+    /// from the name of the manifest file, so that it matches the remote url
+    /// if the imported file is the current remote version.
+    pub fn from_import(file_name: &str, language: &Language) -> ManifestInfo {
+        ManifestInfo {
+            version: "imported".to_string(),
+            url: format!(
+                "{}/common/destiny2_content/sqlite/{}/{}",
+                RESOURCE_BASE_URL,
+                language.to_code(),
+                file_name
+            ),
         }
     }
 
@@ -22,22 +22,28 @@
 
 use std::path::PathBuf;
 
+use dcli::enums::language::Language;
 use dcli::error::Error;
 use dcli::manifestinterface::{FindResult, ManifestInterface};
+use dcli::manifestsearch::{ManifestSearchIndex, SearchResults};
 use dcli::output::Output;
 use dcli::utils::{
-    determine_data_dir, print_error, print_verbose, EXIT_FAILURE, TSV_DELIM,
-    TSV_EOL,
+    determine_data_dir, normalize_name, print_error, print_verbose, repeat_str,
+    EXIT_FAILURE, TSV_DELIM, TSV_EOL,
 };
+use structopt::clap::ArgGroup;
 use structopt::StructOpt;
 
 #[derive(StructOpt, Debug)]
-#[structopt(verbatim_doc_comment)]
-/// Command line tool for searching the Destiny 2 manifest by hash ids.
+#[structopt(verbatim_doc_comment, group = ArgGroup::with_name("search").required(true))]
+/// Command line tool for searching the Destiny 2 manifest by hash ids or names.
 ///
 /// Takes a hash / id from the Destiny 2 API, and returns data from the
 /// item from the manifest. May return more than one result.
 ///
+/// Alternatively, takes a name and returns the hashes of matching items, using
+/// a search index generated by dclim when the manifest is downloaded.
+///
 /// Created by Mike Chambers.
 /// https://www.mikechambers.com
 ///
@@ -49,6 +55,14 @@ use structopt::StructOpt;
 ///
 /// Released under an MIT License.
 struct Opt {
+    /// Language for names retrieved from the manifest
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
     /// Directory where Destiny 2 manifest database file is stored. (optional)
     ///
     /// This will normally be downloaded using the dclim tool, and stored in a file
@@ -60,8 +74,34 @@ struct Opt {
     ///The hash id from the Destiny 2 API for the item to be searched for.
     ///
     ///Example : 326060471
-    #[structopt(long = "hash", short = "h", required = true)]
-    hash: u32,
+    #[structopt(long = "hash", short = "h", group = "search")]
+    hash: Option<u32>,
+
+    /// Name of the item to search for
+    ///
+    /// Search is case insensitive, and matches exact names, names starting
+    /// with or containing the value, as well as similar names (to account for
+    /// typos). Results are ranked by how closely they match.
+    ///
+    /// Example : "Ace of Spades"
+    #[structopt(long = "name", short = "n", group = "search")]
+    name: Option<String>,
+
+    /// Only search the specified manifest table (can be specified multiple times)
+    ///
+    /// The Destiny prefix is optional, and table names are case insensitive.
+    /// For example, InventoryItemDefinition or DestinyActivityDefinition. Only
+    /// used with --name.
+    #[structopt(long = "table", short = "t")]
+    tables: Vec<String>,
+
+    /// Maximum number of name search results to display per page
+    #[structopt(long = "limit", short = "l", default_value = "25")]
+    limit: usize,
+
+    /// Page of name search results to display, starting at 1
+    #[structopt(long = "page", short = "P", default_value = "1")]
+    page: usize,
 
     /// Format for command output
     ///
@@ -87,13 +127,29 @@ struct Opt {
 async fn search_manifest_by_hash(
     hash: u32,
     manifest_dir: PathBuf,
+    language: &Language,
 ) -> Result<Vec<FindResult>, Error> {
-    let mut manifest = ManifestInterface::new(&manifest_dir, false).await?;
+    let mut manifest =
+        ManifestInterface::new(&manifest_dir, language, false).await?;
     let out = manifest.find(hash).await?;
 
     Ok(out)
 }
 
+async fn search_manifest_by_name(
+    name: &str,
+    tables: &[String],
+    limit: usize,
+    offset: usize,
+    manifest_dir: PathBuf,
+    language: &Language,
+) -> Result<SearchResults, Error> {
+    let index = ManifestSearchIndex::new(&manifest_dir, language)?;
+    let out = index.search(name, tables, limit, offset).await?;
+
+    Ok(out)
+}
+
 #[tokio::main]
 async fn main() {
     let opt = Opt::from_args();
@@ -107,8 +163,51 @@ async fn main() {
         }
     };
 
+    if let Some(name) = opt.name {
+        if opt.page == 0 || opt.limit == 0 {
+            eprintln!("--page and --limit must be greater than 0");
+            std::process::exit(EXIT_FAILURE);
+        }
+
+        if normalize_name(&name).is_empty() {
+            eprintln!("--name must contain at least one letter or number");
+            std::process::exit(EXIT_FAILURE);
+        }
+
+        let results = match search_manifest_by_name(
+            &name,
+            &opt.tables,
+            opt.limit,
+            (opt.page - 1) * opt.limit,
+            data_dir,
+            &opt.language,
+        )
+        .await
+        {
+            Ok(e) => e,
+            Err(e) => {
+                print_error("Error searching manifest.", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        };
+
+        match opt.output {
+            Output::Default => {
+                print_search_default(&name, &results, opt.page, opt.limit);
+            }
+            Output::Tsv => {
+                print_search_tsv(&results);
+            }
+        };
+
+        return;
+    }
+
+    //hash is required if name is not specified
+    let hash = opt.hash.unwrap();
+
     let results: Vec<FindResult> =
-        match search_manifest_by_hash(opt.hash, data_dir).await {
+        match search_manifest_by_hash(hash, data_dir, &opt.language).await {
             Ok(e) => e,
             Err(e) => {
                 print_error("Error searching manifest.", e);
@@ -126,6 +225,87 @@ async fn main() {
     };
 }
 
+fn print_search_default(
+    name: &str,
+    results: &SearchResults,
+    page: usize,
+    limit: usize,
+) {
+    if results.results.is_empty() {
+        if results.total > 0 {
+            println!(
+                "No items found on page {} ({} total matches).",
+                page, results.total
+            );
+        } else {
+            println!("No items found.");
+        }
+        return;
+    }
+
+    let page_count = (results.total as f32 / limit as f32).ceil() as usize;
+
+    println!(
+        "Found {} item{} matching \"{}\" (page {} of {})",
+        results.total,
+        if results.total > 1 { "s" } else { "" },
+        name,
+        page,
+        page_count,
+    );
+    println!("-----------------------------");
+
+    let hash_col_w = 14;
+    let table_col_w = 38;
+    let name_col_w = 40;
+
+    let header = format!(
+        "{:<0hash_col_w$}{:<0table_col_w$}{:<0name_col_w$}{}",
+        "HASH",
+        "TABLE",
+        "NAME",
+        "MATCH",
+        hash_col_w = hash_col_w,
+        table_col_w = table_col_w,
+        name_col_w = name_col_w,
+    );
+
+    println!("{}", header);
+    println!("{}", repeat_str("=", header.chars().count()));
+
+    for r in results.results.iter() {
+        println!(
+            "{:<0hash_col_w$}{:<0table_col_w$}{:<0name_col_w$}{}",
+            r.hash.to_string(),
+            r.table_name,
+            r.name,
+            r.match_type,
+            hash_col_w = hash_col_w,
+            table_col_w = table_col_w,
+            name_col_w = name_col_w,
+        );
+    }
+
+    if page < page_count {
+        println!();
+        println!("Use --page {} to view more results", page + 1);
+    }
+}
+
+fn print_search_tsv(results: &SearchResults) {
+    for r in results.results.iter() {
+        print!(
+            "{h}{delim}{t}{delim}{n}{delim}{m}{eol}",
+            h = r.hash,
+            t = r.table_name,
+            n = r.name,
+            m = r.match_type,
+            delim = TSV_DELIM,
+            eol = TSV_EOL,
+        );
+    }
+}
+
 fn print_default(results: Vec<FindResult>) {
     if results.is_empty() {
         println!("No items found.");
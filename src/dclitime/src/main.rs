@@ -22,12 +22,18 @@
 
 mod datetimeformat;
 
+use std::path::PathBuf;
 use std::str::FromStr;
 
+use chrono::{DateTime, Utc};
 use datetimeformat::DateTimeFormat;
+use dcli::enums::language::Language;
 use dcli::enums::moment::Moment;
+use dcli::manifestinterface::ManifestInterface;
 use dcli::output::Output;
-use dcli::utils::{build_tsv, print_verbose};
+use dcli::seasons::{Season, Seasons};
+use dcli::utils::EXIT_FAILURE;
+use dcli::utils::{build_tsv, determine_data_dir, print_error, print_verbose};
 use structopt::StructOpt;
 
 //we do a custom parse / validation here so we can reuse Moment enum
@@ -52,6 +58,9 @@ fn parse_and_validate_moment(src: &str) -> Result<Moment, String> {
         Moment::Month => {}
         Moment::NextMonth => {}
         Moment::AllTime => {}
+        Moment::Season(_) => {}
+        Moment::CurrentSeason => {}
+        Moment::PreviousSeason => {}
         _ => {
             return Err(format!("Unsupported moment specified : {}", src));
         }
@@ -82,6 +91,11 @@ struct Opt {
     /// next_weekly (upcoming Tuesday weekly reset), current_daily, next_daily,
     /// current_xur (previous Friday Xur reset), next_xur (upcoming Friday Xur reset),
     /// current_trials (previous Friday Trials reset), next_trials (upcoming Friday Trials reset)
+    ///
+    /// Season moments current_season, previous_season and season:<name-or-number>
+    /// (i.e. season:13 or season:chosen) are also supported, and will print the
+    /// start and end of the season. Season moments are loaded from the manifest,
+    /// which must be downloaded with dclim.
     #[structopt(short = "T", parse(try_from_str = parse_and_validate_moment), long = "moment", default_value = "now")]
     moment: Moment,
 
@@ -110,6 +124,49 @@ struct Opt {
         default_value = "default"
     )]
     output: Output,
+
+    /// Language of the manifest used for season moments
+    ///
+    /// Valid values are en (default), fr, es, es-mx, de, it, ja, pt-br, ru, pl,
+    /// ko, zh-cht and zh-chs. The manifest for the language must be downloaded
+    /// with dclim --language.
+    #[structopt(long = "language", default_value = "en")]
+    language: Language,
+
+    /// Directory where Destiny 2 manifest is stored. (optional)
+    ///
+    /// Only used for season moments. This will normally be downloaded using the
+    /// dclim tool, and uses a system appropriate directory by default.
+    #[structopt(short = "D", long = "data-dir", parse(from_os_str))]
+    data_dir: Option<PathBuf>,
+}
+
+fn format_date_time(
+    dt: &DateTime<Utc>,
+    time_format: &DateTimeFormat,
+) -> String {
+    match time_format {
+        DateTimeFormat::RFC3339 => dt.to_rfc3339(),
+        DateTimeFormat::RFC2822 => dt.to_rfc2822(),
+        DateTimeFormat::Unix => dt.timestamp().to_string(),
+    }
+}
+
+async fn retrieve_season(
+    moment: &Moment,
+    data_dir: Option<PathBuf>,
+    language: &Language,
+) -> Result<Season, dcli::error::Error> {
+    let data_dir = determine_data_dir(data_dir)?;
+    let mut manifest =
+        ManifestInterface::new(&data_dir, language, false).await?;
+
+    let seasons = Seasons::with_manifest(&mut manifest).await?;
+    let season = seasons.get_season_for_moment(moment)?.clone();
+
+    manifest.close().await?;
+
+    Ok(season)
 }
 
 #[tokio::main]
@@ -117,23 +174,64 @@ async fn main() {
     let opt = Opt::from_args();
     print_verbose(&format!("{:#?}", opt), opt.verbose);
 
-    let dt = opt.moment.get_date_time();
-    let date_time_str = match opt.time_format {
-        DateTimeFormat::RFC3339 => dt.to_rfc3339(),
-        DateTimeFormat::RFC2822 => dt.to_rfc2822(),
-        DateTimeFormat::Unix => dt.timestamp().to_string(),
+    let season = if opt.moment.is_season() {
+        match retrieve_season(&opt.moment, opt.data_dir.clone(), &opt.language)
+            .await
+        {
+            Ok(e) => Some(e),
+            Err(e) => {
+                print_error("Could not load season from manifest.", e);
+                std::process::exit(EXIT_FAILURE);
+            }
+        }
+    } else {
+        None
     };
 
-    match opt.output {
-        Output::Default => {
-            println!("{}", date_time_str);
+    //season moments are the start of the season
+    let dt = match season.as_ref() {
+        Some(e) => Some(e.start),
+        None => opt.moment.get_date_time(),
+    };
+
+    let dt = match dt {
+        Some(e) => e,
+        None => {
+            eprintln!("Could not determine date / time for {}.", opt.moment);
+            std::process::exit(EXIT_FAILURE);
         }
+    };
+    let date_time_str = format_date_time(&dt, &opt.time_format);
+
+    //seasons without an end date are ongoing
+    let season_end_str = season
+        .as_ref()
+        .and_then(|e| e.end.as_ref())
+        .map(|e| format_date_time(e, &opt.time_format))
+        .unwrap_or_default();
+
+    match opt.output {
+        Output::Default => match season.as_ref() {
+            Some(e) => {
+                println!("{} (season {})", e.name, e.number);
+                println!("Start : {}", date_time_str);
+                println!("End   : {}", season_end_str);
+            }
+            None => println!("{}", date_time_str),
+        },
         Output::Tsv => {
             let mut name_values: Vec<(&str, String)> = Vec::new();
-            name_values.push(("date_time", date_time_str));
+            name_values.push(("date_time", date_time_str.clone()));
             name_values.push(("format", format!("{}", opt.time_format)));
             name_values.push(("moment", format!("{}", opt.moment)));
 
+            if let Some(e) = season.as_ref() {
+                name_values.push(("season_name", e.name.clone()));
+                name_values.push(("season_number", format!("{}", e.number)));
+                name_values.push(("season_start", date_time_str));
+                name_values.push(("season_end", season_end_str));
+            }
+
             print!("{}", build_tsv(name_values));
         }
     }